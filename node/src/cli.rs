@@ -5,6 +5,11 @@ pub struct Cli {
 
 	#[clap(flatten)]
 	pub run: sc_cli::RunCmd,
+
+	/// Insert the well-known `//Alice` key into the keystore as the oracle feeder key, so that the
+	/// offchain worker submits prices. Only accepted on development chains.
+	#[arg(long)]
+	pub dev_oracle_key: bool,
 }
 
 #[derive(Debug, clap::Subcommand)]
@@ -4,11 +4,12 @@ use futures::FutureExt;
 use sc_client_api::{Backend, BlockBackend};
 use sc_consensus_babe::{BabeBlockImport, BabeLink, BabeParams};
 use sc_consensus_grandpa::SharedVoterState;
-use sc_service::{error::Error as ServiceError, Configuration, TaskManager, WarpSyncConfig};
+use sc_service::{error::Error as ServiceError, ChainType, Configuration, TaskManager, WarpSyncConfig};
 use sc_telemetry::{Telemetry, TelemetryWorker};
 use sc_transaction_pool_api::OffchainTransactionPoolFactory;
 use orium_runtime::{self, apis::RuntimeApi, opaque::Block};
 use sp_consensus_babe::AuthorityPair as BabePair;
+use sp_keystore::Keystore;
 use std::{sync::Arc, time::Duration};
 
 pub(crate) type FullClient = sc_service::TFullClient<
@@ -125,11 +126,13 @@ pub fn new_partial(config: &Configuration) -> Result<Service, ServiceError> {
 	})
 }
 
-/// Builds a new service for a full client.
+/// Builds a new service for a full client. With `dev_oracle_key`, the `//Alice` key feeds the
+/// oracle, which is only accepted on development chains.
 pub fn new_full<
 	N: sc_network::NetworkBackend<Block, <Block as sp_runtime::traits::Block>::Hash>,
 >(
 	config: Configuration,
+	dev_oracle_key: bool,
 ) -> Result<TaskManager, ServiceError> {
 	let sc_service::PartialComponents {
 		client,
@@ -183,6 +186,20 @@ pub fn new_full<
 		})?;
 
 	if config.offchain_worker.enabled {
+		// Development chains may feed prices with the sudo key, which the oracle authorizes at
+		// genesis. The key is public, so it is only inserted when asked for.
+		if dev_oracle_key {
+			if config.chain_spec.chain_type() != ChainType::Development {
+				return Err(ServiceError::Other(
+					"--dev-oracle-key is only accepted on development chains".into(),
+				));
+			}
+			keystore_container
+				.keystore()
+				.sr25519_generate_new(pallet_oracle::KEY_TYPE, Some("//Alice"))
+				.map_err(|e| ServiceError::Other(format!("Failed to insert the oracle feeder key: {e}")))?;
+		}
+
 		let offchain_workers =
 			sc_offchain::OffchainWorkers::new(sc_offchain::OffchainWorkerOptions {
 				runtime_api_provider: client.clone(),
@@ -214,8 +231,12 @@ pub fn new_full<
 		let client = client.clone();
 		let pool = transaction_pool.clone();
 
-		Box::new(move |_| {
-			let deps = crate::rpc::FullDeps { client: client.clone(), pool: pool.clone() };
+		Box::new(move |subscription_executor| {
+			let deps = crate::rpc::FullDeps {
+				client: client.clone(),
+				pool: pool.clone(),
+				subscription_executor,
+			};
 			crate::rpc::create_full(deps).map_err(Into::into)
 		})
 	};
@@ -0,0 +1,199 @@
+//! RPC interface to the collateral engine pallet.
+//!
+//! Wallets and frontends read CDPs through these methods rather than repeating the ratio math
+//! of the pallet, so the numbers they show are the ones the chain enforces.
+
+use std::{collections::BTreeSet, marker::PhantomData, sync::Arc};
+
+use codec::Codec;
+use futures::{future, FutureExt, StreamExt};
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::error::{ErrorObject, ErrorObjectOwned},
+	PendingSubscriptionSink, SubscriptionMessage,
+};
+use sc_client_api::BlockchainEvents;
+use serde::{de::DeserializeOwned, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::traits::SpawnNamed;
+use sp_runtime::traits::Block as BlockT;
+
+pub use pallet_collateral_engine_runtime_api::{
+	CdpHealth, CdpId, CdpInfo, CollateralEngineApi as CollateralEngineRuntimeApi, LiquidatableCdp,
+	MAX_LIQUIDATABLE_SCAN,
+};
+
+/// CDP queries, answered at the best block unless `at` names another.
+#[rpc(client, server)]
+pub trait CollateralEngineApi<BlockHash, AccountId, Balance> {
+	/// The CDP `id`, with its debt including stability fees up to the block.
+	#[method(name = "collateral_getCdp")]
+	fn cdp(&self, id: CdpId, at: Option<BlockHash>) -> RpcResult<Option<CdpInfo<AccountId, Balance>>>;
+
+	/// The ids of the CDPs held by `owner`.
+	#[method(name = "collateral_getCdpsOf")]
+	fn cdps_of(&self, owner: AccountId, at: Option<BlockHash>) -> RpcResult<Vec<CdpId>>;
+
+	/// The collateral ratio, liquidation price and remaining mint and withdrawal capacity of the
+	/// CDP `id`.
+	#[method(name = "collateral_getHealth")]
+	fn health(&self, id: CdpId, at: Option<BlockHash>) -> RpcResult<CdpHealth<Balance>>;
+
+	/// The CDPs below their liquidation ratio among the `limit` ids from `start`, largest
+	/// shortfall first. At most `MAX_LIQUIDATABLE_SCAN` ids are checked per call.
+	#[method(name = "collateral_getLiquidatable")]
+	fn liquidatable(&self, start: CdpId, limit: u32, at: Option<BlockHash>) -> RpcResult<Vec<LiquidatableCdp>>;
+
+	/// Notifies the CDPs that have become liquidatable at each new best block.
+	#[subscription(
+		name = "collateral_subscribeLiquidatable" => "collateral_liquidatable",
+		unsubscribe = "collateral_unsubscribeLiquidatable",
+		item = Vec<LiquidatableCdp>
+	)]
+	fn subscribe_liquidatable(&self);
+}
+
+/// Error codes of the collateral engine RPC.
+pub enum Error {
+	/// The runtime API call failed.
+	RuntimeError,
+	/// The runtime rejected the query, for example for an unknown CDP.
+	QueryFailed,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::RuntimeError => 1,
+			Error::QueryFailed => 2,
+		}
+	}
+}
+
+/// Answers collateral engine RPC calls through the runtime API.
+pub struct CollateralEngine<C, Block> {
+	client: Arc<C>,
+	executor: Arc<dyn SpawnNamed>,
+	_marker: PhantomData<Block>,
+}
+
+impl<C, Block> CollateralEngine<C, Block> {
+	/// Serve CDP queries from `client`, running subscriptions on `executor`.
+	pub fn new(client: Arc<C>, executor: Arc<dyn SpawnNamed>) -> Self {
+		Self { client, executor, _marker: Default::default() }
+	}
+}
+
+/// All liquidatable CDPs at `at`, paged so that no runtime call checks more than
+/// `MAX_LIQUIDATABLE_SCAN` of them.
+fn liquidatable_at<C, Block, AccountId, Balance>(
+	client: &C,
+	at: Block::Hash,
+) -> Result<Vec<LiquidatableCdp>, sp_api::ApiError>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block>,
+	C::Api: CollateralEngineRuntimeApi<Block, AccountId, Balance>,
+	AccountId: Codec,
+	Balance: Codec,
+{
+	let api = client.runtime_api();
+	let end = api.next_cdp_id(at)?;
+	let mut cdps = Vec::new();
+	let mut start = 0;
+	while start < end {
+		cdps.extend(api.liquidatable_cdps(at, start, MAX_LIQUIDATABLE_SCAN)?);
+		start = start.saturating_add(MAX_LIQUIDATABLE_SCAN as CdpId);
+	}
+	cdps.sort_by(|a, b| b.shortfall.cmp(&a.shortfall).then(a.id.cmp(&b.id)));
+
+	Ok(cdps)
+}
+
+fn runtime_error(message: &'static str, e: impl std::fmt::Debug) -> ErrorObjectOwned {
+	ErrorObject::owned(Error::RuntimeError.into(), message, Some(format!("{:?}", e)))
+}
+
+impl<C, Block, AccountId, Balance> CollateralEngineApiServer<Block::Hash, AccountId, Balance>
+	for CollateralEngine<C, Block>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + BlockchainEvents<Block> + Send + Sync + 'static,
+	C::Api: CollateralEngineRuntimeApi<Block, AccountId, Balance>,
+	AccountId: Codec + Clone + Send + Sync + DeserializeOwned + Serialize + 'static,
+	Balance: Codec + Send + Sync + DeserializeOwned + Serialize + 'static,
+{
+	fn cdp(&self, id: CdpId, at: Option<Block::Hash>) -> RpcResult<Option<CdpInfo<AccountId, Balance>>> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		self.client.runtime_api().cdp(at, id).map_err(|e| runtime_error("Unable to query CDP.", e))
+	}
+
+	fn cdps_of(&self, owner: AccountId, at: Option<Block::Hash>) -> RpcResult<Vec<CdpId>> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		self.client
+			.runtime_api()
+			.cdps_of(at, owner)
+			.map_err(|e| runtime_error("Unable to query CDPs of owner.", e))
+	}
+
+	fn health(&self, id: CdpId, at: Option<Block::Hash>) -> RpcResult<CdpHealth<Balance>> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		self.client
+			.runtime_api()
+			.health(at, id)
+			.map_err(|e| runtime_error("Unable to query CDP health.", e))?
+			.map_err(|e| ErrorObject::owned(Error::QueryFailed.into(), "CDP health not available.", Some(format!("{:?}", e))))
+	}
+
+	fn liquidatable(&self, start: CdpId, limit: u32, at: Option<Block::Hash>) -> RpcResult<Vec<LiquidatableCdp>> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		self.client
+			.runtime_api()
+			.liquidatable_cdps(at, start, limit)
+			.map_err(|e| runtime_error("Unable to query liquidatable CDPs.", e))
+	}
+
+	fn subscribe_liquidatable(&self, pending: PendingSubscriptionSink) {
+		let client = self.client.clone();
+		let mut best_blocks = self
+			.client
+			.import_notification_stream()
+			.filter(|notification| future::ready(notification.is_new_best));
+
+		let task = async move {
+			let Ok(sink) = pending.accept().await else { return };
+
+			// Only CDPs that were not liquidatable at the previous best block are sent
+			let notify = async {
+				let mut known = BTreeSet::new();
+				while let Some(notification) = best_blocks.next().await {
+					let Ok(cdps) =
+						liquidatable_at::<C, Block, AccountId, Balance>(&*client, notification.hash)
+					else {
+						continue;
+					};
+					let fresh: Vec<_> = cdps.iter().filter(|cdp| !known.contains(&cdp.id)).cloned().collect();
+					known = cdps.into_iter().map(|cdp| cdp.id).collect();
+					if fresh.is_empty() {
+						continue;
+					}
+
+					let Ok(message) = SubscriptionMessage::from_json(&fresh) else { return };
+					if sink.send(message).await.is_err() {
+						return;
+					}
+				}
+			};
+			futures::pin_mut!(notify);
+			future::select(notify, sink.closed().boxed()).await;
+		};
+
+		self.executor.spawn("collateral-engine-liquidatable", Some("rpc"), task.boxed());
+	}
+}
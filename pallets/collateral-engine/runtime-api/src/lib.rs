@@ -0,0 +1,36 @@
+//! Runtime API of the collateral engine pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use codec::Codec;
+use sp_runtime::DispatchError;
+
+pub use pallet_collateral_engine::{CdpHealth, CdpId, CdpInfo, LiquidatableCdp, MAX_LIQUIDATABLE_SCAN};
+
+sp_api::decl_runtime_apis! {
+	/// Reports CDPs with the same math the collateral engine applies on chain.
+	pub trait CollateralEngineApi<AccountId, Balance>
+	where
+		AccountId: Codec,
+		Balance: Codec,
+	{
+		/// The CDP `id`, with its debt including stability fees up to the current block.
+		fn cdp(id: CdpId) -> Option<CdpInfo<AccountId, Balance>>;
+
+		/// The ids of the CDPs held by `owner`.
+		fn cdps_of(owner: AccountId) -> Vec<CdpId>;
+
+		/// The collateral ratio of the CDP `id` and how much it can still mint or withdraw.
+		fn health(id: CdpId) -> Result<CdpHealth<Balance>, DispatchError>;
+
+		/// The liquidatable CDPs among the `limit` ids from `start`, largest shortfall first. At
+		/// most `MAX_LIQUIDATABLE_SCAN` ids are checked per call.
+		fn liquidatable_cdps(start: CdpId, limit: u32) -> Vec<LiquidatableCdp>;
+
+		/// The id the next CDP opened will get, where paging through CDPs ends.
+		fn next_cdp_id() -> CdpId;
+	}
+}
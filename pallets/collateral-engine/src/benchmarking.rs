@@ -1,35 +1,490 @@
-//! Benchmarking setup for pallet-template
+//! Benchmarking setup for pallet-collateral-engine
 
 use super::*;
 
 #[allow(unused)]
-use crate::Pallet as Template;
+use crate::Pallet as CollateralEngine;
 use frame_benchmarking::v2::*;
+use frame_support::traits::Currency;
 use frame_system::RawOrigin;
+use sp_runtime::traits::{SaturatedConversion, Zero};
 
 #[benchmarks]
 mod benchmarks {
 	use super::*;
 
+	const NATIVE: CollateralId = 0;
+
+	/// `amount` whole units of ORM or stablecoin, which share 12 decimals.
+	fn units<T: Config>(amount: u128) -> T::Balance {
+		amount.saturating_mul(1_000_000_000_000).saturated_into()
+	}
+
+	/// Native ORM, minted against at 150% and liquidated below 130%.
+	fn collateral_type<T: Config>() -> CollateralType<T::Balance> {
+		CollateralType {
+			asset: CollateralAsset::Native,
+			price_feed: PriceFeed::Orm,
+			min_collateral_ratio: 15000,
+			liquidation_ratio: 13000,
+			stability_fee: 500,
+			debt_ceiling: units::<T>(1_000_000_000),
+			dust: Zero::zero(),
+		}
+	}
+
+	/// Registers native ORM as collateral type 0 and prices ORM at 1 USD.
+	fn setup<T: Config>() {
+		CollateralTypes::<T>::insert(NATIVE, collateral_type::<T>());
+		set_price::<T>(PRICE_PRECISION);
+	}
+
+	/// Registers `count` collateral types of native ORM in place of any registered before.
+	fn register_collateral_types<T: Config>(count: u32) {
+		let _ = CollateralTypes::<T>::clear(u32::MAX, None);
+		for id in 0..count {
+			CollateralTypes::<T>::insert(id, collateral_type::<T>());
+		}
+	}
+
+	/// Prices ORM at `usd` in USD and at 90% of that in EUR.
+	fn set_price<T: Config>(usd: u128) {
+		T::Oracle::set_price(AssetPair::OrmUsd, usd);
+		T::Oracle::set_price(AssetPair::OrmEur, usd / 10 * 9);
+	}
+
+	/// Gives `who` enough ORM to open any CDP used here.
+	fn fund<T: Config>(who: &T::AccountId) {
+		let _ = T::Currency::make_free_balance_be(who, units::<T>(10_000));
+	}
+
+	/// Gives `who` enough dUSD and dEUR to cover the debt of any CDP opened here.
+	fn fund_stablecoin<T: Config>(who: &T::AccountId) {
+		T::DusdMinter::mint(Pallet::<T>::issuer(), who, units::<T>(1_000)).unwrap();
+		T::DeurMinter::mint(Pallet::<T>::issuer(), who, units::<T>(1_000)).unwrap();
+	}
+
+	/// Opens a CDP for `owner` with 1_000 ORM of collateral and no debt.
+	fn open_cdp<T: Config>(owner: &T::AccountId) -> CdpId {
+		fund::<T>(owner);
+		let cdp_id = NextCdpId::<T>::get();
+		Pallet::<T>::create_cdp(RawOrigin::Signed(owner.clone()).into(), NATIVE, units::<T>(1_000))
+			.unwrap();
+
+		cdp_id
+	}
+
+	/// Opens a CDP for `owner` with 1_000 ORM of collateral, 300 dUSD and 200 dEUR of debt, at
+	/// a collateral ratio of 190%.
+	fn open_cdp_with_debt<T: Config>(owner: &T::AccountId) -> CdpId {
+		let cdp_id = open_cdp::<T>(owner);
+		Pallet::<T>::mint_dusd(RawOrigin::Signed(owner.clone()).into(), cdp_id, units::<T>(300))
+			.unwrap();
+		Pallet::<T>::mint_deur(RawOrigin::Signed(owner.clone()).into(), cdp_id, units::<T>(200))
+			.unwrap();
+
+		cdp_id
+	}
+
+	/// Opens a CDP for another account and drops the ORM price until it is liquidatable at a
+	/// collateral ratio of 115%.
+	fn unsafe_cdp<T: Config>() -> CdpId {
+		let owner: T::AccountId = account("owner", 0, 0);
+		let cdp_id = open_cdp_with_debt::<T>(&owner);
+		set_price::<T>(PRICE_PRECISION / 10 * 6);
+
+		cdp_id
+	}
+
+	/// The origin that manages collateral types.
+	fn admin_origin<T: Config>() -> Result<T::RuntimeOrigin, BenchmarkError> {
+		T::AdminOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)
+	}
+
+	/// Triggers emergency shutdown at the current prices.
+	fn shut_down<T: Config>() -> Result<(), BenchmarkError> {
+		let origin =
+			T::ShutdownOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		Pallet::<T>::cage(origin)?;
+
+		Ok(())
+	}
+
+	#[benchmark]
+	fn create_cdp() {
+		setup::<T>();
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let cdp_id = NextCdpId::<T>::get();
+		#[extrinsic_call]
+		create_cdp(RawOrigin::Signed(caller.clone()), NATIVE, units::<T>(1_000));
+
+		assert_eq!(CdpOwner::<T>::get(cdp_id), Some(caller));
+	}
+
+	#[benchmark]
+	fn deposit_collateral() {
+		setup::<T>();
+		let owner: T::AccountId = account("owner", 0, 0);
+		let cdp_id = open_cdp::<T>(&owner);
+		// A manager is the worst case, as access is checked against the owner first
+		let caller: T::AccountId = whitelisted_caller();
+		let origin = RawOrigin::Signed(owner).into();
+		Pallet::<T>::allow_cdp_manager(origin, cdp_id, caller.clone(), true).unwrap();
+		#[extrinsic_call]
+		deposit_collateral(RawOrigin::Signed(caller), cdp_id, units::<T>(500));
+
+		assert_eq!(Cdps::<T>::get(cdp_id).unwrap().collateral, units::<T>(1_500));
+	}
+
+	#[benchmark]
+	fn withdraw_collateral() {
+		setup::<T>();
+		let caller: T::AccountId = whitelisted_caller();
+		let cdp_id = open_cdp_with_debt::<T>(&caller);
+		#[extrinsic_call]
+		withdraw_collateral(RawOrigin::Signed(caller), cdp_id, units::<T>(100));
+
+		assert_eq!(Cdps::<T>::get(cdp_id).unwrap().collateral, units::<T>(900));
+	}
+
+	#[benchmark]
+	fn mint_dusd() {
+		setup::<T>();
+		GlobalDebtCeilings::<T>::insert(DebtType::Dusd, units::<T>(1_000_000));
+		let caller: T::AccountId = whitelisted_caller();
+		let cdp_id = open_cdp::<T>(&caller);
+		#[extrinsic_call]
+		mint_dusd(RawOrigin::Signed(caller.clone()), cdp_id, units::<T>(300));
+
+		assert_eq!(T::DusdMinter::balance(&caller), units::<T>(300));
+	}
+
+	#[benchmark]
+	fn mint_deur() {
+		setup::<T>();
+		GlobalDebtCeilings::<T>::insert(DebtType::Deur, units::<T>(1_000_000));
+		let caller: T::AccountId = whitelisted_caller();
+		let cdp_id = open_cdp::<T>(&caller);
+		#[extrinsic_call]
+		mint_deur(RawOrigin::Signed(caller.clone()), cdp_id, units::<T>(200));
+
+		assert_eq!(T::DeurMinter::balance(&caller), units::<T>(200));
+	}
+
+	#[benchmark]
+	fn repay_dusd() {
+		setup::<T>();
+		let caller: T::AccountId = whitelisted_caller();
+		let cdp_id = open_cdp_with_debt::<T>(&caller);
+		// A partial repayment also checks the debt left against the dust limit
+		#[extrinsic_call]
+		repay_dusd(RawOrigin::Signed(caller.clone()), cdp_id, units::<T>(100));
+
+		assert_eq!(T::DusdMinter::balance(&caller), units::<T>(200));
+	}
+
+	#[benchmark]
+	fn repay_deur() {
+		setup::<T>();
+		let caller: T::AccountId = whitelisted_caller();
+		let cdp_id = open_cdp_with_debt::<T>(&caller);
+		#[extrinsic_call]
+		repay_deur(RawOrigin::Signed(caller.clone()), cdp_id, units::<T>(100));
+
+		assert_eq!(T::DeurMinter::balance(&caller), units::<T>(100));
+	}
+
+	#[benchmark]
+	fn close_cdp() {
+		setup::<T>();
+		let caller: T::AccountId = whitelisted_caller();
+		let cdp_id = open_cdp::<T>(&caller);
+		#[extrinsic_call]
+		close_cdp(RawOrigin::Signed(caller), cdp_id);
+
+		assert!(Cdps::<T>::get(cdp_id).is_none());
+	}
+
+	#[benchmark]
+	fn liquidate() {
+		setup::<T>();
+		let cdp_id = unsafe_cdp::<T>();
+		let caller: T::AccountId = whitelisted_caller();
+		fund_stablecoin::<T>(&caller);
+		#[extrinsic_call]
+		liquidate(RawOrigin::Signed(caller), cdp_id);
+
+		assert!(Cdps::<T>::get(cdp_id).is_none());
+	}
+
+	#[benchmark]
+	fn start_auction() {
+		setup::<T>();
+		let cdp_id = unsafe_cdp::<T>();
+		let caller: T::AccountId = whitelisted_caller();
+		// Debt in both stablecoins opens two auctions
+		#[extrinsic_call]
+		start_auction(RawOrigin::Signed(caller), cdp_id);
+
+		assert_eq!(Auctions::<T>::iter_keys().count(), 2);
+	}
+
+	#[benchmark]
+	fn take() {
+		setup::<T>();
+		let cdp_id = unsafe_cdp::<T>();
+		let caller: T::AccountId = whitelisted_caller();
+		fund_stablecoin::<T>(&caller);
+		let auction_id = NextAuctionId::<T>::get();
+		Pallet::<T>::start_auction(RawOrigin::Signed(caller.clone()).into(), cdp_id).unwrap();
+		// Raising the full tab also finishes the auction
+		#[extrinsic_call]
+		take(RawOrigin::Signed(caller), auction_id, units::<T>(1_000), u128::MAX);
+
+		assert!(Auctions::<T>::get(auction_id).is_none());
+	}
+
+	#[benchmark]
+	fn reset_auction() {
+		setup::<T>();
+		let cdp_id = unsafe_cdp::<T>();
+		let caller: T::AccountId = whitelisted_caller();
+		let auction_id = NextAuctionId::<T>::get();
+		Pallet::<T>::start_auction(RawOrigin::Signed(caller.clone()).into(), cdp_id).unwrap();
+		let now: u32 = frame_system::Pallet::<T>::block_number().saturated_into();
+		let stale = now.saturating_add(T::AuctionResetDuration::get()).saturating_add(1);
+		frame_system::Pallet::<T>::set_block_number(stale.into());
+		#[extrinsic_call]
+		reset_auction(RawOrigin::Signed(caller), auction_id);
+
+		assert_eq!(Auctions::<T>::get(auction_id).unwrap().started_at, stale);
+	}
+
+	#[benchmark]
+	fn add_collateral_type() -> Result<(), BenchmarkError> {
+		// Every other collateral type is registered, so the limit check reads them all
+		let id = T::MaxCollateralTypes::get().saturating_sub(1);
+		register_collateral_types::<T>(id);
+		let origin = admin_origin::<T>()?;
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, id, collateral_type::<T>());
+
+		assert!(CollateralTypes::<T>::contains_key(id));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn update_collateral_type() -> Result<(), BenchmarkError> {
+		setup::<T>();
+		// A new debt ceiling and dust limit emit every event
+		let updated = CollateralType {
+			debt_ceiling: units::<T>(1_000),
+			dust: units::<T>(10),
+			..collateral_type::<T>()
+		};
+		let origin = admin_origin::<T>()?;
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, NATIVE, updated.clone());
+
+		assert_eq!(CollateralTypes::<T>::get(NATIVE), Some(updated));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn transfer_cdp_ownership() {
+		setup::<T>();
+		let caller: T::AccountId = whitelisted_caller();
+		let cdp_id = open_cdp_with_debt::<T>(&caller);
+		// The managers of the previous owner are removed
+		let manager: T::AccountId = account("manager", 0, 0);
+		let origin = RawOrigin::Signed(caller.clone()).into();
+		Pallet::<T>::allow_cdp_manager(origin, cdp_id, manager, true).unwrap();
+		let new_owner: T::AccountId = account("new_owner", 0, 0);
+		fund::<T>(&new_owner);
+		#[extrinsic_call]
+		transfer_cdp_ownership(RawOrigin::Signed(caller), cdp_id, new_owner.clone());
+
+		assert_eq!(CdpOwner::<T>::get(cdp_id), Some(new_owner));
+	}
+
+	#[benchmark]
+	fn allow_cdp_manager() {
+		setup::<T>();
+		let caller: T::AccountId = whitelisted_caller();
+		let cdp_id = open_cdp::<T>(&caller);
+		let manager: T::AccountId = account("manager", 0, 0);
+		#[extrinsic_call]
+		allow_cdp_manager(RawOrigin::Signed(caller), cdp_id, manager.clone(), true);
+
+		assert!(CdpManagers::<T>::contains_key(cdp_id, manager));
+	}
+
+	#[benchmark]
+	fn set_global_debt_ceiling() -> Result<(), BenchmarkError> {
+		let origin = admin_origin::<T>()?;
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, DebtType::Dusd, Some(units::<T>(1_000_000)));
+
+		assert_eq!(GlobalDebtCeilings::<T>::get(DebtType::Dusd), Some(units::<T>(1_000_000)));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn set_collateral_debt_ceiling() -> Result<(), BenchmarkError> {
+		setup::<T>();
+		let origin = admin_origin::<T>()?;
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, NATIVE, units::<T>(1_000_000));
+
+		assert_eq!(CollateralTypes::<T>::get(NATIVE).unwrap().debt_ceiling, units::<T>(1_000_000));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn set_dust() -> Result<(), BenchmarkError> {
+		setup::<T>();
+		let origin = admin_origin::<T>()?;
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, NATIVE, units::<T>(10));
+
+		assert_eq!(CollateralTypes::<T>::get(NATIVE).unwrap().dust, units::<T>(10));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn set_twap_window() -> Result<(), BenchmarkError> {
+		setup::<T>();
+		let origin = admin_origin::<T>()?;
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, NATIVE, Some(3_600_000));
+
+		assert_eq!(TwapWindows::<T>::get(NATIVE), Some(3_600_000));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn liquidate_unsigned() {
+		setup::<T>();
+		let cdp_id = unsafe_cdp::<T>();
+		#[extrinsic_call]
+		liquidate_unsigned(RawOrigin::None, cdp_id);
+
+		assert_eq!(Auctions::<T>::iter_keys().count(), 2);
+	}
+
+	#[benchmark]
+	fn liquidate_partial() {
+		setup::<T>();
+		let cdp_id = unsafe_cdp::<T>();
+		let caller: T::AccountId = whitelisted_caller();
+		fund_stablecoin::<T>(&caller);
+		#[extrinsic_call]
+		liquidate_partial(RawOrigin::Signed(caller), cdp_id);
+
+		assert!(Cdps::<T>::get(cdp_id).unwrap().collateral < units::<T>(1_000));
+	}
+
+	#[benchmark]
+	fn accrue_stability_fees(c: Linear<1, { T::MaxCollateralTypes::get() }>) {
+		// Every collateral type carries debt in both stablecoins, so every rate is compounded
+		register_collateral_types::<T>(c);
+		for id in 0..c {
+			NormalizedDebt::<T>::insert(id, DebtType::Dusd, units::<T>(1_000));
+			NormalizedDebt::<T>::insert(id, DebtType::Deur, units::<T>(1_000));
+		}
+		RateUpdatedAt::<T>::put(0);
+		frame_system::Pallet::<T>::set_block_number(100u32.into());
+		#[block]
+		{
+			Pallet::<T>::accrue_stability_fees().unwrap();
+		}
+
+		assert_eq!(RateUpdatedAt::<T>::get(), Some(100));
+	}
+
+	#[benchmark]
+	fn cage(c: Linear<1, { T::MaxCollateralTypes::get() }>) -> Result<(), BenchmarkError> {
+		setup::<T>();
+		register_collateral_types::<T>(c);
+		let origin =
+			T::ShutdownOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin);
+
+		assert!(Pallet::<T>::is_caged());
+		Ok(())
+	}
+
+	#[benchmark]
+	fn skip_auction() -> Result<(), BenchmarkError> {
+		setup::<T>();
+		let cdp_id = unsafe_cdp::<T>();
+		let caller: T::AccountId = whitelisted_caller();
+		let auction_id = NextAuctionId::<T>::get();
+		Pallet::<T>::start_auction(RawOrigin::Signed(caller.clone()).into(), cdp_id)?;
+		shut_down::<T>()?;
+		#[extrinsic_call]
+		skip_auction(RawOrigin::Signed(caller), auction_id);
+
+		assert!(Auctions::<T>::get(auction_id).is_none());
+		Ok(())
+	}
+
+	#[benchmark]
+	fn settle_cdp() -> Result<(), BenchmarkError> {
+		setup::<T>();
+		let caller: T::AccountId = whitelisted_caller();
+		let cdp_id = open_cdp_with_debt::<T>(&caller);
+		shut_down::<T>()?;
+		#[extrinsic_call]
+		settle_cdp(RawOrigin::Signed(caller), cdp_id);
+
+		assert!(Cdps::<T>::get(cdp_id).unwrap().dusd_debt.is_zero());
+		Ok(())
+	}
+
 	#[benchmark]
-	fn do_something() {
-		let value = 100u32;
+	fn fix_redemption_rates(
+		c: Linear<1, { T::MaxCollateralTypes::get() }>,
+	) -> Result<(), BenchmarkError> {
+		setup::<T>();
+		register_collateral_types::<T>(c);
+		shut_down::<T>()?;
+		// Every collateral type backs both stablecoins
+		for id in 0..c {
+			SettledCollateral::<T>::insert(DebtType::Dusd, id, units::<T>(1_000));
+			SettledCollateral::<T>::insert(DebtType::Deur, id, units::<T>(1_000));
+		}
+		TotalDusdDebt::<T>::put(units::<T>(1_000));
+		TotalDeurDebt::<T>::put(units::<T>(1_000));
 		let caller: T::AccountId = whitelisted_caller();
 		#[extrinsic_call]
-		do_something(RawOrigin::Signed(caller), value);
+		fix_redemption_rates(RawOrigin::Signed(caller));
 
-		assert_eq!(Something::<T>::get(), Some(value));
+		assert_eq!(RedemptionRates::<T>::iter_prefix(DebtType::Dusd).count() as u32, c);
+		Ok(())
 	}
 
 	#[benchmark]
-	fn cause_error() {
-		Something::<T>::put(100u32);
+	fn redeem(c: Linear<1, { T::MaxCollateralTypes::get() }>) -> Result<(), BenchmarkError> {
+		setup::<T>();
+		register_collateral_types::<T>(c);
+		shut_down::<T>()?;
+		// Every collateral type pays out a tenth of an ORM per dUSD
+		fund::<T>(&Pallet::<T>::vault_account());
+		for id in 0..c {
+			SettledCollateral::<T>::insert(DebtType::Dusd, id, units::<T>(1_000));
+			RedemptionRates::<T>::insert(DebtType::Dusd, id, PRICE_PRECISION / 10);
+		}
 		let caller: T::AccountId = whitelisted_caller();
+		fund_stablecoin::<T>(&caller);
 		#[extrinsic_call]
-		cause_error(RawOrigin::Signed(caller));
+		redeem(RawOrigin::Signed(caller.clone()), DebtType::Dusd, units::<T>(100));
 
-		assert_eq!(Something::<T>::get(), Some(101u32));
+		assert_eq!(T::DusdMinter::balance(&caller), units::<T>(900));
+		Ok(())
 	}
 
-	impl_benchmark_test_suite!(Template, crate::mock::new_test_ext(), crate::mock::Test);
+	impl_benchmark_test_suite!(CollateralEngine, crate::mock::new_test_ext(), crate::mock::Test);
 }
@@ -49,6 +49,156 @@ mod benchmarking;
 pub mod weights;
 pub use weights::*;
 
+// Storage migrations between versions of this pallet.
+pub mod migrations;
+
+use frame_support::{
+	dispatch::DispatchResult,
+	traits::{EnsureOrigin, Get},
+	PalletId,
+};
+use pallet_oracle::{AssetPair, OnPriceUpdate, PriceProvider};
+use sp_runtime::{
+	helpers_128bit::multiply_by_rational_with_rounding,
+	traits::{AccountIdConversion, Saturating},
+	FixedPointNumber, FixedU128, Perquintill, Rounding,
+};
+
+/// Issues and retires the stablecoin backing a given debt type.
+///
+/// The collateral engine only keeps track of debt; the tokens themselves live in the
+/// stablecoin pallets. Implementations must keep the token supply in lockstep with the
+/// debt recorded here, and only act for an `issuer` the stablecoin accepts.
+pub trait StablecoinMinter<AccountId, Balance> {
+	/// Credit `amount` freshly issued tokens to `to`.
+	fn mint(issuer: RawOrigin<AccountId>, to: &AccountId, amount: Balance) -> DispatchResult;
+	/// Burn `amount` tokens held by `from`.
+	fn burn(issuer: RawOrigin<AccountId>, from: &AccountId, amount: Balance) -> DispatchResult;
+	/// Move `amount` tokens from `from` to `to` without changing the supply.
+	fn transfer(
+		issuer: RawOrigin<AccountId>,
+		from: &AccountId,
+		to: &AccountId,
+		amount: Balance,
+	) -> DispatchResult;
+	/// The spendable balance of `who`.
+	fn balance(who: &AccountId) -> Balance;
+	/// The total amount of tokens in circulation.
+	fn total_issuance() -> Balance;
+}
+
+/// Mints, burns and moves tokens with the issuer's own origin, so the stablecoin instance must
+/// accept an [`EnsureIssuer`] for every module issuing through the engine as its `IssuerOrigin`.
+impl<T, I> StablecoinMinter<T::AccountId, <T as pallet_stablecoin::Config<I>>::Balance>
+	for pallet_stablecoin::Pallet<T, I>
+where
+	T: Config + pallet_stablecoin::Config<I>,
+	I: 'static,
+	<T as frame_system::Config>::RuntimeOrigin: From<Origin<T>>,
+{
+	fn mint(
+		issuer: Origin<T>,
+		to: &T::AccountId,
+		amount: <T as pallet_stablecoin::Config<I>>::Balance,
+	) -> DispatchResult {
+		Self::mint_to(issuer.into(), to, amount)
+	}
+
+	fn burn(
+		issuer: Origin<T>,
+		from: &T::AccountId,
+		amount: <T as pallet_stablecoin::Config<I>>::Balance,
+	) -> DispatchResult {
+		Self::burn_from(issuer.into(), from, amount)
+	}
+
+	fn transfer(
+		issuer: Origin<T>,
+		from: &T::AccountId,
+		to: &T::AccountId,
+		amount: <T as pallet_stablecoin::Config<I>>::Balance,
+	) -> DispatchResult {
+		Self::transfer_between(issuer.into(), from, to, amount)
+	}
+
+	fn balance(who: &T::AccountId) -> <T as pallet_stablecoin::Config<I>>::Balance {
+		Self::balance_of(who)
+	}
+
+	fn total_issuance() -> <T as pallet_stablecoin::Config<I>>::Balance {
+		Self::total_supply()
+	}
+}
+
+/// Moves a fungible token used as CDP collateral.
+pub trait CollateralToken<AccountId, Balance> {
+	/// Move `amount` tokens from `from` to `to`.
+	fn transfer(from: &AccountId, to: &AccountId, amount: Balance) -> DispatchResult;
+	/// The balance of `who`.
+	fn balance(who: &AccountId) -> Balance;
+}
+
+impl<T: pallet_orium_token::Config> CollateralToken<T::AccountId, T::Balance> for pallet_orium_token::Pallet<T> {
+	fn transfer(from: &T::AccountId, to: &T::AccountId, amount: T::Balance) -> DispatchResult {
+		Self::transfer_balance(from, to, amount)
+	}
+
+	fn balance(who: &T::AccountId) -> T::Balance {
+		Self::balance_of(who)
+	}
+}
+
+/// Records the income and losses of the stablecoin system.
+pub trait SystemLedger<Balance> {
+	/// Stablecoin of `currency` was paid into the surplus account as fees or penalties.
+	fn on_surplus(currency: DebtType, amount: Balance);
+	/// Debt of `currency` was left uncovered after its collateral was sold, or stablecoin was
+	/// issued without collateral behind it.
+	fn on_bad_debt(currency: DebtType, amount: Balance);
+}
+
+impl<Balance> SystemLedger<Balance> for () {
+	fn on_surplus(_currency: DebtType, _amount: Balance) {}
+	fn on_bad_debt(_currency: DebtType, _amount: Balance) {}
+}
+
+/// The descending price schedule of a collateral auction.
+pub trait AuctionPriceCurve {
+	/// The price `elapsed` blocks into an auction that started at `top`.
+	fn price(top: u128, elapsed: u32) -> u128;
+}
+
+/// Decreases the price linearly from `top` to zero over `Duration` blocks.
+pub struct LinearDecrease<Duration>(core::marker::PhantomData<Duration>);
+
+impl<Duration: Get<u32>> AuctionPriceCurve for LinearDecrease<Duration> {
+	fn price(top: u128, elapsed: u32) -> u128 {
+		let duration = Duration::get();
+		if elapsed >= duration {
+			return 0;
+		}
+		multiply_by_rational_with_rounding(
+			top,
+			duration.saturating_sub(elapsed) as u128,
+			duration as u128,
+			Rounding::Down,
+		)
+		.unwrap_or(0)
+	}
+}
+
+/// Multiplies the price by `Cut` (in basis points) every `Step` blocks.
+pub struct StairstepExponentialDecrease<Step, Cut>(core::marker::PhantomData<(Step, Cut)>);
+
+impl<Step: Get<u32>, Cut: Get<u32>> AuctionPriceCurve for StairstepExponentialDecrease<Step, Cut> {
+	fn price(top: u128, elapsed: u32) -> u128 {
+		let steps = elapsed.checked_div(Step::get()).unwrap_or(0);
+		FixedU128::saturating_from_rational(Cut::get(), 10000u32)
+			.saturating_pow(steps as usize)
+			.saturating_mul_int(top)
+	}
+}
+
 // All pallet logic is defined in its own module and must be annotated by the `pallet` attribute.
 #[frame_support::pallet]
 pub mod pallet {
@@ -56,292 +206,2912 @@ pub mod pallet {
 	use super::*;
 	use frame_support::{
 		pallet_prelude::*,
-		traits::{Currency, Get, ReservableCurrency},
+		storage::with_storage_layer,
+		traits::{BalanceStatus, Currency, ExistenceRequirement, Get, ReservableCurrency, UnixTime},
+		weights::WeightMeter,
+		PalletId,
+	};
+	use frame_system::{
+		offchain::{CreateBare, SubmitTransaction},
+		pallet_prelude::*,
+	};
+	use sp_runtime::offchain::storage::StorageValueRef;
+	use sp_runtime::traits::{
+		AccountIdConversion, AtLeast32BitUnsigned, MaybeSerializeDeserialize, Member, One,
+		SaturatedConversion, Zero,
 	};
-	use frame_system::pallet_prelude::*;
-	use sp_runtime::traits::{AtLeast32BitUnsigned, MaybeSerializeDeserialize, Member, Saturating, Zero};
+	use serde::{Deserialize, Serialize};
 	use sp_std::{fmt::Debug, vec::Vec};
 
+	/// Prices are quoted with 18 decimals, the precision of `FixedU128`.
+	///
+	/// Collateral and stablecoin balances share the 12 decimals of the native token, so a price
+	/// converts an amount of one into the other without rescaling.
+	pub const PRICE_PRECISION: u128 = FixedU128::DIV;
+
+	/// The most CDP ids one `liquidatable_cdps` query checks, so that it stays cheap however
+	/// many CDPs are open.
+	pub const MAX_LIQUIDATABLE_SCAN: u32 = 1_000;
+
+	/// The offchain storage key of the next CDP id the offchain worker checks for liquidation.
+	const OFFCHAIN_SCAN_CURSOR: &[u8] = b"collateral-engine::liquidation-scan-cursor";
+
+	/// The in-code storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	// The `Pallet` struct serves as a placeholder to implement traits, methods and dispatchables
 	// (`Call`s) in this pallet.
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	/// The pallet's configuration trait.
 	#[pallet::config]
-	pub trait Config: frame_system::Config {
+	pub trait Config: CreateBare<Call<Self>> + frame_system::Config {
 		/// The overarching runtime event type.
 		#[allow(deprecated)]
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 		/// A type representing the weights required by the dispatchables of this pallet.
 		type WeightInfo: WeightInfo;
 		type Balance: Parameter + Member + AtLeast32BitUnsigned + Default + Copy + MaybeSerializeDeserialize + MaxEncodedLen;
-		type Currency: Currency<Self::AccountId, Balance = Self::Balance>;
-		type MinCollateralRatio: Get<u32>;
-		type LiquidationRatio: Get<u32>;
-		type StabilityFee: Get<u32>;
+		/// Holds collateral of the `CollateralAsset::Native` type.
+		type Currency: ReservableCurrency<Self::AccountId, Balance = Self::Balance>;
+		/// Holds collateral of the `CollateralAsset::OriumToken` type.
+		type OriumToken: CollateralToken<Self::AccountId, Self::Balance>;
+		/// The prices collateral is valued at.
+		type Oracle: PriceProvider;
+		/// The current time, used to tell whether oracle prices are fresh.
+		type UnixTime: UnixTime;
+		/// The age, in milliseconds, beyond which oracle prices may no longer back new debt or
+		/// collateral withdrawals.
+		#[pallet::constant]
+		type MaxPriceAge: Get<u64>;
+		/// The number of blocks over which the stability fee of a collateral type is charged once.
+		type BlocksPerYear: Get<u32>;
+		/// The pallet id, used to derive the system surplus account that receives fees.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+		/// How the price of a collateral auction falls over time.
+		type AuctionCurve: AuctionPriceCurve;
+		/// Starting auction price relative to the oracle price, in basis points.
+		type AuctionStartBuffer: Get<u32>;
+		/// The number of blocks after which an auction must be reset.
+		type AuctionResetDuration: Get<u32>;
+		/// The fraction of its starting price, in basis points, below which an auction must be
+		/// reset.
+		type AuctionResetRatio: Get<u32>;
+		/// Keeps the books on system surplus and bad debt.
+		type Ledger: SystemLedger<Self::Balance>;
+		/// Collateral paid to the liquidator on top of the covered debt, in basis points.
+		type LiquidationPenalty: Get<u32>;
+		/// The largest share of a CDP's debt, in basis points, one partial liquidation may close.
+		#[pallet::constant]
+		type CloseFactor: Get<u32>;
+		/// Issues dUSD against CDP debt.
+		type DusdMinter: StablecoinMinter<Self::AccountId, Self::Balance>;
+		/// Issues dEUR against CDP debt.
+		type DeurMinter: StablecoinMinter<Self::AccountId, Self::Balance>;
+		/// The origin that can trigger emergency shutdown.
+		type ShutdownOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		/// The origin that registers collateral types and updates their risk parameters.
+		type AdminOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		/// The most collateral types that can be registered, which bounds the work of charging
+		/// stability fees.
+		#[pallet::constant]
+		type MaxCollateralTypes: Get<u32>;
+		/// The most CDPs checked for liquidation per block in `on_idle` after a price update.
+		#[pallet::constant]
+		type LiquidationScanBatch: Get<u32>;
+		/// The most auctions started per block, by `on_idle` or by the offchain worker.
+		#[pallet::constant]
+		type MaxAutoLiquidations: Get<u32>;
+		/// The priority of the unsigned liquidations submitted by the offchain worker.
+		#[pallet::constant]
+		type UnsignedPriority: Get<TransactionPriority>;
+	}
+
+	pub type CollateralId = u32;
+
+	pub type CdpId = u64;
+
+	/// The asset a collateral type is denominated in, and how it is held.
+	#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen, Serialize, Deserialize)]
+	pub enum CollateralAsset {
+		/// Native ORM, reserved on the CDP owner's account.
+		Native,
+		/// ORM issued by `pallet_orium_token`, moved into the vault account.
+		OriumToken,
 	}
 
+	/// The prices a collateral type is valued at.
+	#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen, Serialize, Deserialize)]
+	pub enum PriceFeed {
+		/// The ORM/USD and ORM/EUR prices aggregated by the oracle.
+		Orm,
+	}
+
+	/// Which oracle price a valuation uses.
+	#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+	pub enum PriceSource {
+		/// The latest aggregated price, which minting and withdrawals are checked against.
+		Spot,
+		/// The price released by the oracle security module, which liquidations use so that a
+		/// manipulated update cannot trigger them before it can be reviewed.
+		Delayed,
+		/// The time-weighted average over the given number of milliseconds, falling back to the
+		/// delayed price while the oracle holds too little history to cover the window.
+		Twap(u64),
+	}
+
+	/// A registered kind of collateral and its risk parameters.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen, Serialize, Deserialize)]
+	pub struct CollateralType<Balance> {
+		pub asset: CollateralAsset,
+		pub price_feed: PriceFeed,
+		/// Collateral ratio required to mint and withdraw, in basis points.
+		pub min_collateral_ratio: u32,
+		/// Collateral ratio below which CDPs can be liquidated, in basis points.
+		pub liquidation_ratio: u32,
+		/// Annual stability fee charged on outstanding debt, in basis points.
+		pub stability_fee: u32,
+		/// The most debt, valued in USD, that may be drawn against this collateral type.
+		pub debt_ceiling: Balance,
+		/// The least debt, valued in USD, that a CDP of this collateral type may carry.
+		pub dust: Balance,
+	}
+
+	/// A collateralised debt position.
+	///
+	/// Debt is stored normalized against the rate accumulators of its collateral type: the
+	/// amount owed is `dusd_debt * Rates[collateral_type, Dusd]` and likewise for dEUR.
 	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
 	pub struct Cdp<Balance> {
+		pub collateral_type: CollateralId,
 		pub collateral: Balance,
 		pub dusd_debt: Balance,
 		pub deur_debt: Balance,
 		pub last_update: u32,
 	}
 
+	/// A CDP as reported to clients, with its debt at the current rates.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, Serialize, Deserialize)]
+	pub struct CdpInfo<AccountId, Balance> {
+		pub owner: AccountId,
+		pub collateral_type: CollateralId,
+		pub collateral: Balance,
+		/// dUSD owed, including stability fees up to the current block.
+		pub dusd_debt: Balance,
+		/// dEUR owed, including stability fees up to the current block.
+		pub deur_debt: Balance,
+	}
+
+	/// How far a CDP is from its limits at the current prices.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, Serialize, Deserialize)]
+	pub struct CdpHealth<Balance> {
+		/// Collateral ratio at the spot price in basis points, or `None` without debt.
+		pub collateral_ratio: Option<u128>,
+		/// Whether the CDP can be liquidated at the price its liquidations are checked against.
+		pub liquidatable: bool,
+		/// The USD price of collateral, with 18 decimals, below which the CDP can be liquidated,
+		/// assuming the EUR/USD rate holds. `None` without debt.
+		pub liquidation_price: Option<u128>,
+		/// dUSD that can still be minted, within the minimum collateral ratio and debt ceilings.
+		pub max_mint_dusd: Balance,
+		/// dEUR that can still be minted, within the minimum collateral ratio and debt ceilings.
+		pub max_mint_deur: Balance,
+		/// Collateral that can be withdrawn without falling below the minimum collateral ratio.
+		pub max_withdraw: Balance,
+		/// Stability fees accrued on the dUSD debt since rates were last compounded.
+		pub dusd_fees: Balance,
+		/// Stability fees accrued on the dEUR debt since rates were last compounded.
+		pub deur_fees: Balance,
+	}
+
+	/// A CDP that has fallen below the liquidation ratio of its collateral type.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, Serialize, Deserialize)]
+	pub struct LiquidatableCdp {
+		pub id: CdpId,
+		/// Collateral ratio at the price liquidations are checked against, in basis points.
+		pub collateral_ratio: u128,
+		/// The USD value of collateral missing to bring the CDP back to the liquidation ratio.
+		pub shortfall: u128,
+	}
+
+	/// The stablecoins that can be issued against collateral.
+	#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub enum DebtType {
+		Dusd,
+		Deur,
+	}
+
+	/// The origin of a module issuing stablecoin through the engine, named by its pallet account.
+	///
+	/// Only the modules themselves create it, so no extrinsic can mint, burn or move stablecoin
+	/// as one of them.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub enum RawOrigin<AccountId> {
+		Issuer(AccountId),
+	}
+
+	#[pallet::origin]
+	pub type Origin<T> = RawOrigin<<T as frame_system::Config>::AccountId>;
+
+	pub type AuctionId = u32;
+
+	/// A descending price auction selling seized collateral for stablecoin.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct CollateralAuction<AccountId, Balance> {
+		/// The owner of the liquidated CDP, who receives any collateral left over.
+		pub owner: AccountId,
+		/// The collateral type being sold.
+		pub collateral_type: CollateralId,
+		/// The stablecoin bids are paid in.
+		pub currency: DebtType,
+		/// Collateral still for sale.
+		pub lot: Balance,
+		/// Stablecoin still to raise, including the liquidation penalty.
+		pub tab: Balance,
+		/// The part of `tab` that repays CDP debt; the rest is penalty.
+		pub debt: Balance,
+		/// Starting price in stablecoin per unit of collateral, with 18 decimals.
+		pub top: u128,
+		/// The block the auction was started or last reset.
+		pub started_at: u32,
+	}
+
+	/// The registered collateral types.
+	#[pallet::storage]
+	pub type CollateralTypes<T: Config> =
+		StorageMap<_, Twox64Concat, CollateralId, CollateralType<T::Balance>, OptionQuery>;
+
+	/// CDPs by id.
+	#[pallet::storage]
+	pub type Cdps<T: Config> = StorageMap<_, Twox64Concat, CdpId, Cdp<T::Balance>, OptionQuery>;
+
+	/// The owner of each CDP.
+	#[pallet::storage]
+	pub type CdpOwner<T: Config> = StorageMap<_, Twox64Concat, CdpId, T::AccountId, OptionQuery>;
+
+	/// The CDPs held by each account.
+	#[pallet::storage]
+	pub type OwnerCdps<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Twox64Concat, CdpId, (), OptionQuery>;
+
+	/// Accounts allowed to manage a CDP on behalf of its owner.
+	#[pallet::storage]
+	pub type CdpManagers<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, CdpId, Blake2_128Concat, T::AccountId, (), OptionQuery>;
+
+	/// The id given to the next CDP opened.
 	#[pallet::storage]
-	pub type Cdps<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, Cdp<T::Balance>, OptionQuery>;
+	pub type NextCdpId<T> = StorageValue<_, CdpId, ValueQuery>;
 
+	/// Collateral locked in CDPs, per collateral type.
 	#[pallet::storage]
-	pub type TotalCollateral<T> = StorageValue<_, T::Balance, ValueQuery>;
+	pub type TotalCollateral<T: Config> = StorageMap<_, Twox64Concat, CollateralId, T::Balance, ValueQuery>;
 
+	/// Total dUSD owed to the system, including accrued stability fees.
 	#[pallet::storage]
 	pub type TotalDusdDebt<T> = StorageValue<_, T::Balance, ValueQuery>;
 
+	/// Total dEUR owed to the system, including accrued stability fees.
 	#[pallet::storage]
 	pub type TotalDeurDebt<T> = StorageValue<_, T::Balance, ValueQuery>;
 
+	/// The part of `TotalDusdDebt` and `TotalDeurDebt` backed by reserves held outside of CDPs,
+	/// such as those of the peg stability module. It is kept out of the debt ceilings and the
+	/// redemption rates, which cover the debt backed by CDP collateral.
+	#[pallet::storage]
+	pub type ReserveBackedDebt<T: Config> = StorageMap<_, Twox64Concat, DebtType, T::Balance, ValueQuery>;
+
+	/// The most of each stablecoin that may be owed to the system across all collateral types.
+	/// Issuance is unbounded for stablecoins without a ceiling.
+	#[pallet::storage]
+	pub type GlobalDebtCeilings<T: Config> = StorageMap<_, Twox64Concat, DebtType, T::Balance, OptionQuery>;
+
+	/// Sum of the normalized debt of all CDPs, per collateral type and stablecoin.
+	#[pallet::storage]
+	pub type NormalizedDebt<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, CollateralId, Twox64Concat, DebtType, T::Balance, ValueQuery>;
+
+	#[pallet::type_value]
+	pub fn DefaultRate() -> FixedU128 {
+		FixedU128::one()
+	}
+
+	/// Stability fee accumulators, per collateral type and stablecoin.
+	#[pallet::storage]
+	pub type Rates<T> =
+		StorageDoubleMap<_, Twox64Concat, CollateralId, Twox64Concat, DebtType, FixedU128, ValueQuery, DefaultRate>;
+
+	/// The block at which the rate accumulators were last brought up to date.
+	#[pallet::storage]
+	pub type RateUpdatedAt<T> = StorageValue<_, u32, OptionQuery>;
+
+	/// The prices collateral is settled at after shutdown, recorded when the system is caged.
+	#[pallet::storage]
+	pub type CagePrices<T> = StorageDoubleMap<_, Twox64Concat, PriceFeed, Twox64Concat, DebtType, u128, ValueQuery>;
+
+	#[pallet::storage]
+	pub type Auctions<T: Config> =
+		StorageMap<_, Blake2_128Concat, AuctionId, CollateralAuction<T::AccountId, T::Balance>, OptionQuery>;
+
+	#[pallet::storage]
+	pub type NextAuctionId<T> = StorageValue<_, AuctionId, ValueQuery>;
+
+	/// The block at which emergency shutdown caged the system.
+	#[pallet::storage]
+	pub type CagedAt<T> = StorageValue<_, u32, OptionQuery>;
+
+	/// Collateral of each type taken from CDPs during shutdown to back each stablecoin.
+	#[pallet::storage]
+	pub type SettledCollateral<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, DebtType, Twox64Concat, CollateralId, T::Balance, ValueQuery>;
+
+	/// Collateral of each type paid out per unit of each stablecoin after shutdown, with 18
+	/// decimals.
+	#[pallet::storage]
+	pub type RedemptionRates<T> =
+		StorageDoubleMap<_, Twox64Concat, DebtType, Twox64Concat, CollateralId, u128, OptionQuery>;
+
+	/// CDPs found below their liquidation ratio, whose auctions `on_idle` starts.
+	#[pallet::storage]
+	pub type LiquidationQueue<T> = StorageMap<_, Twox64Concat, CdpId, bool, ValueQuery>;
+
+	/// The next CDP id `on_idle` checks for liquidation.
 	#[pallet::storage]
-	pub type OrmUsdPrice<T> = StorageValue<_, u128, ValueQuery>;
+	pub type ScanCursor<T> = StorageValue<_, CdpId, ValueQuery>;
 
+	/// The number of CDP ids left to check since the last price update.
 	#[pallet::storage]
-	pub type OrmEurPrice<T> = StorageValue<_, u128, ValueQuery>;
+	pub type PendingScan<T> = StorageValue<_, u64, ValueQuery>;
 
+	/// Collateral types valued at a time-weighted average price for liquidations, with the
+	/// averaging window in milliseconds.
 	#[pallet::storage]
-	pub type LiquidationQueue<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, bool, ValueQuery>;
+	pub type TwapWindows<T> = StorageMap<_, Twox64Concat, CollateralId, u64, OptionQuery>;
+
+	#[pallet::genesis_config]
+	#[derive(frame_support::DefaultNoBound)]
+	pub struct GenesisConfig<T: Config> {
+		/// Collateral types registered at genesis.
+		pub collateral_types: Vec<(CollateralId, CollateralType<T::Balance>)>,
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+		fn build(&self) {
+			assert!(
+				self.collateral_types.len() as u32 <= T::MaxCollateralTypes::get(),
+				"more collateral types than MaxCollateralTypes"
+			);
+			for (id, collateral_type) in &self.collateral_types {
+				CollateralTypes::<T>::insert(id, collateral_type);
+			}
+		}
+	}
 
 	/// Events that functions in this pallet can emit.
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
 		CdpCreated {
+			cdp_id: CdpId,
 			owner: T::AccountId,
+			collateral_type: CollateralId,
 			collateral: T::Balance,
 		},
 		CollateralDeposited {
-			owner: T::AccountId,
+			cdp_id: CdpId,
 			amount: T::Balance,
 		},
 		CollateralWithdrawn {
-			owner: T::AccountId,
+			cdp_id: CdpId,
 			amount: T::Balance,
 		},
 		DusdMinted {
-			owner: T::AccountId,
+			cdp_id: CdpId,
 			amount: T::Balance,
 		},
 		DeurMinted {
-			owner: T::AccountId,
+			cdp_id: CdpId,
 			amount: T::Balance,
 		},
 		DusdRepaid {
-			owner: T::AccountId,
+			cdp_id: CdpId,
 			amount: T::Balance,
 		},
 		DeurRepaid {
-			owner: T::AccountId,
+			cdp_id: CdpId,
 			amount: T::Balance,
 		},
 		CdpLiquidated {
-			owner: T::AccountId,
+			cdp_id: CdpId,
+			liquidator: T::AccountId,
+			collateral_seized: T::Balance,
+		},
+		CdpPartiallyLiquidated {
+			cdp_id: CdpId,
 			liquidator: T::AccountId,
+			dusd_repaid: T::Balance,
+			deur_repaid: T::Balance,
 			collateral_seized: T::Balance,
 		},
-		PriceUpdated {
-			asset: Vec<u8>,
+		CdpClosed {
+			cdp_id: CdpId,
+			collateral_returned: T::Balance,
+		},
+		StabilityFeeAccrued {
+			dusd_fee: T::Balance,
+			deur_fee: T::Balance,
+		},
+		AuctionStarted {
+			id: AuctionId,
+			owner: T::AccountId,
+			collateral_type: CollateralId,
+			currency: DebtType,
+			lot: T::Balance,
+			tab: T::Balance,
+			top: u128,
+		},
+		AuctionTaken {
+			id: AuctionId,
+			buyer: T::AccountId,
+			collateral: T::Balance,
+			paid: T::Balance,
 			price: u128,
 		},
+		AuctionReset {
+			id: AuctionId,
+			top: u128,
+		},
+		AuctionFinished {
+			id: AuctionId,
+			collateral_returned: T::Balance,
+			debt_remaining: T::Balance,
+		},
+		Caged {
+			at: u32,
+		},
+		AuctionSkipped {
+			id: AuctionId,
+			owner: T::AccountId,
+			cdp_id: CdpId,
+			collateral_returned: T::Balance,
+			debt_returned: T::Balance,
+		},
+		CdpSettled {
+			cdp_id: CdpId,
+			dusd_debt: T::Balance,
+			deur_debt: T::Balance,
+			collateral_seized: T::Balance,
+		},
+		RedemptionRateFixed {
+			currency: DebtType,
+			collateral_type: CollateralId,
+			rate: u128,
+		},
+		StablecoinRedeemed {
+			who: T::AccountId,
+			currency: DebtType,
+			amount: T::Balance,
+		},
+		CollateralRedeemed {
+			who: T::AccountId,
+			collateral_type: CollateralId,
+			amount: T::Balance,
+		},
+		CollateralTypeAdded {
+			id: CollateralId,
+		},
+		CollateralTypeUpdated {
+			id: CollateralId,
+		},
+		CdpTransferred {
+			cdp_id: CdpId,
+			from: T::AccountId,
+			to: T::AccountId,
+		},
+		CdpManagerUpdated {
+			cdp_id: CdpId,
+			manager: T::AccountId,
+			allowed: bool,
+		},
+		GlobalDebtCeilingUpdated {
+			currency: DebtType,
+			ceiling: Option<T::Balance>,
+		},
+		CollateralDebtCeilingUpdated {
+			id: CollateralId,
+			ceiling: T::Balance,
+		},
+		DustUpdated {
+			id: CollateralId,
+			dust: T::Balance,
+		},
+		TwapWindowUpdated {
+			id: CollateralId,
+			window: Option<u64>,
+		},
 	}
 
 	/// Errors that can be returned by this pallet.
 	#[pallet::error]
 	pub enum Error<T> {
 		CdpNotFound,
-		CdpAlreadyExists,
 		InsufficientCollateral,
 		CollateralRatioTooLow,
 		CdpUndercollateralized,
 		InsufficientDebt,
 		PriceNotAvailable,
+		/// The oracle price is older than `MaxPriceAge`.
+		StalePrice,
+		/// The circuit breaker of the price feed has tripped and minting is paused.
+		PriceFeedHalted,
+		/// A valuation does not fit in 128 bits.
 		Overflow,
 		NotAuthorized,
 		CdpNotLiquidatable,
+		CdpHasDebt,
+		AuctionNotFound,
+		AuctionNeedsReset,
+		AuctionNotStale,
+		AuctionPriceTooHigh,
+		SystemCaged,
+		SystemNotCaged,
+		UnsettledDebt,
+		AuctionsOutstanding,
+		RedemptionRatesAlreadyFixed,
+		RedemptionNotAvailable,
+		UnknownCollateralType,
+		CollateralTypeExists,
+		/// `MaxCollateralTypes` collateral types are registered already.
+		TooManyCollateralTypes,
+		InvalidRiskParameters,
+		GlobalDebtCeilingExceeded,
+		CollateralDebtCeilingExceeded,
+		DebtBelowDust,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		/// Check CDPs for liquidation after a price update and start the auctions of those
+		/// found unsafe, within the weight left in the block.
+		fn on_idle(_n: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+			let mut meter = WeightMeter::with_limit(remaining_weight);
+			if meter.try_consume(T::DbWeight::get().reads(1)).is_err() || Self::is_caged() {
+				return meter.consumed();
+			}
+			
+			Self::scan_for_liquidations(&mut meter);
+			Self::start_queued_auctions(&mut meter);
+			
+			meter.consumed()
+		}
+
+		/// Submit unsigned liquidations of unsafe CDPs, so that auctions start even when
+		/// `on_idle` runs out of weight and no keeper is around.
+		fn offchain_worker(_n: BlockNumberFor<T>) {
+			if Self::is_caged() {
+				return;
+			}
+			
+			for cdp_id in Self::offchain_liquidation_candidates() {
+				let call = Call::liquidate_unsigned { cdp_id };
+				let _ = SubmitTransaction::<T, Call<T>>::submit_transaction(T::create_bare(call.into()));
+			}
+		}
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		/// Only liquidations of CDPs that are unsafe right now are valid, once per CDP.
+		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			let Call::liquidate_unsigned { cdp_id } = call else {
+				return InvalidTransaction::Call.into();
+			};
+			if Self::is_caged() {
+				return InvalidTransaction::Call.into();
+			}
+			match Cdps::<T>::get(cdp_id).map(|cdp| Self::is_liquidatable(&cdp)) {
+				Some(Ok(true)) => {},
+				_ => return InvalidTransaction::Stale.into(),
+			}
+			
+			ValidTransaction::with_tag_prefix("CollateralEngineLiquidation")
+				.priority(T::UnsignedPriority::get())
+				.and_provides(cdp_id)
+				.longevity(5)
+				.propagate(true)
+				.build()
+		}
 	}
 
 	/// The pallet's dispatchable functions for CDP management.
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		#[pallet::call_index(0)]
-		#[pallet::weight(T::WeightInfo::do_something())]
+		#[pallet::weight(T::WeightInfo::create_cdp())]
 		pub fn create_cdp(
 			origin: OriginFor<T>,
+			collateral_type: CollateralId,
 			collateral_amount: T::Balance,
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 			
-			ensure!(!Cdps::<T>::contains_key(&who), Error::<T>::CdpAlreadyExists);
+			Self::ensure_live()?;
 			
-			T::Currency::reserve(&who, collateral_amount)?;
+			let ilk = Self::collateral_type(collateral_type)?;
+			
+			Self::lock_collateral(ilk.asset, &who, collateral_amount)?;
 			
 			let cdp = Cdp {
+				collateral_type,
 				collateral: collateral_amount,
 				dusd_debt: Zero::zero(),
 				deur_debt: Zero::zero(),
 				last_update: frame_system::Pallet::<T>::block_number().saturated_into(),
 			};
 			
-			Cdps::<T>::insert(&who, &cdp);
-			TotalCollateral::<T>::mutate(|total| *total = total.saturating_add(collateral_amount));
+			let cdp_id = Self::insert_cdp(&who, &cdp);
+			TotalCollateral::<T>::mutate(collateral_type, |total| *total = total.saturating_add(collateral_amount));
 			
-			Self::deposit_event(Event::CdpCreated { owner: who, collateral: collateral_amount });
+			Self::deposit_event(Event::CdpCreated {
+				cdp_id,
+				owner: who,
+				collateral_type,
+				collateral: collateral_amount,
+			});
 			
 			Ok(())
 		}
 
 		#[pallet::call_index(1)]
-		#[pallet::weight(T::WeightInfo::cause_error())]
+		#[pallet::weight(T::WeightInfo::deposit_collateral())]
 		pub fn deposit_collateral(
 			origin: OriginFor<T>,
+			cdp_id: CdpId,
 			amount: T::Balance,
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 			
-			let mut cdp = Cdps::<T>::get(&who).ok_or(Error::<T>::CdpNotFound)?;
+			Self::ensure_live()?;
 			
-			T::Currency::reserve(&who, amount)?;
+			let mut cdp = Cdps::<T>::get(cdp_id).ok_or(Error::<T>::CdpNotFound)?;
+			let owner = Self::ensure_cdp_access(&who, cdp_id)?;
+			let ilk = Self::collateral_type(cdp.collateral_type)?;
+			
+			Self::lock_collateral(ilk.asset, &owner, amount)?;
 			
 			// Update CDP
 			cdp.collateral = cdp.collateral.saturating_add(amount);
 			cdp.last_update = frame_system::Pallet::<T>::block_number().saturated_into();
 			
-			Cdps::<T>::insert(&who, &cdp);
-			TotalCollateral::<T>::mutate(|total| *total = total.saturating_add(amount));
+			Cdps::<T>::insert(cdp_id, &cdp);
+			TotalCollateral::<T>::mutate(cdp.collateral_type, |total| *total = total.saturating_add(amount));
 			
-			Self::deposit_event(Event::CollateralDeposited { owner: who, amount });
+			Self::deposit_event(Event::CollateralDeposited { cdp_id, amount });
 			
 			Ok(())
 		}
 
 		#[pallet::call_index(2)]
-		#[pallet::weight(T::WeightInfo::cause_error())]
+		#[pallet::weight(T::WeightInfo::withdraw_collateral().saturating_add(Pallet::<T>::accrual_weight()))]
 		pub fn withdraw_collateral(
 			origin: OriginFor<T>,
+			cdp_id: CdpId,
 			amount: T::Balance,
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 			
-			let mut cdp = Cdps::<T>::get(&who).ok_or(Error::<T>::CdpNotFound)?;
+			Self::accrue_stability_fees()?;
+			
+			let mut cdp = Cdps::<T>::get(cdp_id).ok_or(Error::<T>::CdpNotFound)?;
+			let owner = Self::ensure_cdp_access(&who, cdp_id)?;
+			let ilk = Self::collateral_type(cdp.collateral_type)?;
 			
 			ensure!(cdp.collateral >= amount, Error::<T>::InsufficientCollateral);
 			
 			let new_collateral = cdp.collateral.saturating_sub(amount);
 			
 			if !cdp.dusd_debt.is_zero() || !cdp.deur_debt.is_zero() {
+				// A suspect spot price must not value the collateral left behind
+				Self::ensure_not_halted(ilk.price_feed)?;
+				Self::ensure_fresh_prices(ilk.price_feed)?;
+				let (dusd_debt, deur_debt) = Self::cdp_debt(&cdp);
 				ensure!(
-					Self::check_collateral_ratio(&who, new_collateral, cdp.dusd_debt, cdp.deur_debt)?,
+					Self::check_collateral_ratio(&ilk, new_collateral, dusd_debt, deur_debt)?,
 					Error::<T>::CollateralRatioTooLow
 				);
+				Self::ensure_not_dust(&ilk, dusd_debt, deur_debt)?;
 			}
 			
 			// Update CDP
 			cdp.collateral = new_collateral;
 			cdp.last_update = frame_system::Pallet::<T>::block_number().saturated_into();
 			
-			T::Currency::unreserve(&who, amount);
-			Cdps::<T>::insert(&who, &cdp);
-			TotalCollateral::<T>::mutate(|total| *total = total.saturating_sub(amount));
+			Self::release_collateral(ilk.asset, &owner, amount)?;
+			Cdps::<T>::insert(cdp_id, &cdp);
+			TotalCollateral::<T>::mutate(cdp.collateral_type, |total| *total = total.saturating_sub(amount));
 			
-			Self::deposit_event(Event::CollateralWithdrawn { owner: who, amount });
+			Self::deposit_event(Event::CollateralWithdrawn { cdp_id, amount });
 			
 			Ok(())
 		}
 
 		#[pallet::call_index(3)]
-		#[pallet::weight(T::WeightInfo::do_something())]
+		#[pallet::weight(T::WeightInfo::mint_dusd().saturating_add(Pallet::<T>::accrual_weight()))]
 		pub fn mint_dusd(
 			origin: OriginFor<T>,
+			cdp_id: CdpId,
 			amount: T::Balance,
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 			
-			let mut cdp = Cdps::<T>::get(&who).ok_or(Error::<T>::CdpNotFound)?;
+			Self::ensure_live()?;
 			
-			let new_dusd_debt = cdp.dusd_debt.saturating_add(amount);
+			Self::accrue_stability_fees()?;
+			
+			let mut cdp = Cdps::<T>::get(cdp_id).ok_or(Error::<T>::CdpNotFound)?;
+			Self::ensure_cdp_access(&who, cdp_id)?;
+			let ilk = Self::collateral_type(cdp.collateral_type)?;
+			
+			Self::ensure_not_halted(ilk.price_feed)?;
+			Self::ensure_fresh_prices(ilk.price_feed)?;
+			
+			let rate = Rates::<T>::get(cdp.collateral_type, DebtType::Dusd);
+			let normalized = Self::normalize_debt(amount, rate, Rounding::Up)?;
+			let new_dusd_debt = cdp.dusd_debt.saturating_add(normalized);
+			let (_, deur_debt) = Self::cdp_debt(&cdp);
 			
 			ensure!(
-				Self::check_collateral_ratio(&who, cdp.collateral, new_dusd_debt, cdp.deur_debt)?,
+				Self::check_collateral_ratio(
+					&ilk,
+					cdp.collateral,
+					Self::denormalize_debt(new_dusd_debt, rate),
+					deur_debt,
+				)?,
 				Error::<T>::CollateralRatioTooLow
 			);
+			Self::ensure_not_dust(&ilk, Self::denormalize_debt(new_dusd_debt, rate), deur_debt)?;
+			Self::ensure_within_debt_ceilings(cdp.collateral_type, &ilk, DebtType::Dusd, amount)?;
+			
+			T::DusdMinter::mint(Self::issuer(), &who, amount)?;
 			
 			// Update CDP
 			cdp.dusd_debt = new_dusd_debt;
 			cdp.last_update = frame_system::Pallet::<T>::block_number().saturated_into();
 			
-			Cdps::<T>::insert(&who, &cdp);
+			Cdps::<T>::insert(cdp_id, &cdp);
+			NormalizedDebt::<T>::mutate(cdp.collateral_type, DebtType::Dusd, |total| {
+				*total = total.saturating_add(normalized)
+			});
 			TotalDusdDebt::<T>::mutate(|total| *total = total.saturating_add(amount));
 			
+			Self::deposit_event(Event::DusdMinted { cdp_id, amount });
+			
+			Ok(())
+		}
+
+		#[pallet::call_index(5)]
+		#[pallet::weight(T::WeightInfo::repay_dusd().saturating_add(Pallet::<T>::accrual_weight()))]
+		pub fn repay_dusd(
+			origin: OriginFor<T>,
+			cdp_id: CdpId,
+			amount: T::Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			
+			Self::accrue_stability_fees()?;
+			
+			let mut cdp = Cdps::<T>::get(cdp_id).ok_or(Error::<T>::CdpNotFound)?;
+			Self::ensure_cdp_access(&who, cdp_id)?;
+			
+			let rate = Rates::<T>::get(cdp.collateral_type, DebtType::Dusd);
+			let debt = Self::denormalize_debt(cdp.dusd_debt, rate);
+			ensure!(debt >= amount, Error::<T>::InsufficientDebt);
+			
+			// Repaying the full amount owed clears any rounding dust as well
+			let normalized = if amount == debt {
+				cdp.dusd_debt
+			} else {
+				Self::normalize_debt(amount, rate, Rounding::Down)?.min(cdp.dusd_debt)
+			};
+			
+			// Update CDP
+			cdp.dusd_debt = cdp.dusd_debt.saturating_sub(normalized);
+			cdp.last_update = frame_system::Pallet::<T>::block_number().saturated_into();
 			
-			Self::deposit_event(Event::DusdMinted { owner: who, amount });
+			// A partial repayment must not leave the CDP too small to be worth liquidating
+			let ilk = Self::collateral_type(cdp.collateral_type)?;
+			let (dusd_debt, deur_debt) = Self::cdp_debt(&cdp);
+			Self::ensure_not_dust(&ilk, dusd_debt, deur_debt)?;
+			
+			T::DusdMinter::burn(Self::issuer(), &who, amount)?;
+			
+			Cdps::<T>::insert(cdp_id, &cdp);
+			NormalizedDebt::<T>::mutate(cdp.collateral_type, DebtType::Dusd, |total| {
+				*total = total.saturating_sub(normalized)
+			});
+			TotalDusdDebt::<T>::mutate(|total| *total = total.saturating_sub(amount));
+			
+			Self::deposit_event(Event::DusdRepaid { cdp_id, amount });
 			
 			Ok(())
 		}
 
-		#[pallet::call_index(4)]
-		#[pallet::weight(T::WeightInfo::cause_error())]
-		pub fn update_price(
+		#[pallet::call_index(6)]
+		#[pallet::weight(T::WeightInfo::repay_deur().saturating_add(Pallet::<T>::accrual_weight()))]
+		pub fn repay_deur(
 			origin: OriginFor<T>,
-			asset: Vec<u8>,
-			price: u128,
+			cdp_id: CdpId,
+			amount: T::Balance,
 		) -> DispatchResult {
-			ensure_root(origin)?;
+			let who = ensure_signed(origin)?;
 			
-			// Update price based on asset
-			if asset == b"ORM/USD" {
-				OrmUsdPrice::<T>::put(price);
-			} else if asset == b"ORM/EUR" {
-				OrmEurPrice::<T>::put(price);
-			}
+			Self::accrue_stability_fees()?;
+			
+			let mut cdp = Cdps::<T>::get(cdp_id).ok_or(Error::<T>::CdpNotFound)?;
+			Self::ensure_cdp_access(&who, cdp_id)?;
+			
+			let rate = Rates::<T>::get(cdp.collateral_type, DebtType::Deur);
+			let debt = Self::denormalize_debt(cdp.deur_debt, rate);
+			ensure!(debt >= amount, Error::<T>::InsufficientDebt);
+			
+			// Repaying the full amount owed clears any rounding dust as well
+			let normalized = if amount == debt {
+				cdp.deur_debt
+			} else {
+				Self::normalize_debt(amount, rate, Rounding::Down)?.min(cdp.deur_debt)
+			};
+			
+			// Update CDP
+			cdp.deur_debt = cdp.deur_debt.saturating_sub(normalized);
+			cdp.last_update = frame_system::Pallet::<T>::block_number().saturated_into();
+			
+			// A partial repayment must not leave the CDP too small to be worth liquidating
+			let ilk = Self::collateral_type(cdp.collateral_type)?;
+			let (dusd_debt, deur_debt) = Self::cdp_debt(&cdp);
+			Self::ensure_not_dust(&ilk, dusd_debt, deur_debt)?;
+			
+			T::DeurMinter::burn(Self::issuer(), &who, amount)?;
 			
-			Self::deposit_event(Event::PriceUpdated { asset, price });
+			Cdps::<T>::insert(cdp_id, &cdp);
+			NormalizedDebt::<T>::mutate(cdp.collateral_type, DebtType::Deur, |total| {
+				*total = total.saturating_sub(normalized)
+			});
+			TotalDeurDebt::<T>::mutate(|total| *total = total.saturating_sub(amount));
+			
+			Self::deposit_event(Event::DeurRepaid { cdp_id, amount });
 			
 			Ok(())
 		}
-	}
 
-	impl<T: Config> Pallet<T> {
-		fn check_collateral_ratio(
-			_who: &T::AccountId,
-			collateral: T::Balance,
-			dusd_debt: T::Balance,
-			deur_debt: T::Balance,
-		) -> Result<bool, Error<T>> {
-			let orm_usd_price = OrmUsdPrice::<T>::get();
-			let orm_eur_price = OrmEurPrice::<T>::get();
+		#[pallet::call_index(8)]
+		#[pallet::weight(T::WeightInfo::mint_deur().saturating_add(Pallet::<T>::accrual_weight()))]
+		pub fn mint_deur(
+			origin: OriginFor<T>,
+			cdp_id: CdpId,
+			amount: T::Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
 			
-			ensure!(!orm_usd_price.is_zero(), Error::<T>::PriceNotAvailable);
-			ensure!(!orm_eur_price.is_zero(), Error::<T>::PriceNotAvailable);
+			Self::ensure_live()?;
 			
-			let collateral_value_usd = (collateral.saturated_into::<u128>())
-				.saturating_mul(orm_usd_price)
-				.saturating_div(1_000_000_000_000_000_000u128); // Scale down from 1e18
+			Self::accrue_stability_fees()?;
 			
-			let dusd_debt_value = dusd_debt.saturated_into::<u128>();
-			let deur_debt_value_usd = (deur_debt.saturated_into::<u128>())
-				.saturating_mul(orm_eur_price)
-				.saturating_div(orm_usd_price); // Convert EUR to USD
+			let mut cdp = Cdps::<T>::get(cdp_id).ok_or(Error::<T>::CdpNotFound)?;
+			Self::ensure_cdp_access(&who, cdp_id)?;
+			let ilk = Self::collateral_type(cdp.collateral_type)?;
 			
-			let total_debt_usd = dusd_debt_value.saturating_add(deur_debt_value_usd);
+			Self::ensure_not_halted(ilk.price_feed)?;
+			Self::ensure_fresh_prices(ilk.price_feed)?;
 			
-			if total_debt_usd.is_zero() {
-				return Ok(true);
+			let rate = Rates::<T>::get(cdp.collateral_type, DebtType::Deur);
+			let normalized = Self::normalize_debt(amount, rate, Rounding::Up)?;
+			let new_deur_debt = cdp.deur_debt.saturating_add(normalized);
+			let (dusd_debt, _) = Self::cdp_debt(&cdp);
+			
+			ensure!(
+				Self::check_collateral_ratio(
+					&ilk,
+					cdp.collateral,
+					dusd_debt,
+					Self::denormalize_debt(new_deur_debt, rate),
+				)?,
+				Error::<T>::CollateralRatioTooLow
+			);
+			Self::ensure_not_dust(&ilk, dusd_debt, Self::denormalize_debt(new_deur_debt, rate))?;
+			Self::ensure_within_debt_ceilings(cdp.collateral_type, &ilk, DebtType::Deur, amount)?;
+			
+			T::DeurMinter::mint(Self::issuer(), &who, amount)?;
+			
+			// Update CDP
+			cdp.deur_debt = new_deur_debt;
+			cdp.last_update = frame_system::Pallet::<T>::block_number().saturated_into();
+			
+			Cdps::<T>::insert(cdp_id, &cdp);
+			NormalizedDebt::<T>::mutate(cdp.collateral_type, DebtType::Deur, |total| {
+				*total = total.saturating_add(normalized)
+			});
+			TotalDeurDebt::<T>::mutate(|total| *total = total.saturating_add(amount));
+			
+			Self::deposit_event(Event::DeurMinted { cdp_id, amount });
+			
+			Ok(())
+		}
+
+		/// Liquidate a CDP that has fallen below the liquidation ratio of its collateral type.
+		///
+		/// The caller burns stablecoin covering the CDP's debt and receives collateral worth that
+		/// debt plus the liquidation penalty. Any remaining collateral is returned to the owner and
+		/// the CDP is removed. When the collateral falls short of the debt plus penalty, the caller
+		/// only covers the share of the debt it pays for and the rest is recorded as bad debt.
+		#[pallet::call_index(9)]
+		#[pallet::weight(T::WeightInfo::liquidate().saturating_add(Pallet::<T>::accrual_weight()))]
+		pub fn liquidate(
+			origin: OriginFor<T>,
+			cdp_id: CdpId,
+		) -> DispatchResult {
+			let liquidator = ensure_signed(origin)?;
+			
+			Self::ensure_live()?;
+			
+			Self::accrue_stability_fees()?;
+			
+			let cdp = Cdps::<T>::get(cdp_id).ok_or(Error::<T>::CdpNotFound)?;
+			let owner = CdpOwner::<T>::get(cdp_id).ok_or(Error::<T>::CdpNotFound)?;
+			let ilk = Self::collateral_type(cdp.collateral_type)?;
+			
+			ensure!(Self::is_liquidatable(&cdp)?, Error::<T>::CdpNotLiquidatable);
+			
+			let (dusd_debt, deur_debt) = Self::cdp_debt(&cdp);
+			let penalized = Self::penalized_collateral(&cdp, dusd_debt, deur_debt)?;
+			let collateral_seized: T::Balance = penalized.min(cdp.collateral.saturated_into()).saturated_into();
+			
+			// The liquidator covers the debt the seized collateral pays for
+			let covered = Perquintill::from_rational(collateral_seized.saturated_into::<u128>(), penalized.max(1));
+			let dusd_repaid: T::Balance = covered.mul_ceil(dusd_debt.saturated_into::<u128>()).saturated_into();
+			let deur_repaid: T::Balance = covered.mul_ceil(deur_debt.saturated_into::<u128>()).saturated_into();
+			T::DusdMinter::burn(Self::issuer(), &liquidator, dusd_repaid)?;
+			T::DeurMinter::burn(Self::issuer(), &liquidator, deur_repaid)?;
+			
+			Self::seize_collateral(ilk.asset, &owner, &liquidator, collateral_seized)?;
+			Self::release_collateral(ilk.asset, &owner, cdp.collateral.saturating_sub(collateral_seized))?;
+			
+			Self::remove_cdp(cdp_id, &owner);
+			TotalCollateral::<T>::mutate(cdp.collateral_type, |total| *total = total.saturating_sub(cdp.collateral));
+			NormalizedDebt::<T>::mutate(cdp.collateral_type, DebtType::Dusd, |total| {
+				*total = total.saturating_sub(cdp.dusd_debt)
+			});
+			NormalizedDebt::<T>::mutate(cdp.collateral_type, DebtType::Deur, |total| {
+				*total = total.saturating_sub(cdp.deur_debt)
+			});
+			TotalDusdDebt::<T>::mutate(|total| *total = total.saturating_sub(dusd_repaid));
+			TotalDeurDebt::<T>::mutate(|total| *total = total.saturating_sub(deur_repaid));
+			
+			// Whatever the collateral could not cover stays outstanding as bad debt
+			let dusd_shortfall = dusd_debt.saturating_sub(dusd_repaid);
+			if !dusd_shortfall.is_zero() {
+				T::Ledger::on_bad_debt(DebtType::Dusd, dusd_shortfall);
+			}
+			let deur_shortfall = deur_debt.saturating_sub(deur_repaid);
+			if !deur_shortfall.is_zero() {
+				T::Ledger::on_bad_debt(DebtType::Deur, deur_shortfall);
 			}
 			
-			let ratio = collateral_value_usd
-				.saturating_mul(10000u128)
-				.saturating_div(total_debt_usd);
+			Self::deposit_event(Event::CdpLiquidated { cdp_id, liquidator, collateral_seized });
 			
-			Ok(ratio >= T::MinCollateralRatio::get() as u128)
+			Ok(())
 		}
+
+		/// Liquidate an unsafe CDP by auctioning off its collateral.
+		///
+		/// The CDP is closed and its collateral is split into one auction per outstanding debt
+		/// type, in proportion to the value of that debt. Each auction raises the debt plus the
+		/// liquidation penalty.
+		#[pallet::call_index(10)]
+		#[pallet::weight(T::WeightInfo::start_auction().saturating_add(Pallet::<T>::accrual_weight()))]
+		pub fn start_auction(
+			origin: OriginFor<T>,
+			cdp_id: CdpId,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+			
+			Self::do_start_auction(cdp_id)
+		}
+
+		/// Buy up to `max_collateral` from an auction at its current price.
+		///
+		/// Fails if the current price is above `max_price`. Once the auction has raised its
+		/// full tab, the remaining collateral is returned to the CDP owner.
+		#[pallet::call_index(11)]
+		#[pallet::weight(T::WeightInfo::take())]
+		pub fn take(
+			origin: OriginFor<T>,
+			id: AuctionId,
+			max_collateral: T::Balance,
+			max_price: u128,
+		) -> DispatchResult {
+			let buyer = ensure_signed(origin)?;
+			
+			Self::ensure_live()?;
+			
+			let mut auction = Auctions::<T>::get(id).ok_or(Error::<T>::AuctionNotFound)?;
+			let ilk = Self::collateral_type(auction.collateral_type)?;
+			
+			let (price, needs_reset) = Self::auction_status(&auction);
+			ensure!(!needs_reset, Error::<T>::AuctionNeedsReset);
+			ensure!(price <= max_price, Error::<T>::AuctionPriceTooHigh);
+			
+			// Never sell more than needed to cover the tab
+			let mut collateral = max_collateral.min(auction.lot);
+			let mut paid: T::Balance = Self::collateral_value(collateral, price)?;
+			if paid > auction.tab {
+				paid = auction.tab;
+				collateral = Self::collateral_amount(paid.saturated_into(), price, Rounding::Down)?
+					.min(auction.lot.saturated_into())
+					.saturated_into();
+			}
+			
+			// Debt is repaid first; whatever is paid on top of it is the penalty
+			let repaid = paid.min(auction.debt);
+			let penalty = paid.saturating_sub(repaid);
+			Self::burn_debt(Self::issuer(), auction.currency, &buyer, repaid)?;
+			Self::transfer_to_surplus(Self::issuer(), auction.currency, &buyer, penalty)?;
+			
+			Self::seize_collateral(ilk.asset, &auction.owner, &buyer, collateral)?;
+			
+			auction.lot = auction.lot.saturating_sub(collateral);
+			auction.tab = auction.tab.saturating_sub(paid);
+			auction.debt = auction.debt.saturating_sub(repaid);
+			
+			Self::deposit_event(Event::AuctionTaken { id, buyer, collateral, paid, price });
+			
+			if auction.lot.is_zero() || auction.tab.is_zero() {
+				Self::finish_auction(id, &auction, &ilk)?;
+			} else {
+				Auctions::<T>::insert(id, &auction);
+			}
+			
+			Ok(())
+		}
+
+		/// Restart a stale auction from the current oracle price.
+		#[pallet::call_index(12)]
+		#[pallet::weight(T::WeightInfo::reset_auction())]
+		pub fn reset_auction(
+			origin: OriginFor<T>,
+			id: AuctionId,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+			
+			Self::ensure_live()?;
+			
+			let mut auction = Auctions::<T>::get(id).ok_or(Error::<T>::AuctionNotFound)?;
+			let ilk = Self::collateral_type(auction.collateral_type)?;
+			
+			let (_, needs_reset) = Self::auction_status(&auction);
+			ensure!(needs_reset, Error::<T>::AuctionNotStale);
+			
+			auction.top = Self::auction_start_price(auction.collateral_type, ilk.price_feed, auction.currency)?;
+			auction.started_at = frame_system::Pallet::<T>::block_number().saturated_into();
+			
+			Auctions::<T>::insert(id, &auction);
+			
+			Self::deposit_event(Event::AuctionReset { id, top: auction.top });
+			
+			Ok(())
+		}
+
+		/// Close a CDP that carries no debt, returning all of its collateral to the owner.
+		#[pallet::call_index(7)]
+		#[pallet::weight(T::WeightInfo::close_cdp())]
+		pub fn close_cdp(
+			origin: OriginFor<T>,
+			cdp_id: CdpId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			
+			let cdp = Cdps::<T>::get(cdp_id).ok_or(Error::<T>::CdpNotFound)?;
+			let owner = Self::ensure_cdp_access(&who, cdp_id)?;
+			let ilk = Self::collateral_type(cdp.collateral_type)?;
+			
+			ensure!(cdp.dusd_debt.is_zero() && cdp.deur_debt.is_zero(), Error::<T>::CdpHasDebt);
+			
+			Self::release_collateral(ilk.asset, &owner, cdp.collateral)?;
+			Self::remove_cdp(cdp_id, &owner);
+			TotalCollateral::<T>::mutate(cdp.collateral_type, |total| *total = total.saturating_sub(cdp.collateral));
+			
+			Self::deposit_event(Event::CdpClosed { cdp_id, collateral_returned: cdp.collateral });
+			
+			Ok(())
+		}
+
+		/// Trigger emergency shutdown.
+		///
+		/// Stability fees are brought up to date one last time and prices are frozen. From then
+		/// on no CDPs can be opened, no stablecoin minted and no collateral liquidated; CDPs are
+		/// settled at the frozen prices and stablecoin holders redeem for the collateral.
+		#[pallet::call_index(13)]
+		#[pallet::weight(T::WeightInfo::cage(T::MaxCollateralTypes::get()).saturating_add(Pallet::<T>::accrual_weight()))]
+		pub fn cage(origin: OriginFor<T>) -> DispatchResult {
+			T::ShutdownOrigin::ensure_origin(origin)?;
+			
+			Self::ensure_live()?;
+			let mut prices = Vec::new();
+			for ilk in CollateralTypes::<T>::iter_values() {
+				for currency in [DebtType::Dusd, DebtType::Deur] {
+					let price = Self::price(ilk.price_feed, currency, PriceSource::Spot);
+					ensure!(!price.is_zero(), Error::<T>::PriceNotAvailable);
+					prices.push((ilk.price_feed, currency, price));
+				}
+			}
+			
+			Self::accrue_stability_fees()?;
+			
+			for (feed, currency, price) in prices {
+				CagePrices::<T>::insert(feed, currency, price);
+			}
+			let at: u32 = frame_system::Pallet::<T>::block_number().saturated_into();
+			CagedAt::<T>::put(at);
+			
+			Self::deposit_event(Event::Caged { at });
+			
+			Ok(())
+		}
+
+		/// Cancel a collateral auction that was running at shutdown.
+		///
+		/// The unsold collateral and the debt it still has to cover go back into the owner's
+		/// CDP, to be settled like any other. The liquidation penalty is waived.
+		#[pallet::call_index(14)]
+		#[pallet::weight(T::WeightInfo::skip_auction())]
+		pub fn skip_auction(
+			origin: OriginFor<T>,
+			id: AuctionId,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+			
+			ensure!(Self::is_caged(), Error::<T>::SystemNotCaged);
+			
+			let auction = Auctions::<T>::get(id).ok_or(Error::<T>::AuctionNotFound)?;
+			let collateral_type = auction.collateral_type;
+			
+			// The collateral is still locked for the owner, so it only has to be booked again
+			let rate = Rates::<T>::get(collateral_type, auction.currency);
+			let normalized = Self::normalize_debt(auction.debt, rate, Rounding::Up)?;
+			let mut cdp = Cdp {
+				collateral_type,
+				collateral: auction.lot,
+				dusd_debt: Zero::zero(),
+				deur_debt: Zero::zero(),
+				last_update: frame_system::Pallet::<T>::block_number().saturated_into(),
+			};
+			match auction.currency {
+				DebtType::Dusd => cdp.dusd_debt = normalized,
+				DebtType::Deur => cdp.deur_debt = normalized,
+			}
+			
+			let cdp_id = Self::insert_cdp(&auction.owner, &cdp);
+			Auctions::<T>::remove(id);
+			TotalCollateral::<T>::mutate(collateral_type, |total| *total = total.saturating_add(auction.lot));
+			NormalizedDebt::<T>::mutate(collateral_type, auction.currency, |total| {
+				*total = total.saturating_add(normalized)
+			});
+			
+			Self::deposit_event(Event::AuctionSkipped {
+				id,
+				owner: auction.owner,
+				cdp_id,
+				collateral_returned: auction.lot,
+				debt_returned: auction.debt,
+			});
+			
+			Ok(())
+		}
+
+		/// Settle the debt of a CDP after shutdown.
+		///
+		/// Collateral worth the CDP's debt at the frozen prices is set aside in the vault account
+		/// to back the stablecoin in circulation. Whatever is left belongs to the owner, who can
+		/// withdraw it or close the CDP.
+		#[pallet::call_index(15)]
+		#[pallet::weight(T::WeightInfo::settle_cdp())]
+		pub fn settle_cdp(
+			origin: OriginFor<T>,
+			cdp_id: CdpId,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+			
+			ensure!(Self::is_caged(), Error::<T>::SystemNotCaged);
+			
+			let mut cdp = Cdps::<T>::get(cdp_id).ok_or(Error::<T>::CdpNotFound)?;
+			let owner = CdpOwner::<T>::get(cdp_id).ok_or(Error::<T>::CdpNotFound)?;
+			let ilk = Self::collateral_type(cdp.collateral_type)?;
+			
+			ensure!(!cdp.dusd_debt.is_zero() || !cdp.deur_debt.is_zero(), Error::<T>::InsufficientDebt);
+			
+			// An underwater CDP gives up all of its collateral, dUSD debt first
+			let (dusd_debt, deur_debt) = Self::cdp_debt(&cdp);
+			let settlement_price = |currency| Self::price(ilk.price_feed, currency, PriceSource::Spot);
+			let dusd_collateral = Self::settlement_collateral(dusd_debt, settlement_price(DebtType::Dusd))?
+				.min(cdp.collateral);
+			let deur_collateral = Self::settlement_collateral(deur_debt, settlement_price(DebtType::Deur))?
+				.min(cdp.collateral.saturating_sub(dusd_collateral));
+			let collateral_seized = dusd_collateral.saturating_add(deur_collateral);
+			
+			Self::seize_collateral(ilk.asset, &owner, &Self::vault_account(), collateral_seized)?;
+			
+			SettledCollateral::<T>::mutate(DebtType::Dusd, cdp.collateral_type, |total| {
+				*total = total.saturating_add(dusd_collateral)
+			});
+			SettledCollateral::<T>::mutate(DebtType::Deur, cdp.collateral_type, |total| {
+				*total = total.saturating_add(deur_collateral)
+			});
+			NormalizedDebt::<T>::mutate(cdp.collateral_type, DebtType::Dusd, |total| {
+				*total = total.saturating_sub(cdp.dusd_debt)
+			});
+			NormalizedDebt::<T>::mutate(cdp.collateral_type, DebtType::Deur, |total| {
+				*total = total.saturating_sub(cdp.deur_debt)
+			});
+			TotalCollateral::<T>::mutate(cdp.collateral_type, |total| *total = total.saturating_sub(collateral_seized));
+			
+			// Update CDP
+			cdp.collateral = cdp.collateral.saturating_sub(collateral_seized);
+			cdp.dusd_debt = Zero::zero();
+			cdp.deur_debt = Zero::zero();
+			cdp.last_update = frame_system::Pallet::<T>::block_number().saturated_into();
+			
+			Cdps::<T>::insert(cdp_id, &cdp);
+			
+			Self::deposit_event(Event::CdpSettled {
+				cdp_id,
+				dusd_debt,
+				deur_debt,
+				collateral_seized,
+			});
+			
+			Ok(())
+		}
+
+		/// Fix the amount of each collateral type paid out per unit of dUSD and dEUR.
+		///
+		/// Only possible once every auction has been skipped and every CDP settled, so that all
+		/// collateral backing the stablecoins has been set aside.
+		#[pallet::call_index(16)]
+		#[pallet::weight(T::WeightInfo::fix_redemption_rates(T::MaxCollateralTypes::get()))]
+		pub fn fix_redemption_rates(origin: OriginFor<T>) -> DispatchResult {
+			ensure_signed(origin)?;
+			
+			ensure!(Self::is_caged(), Error::<T>::SystemNotCaged);
+			ensure!(
+				RedemptionRates::<T>::iter_prefix(DebtType::Dusd).next().is_none(),
+				Error::<T>::RedemptionRatesAlreadyFixed
+			);
+			ensure!(Auctions::<T>::iter_keys().next().is_none(), Error::<T>::AuctionsOutstanding);
+			ensure!(
+				NormalizedDebt::<T>::iter_values().all(|debt| debt.is_zero()),
+				Error::<T>::UnsettledDebt
+			);
+			
+			// Stablecoin backed by reserves is redeemed against those reserves instead
+			let supplies = [DebtType::Dusd, DebtType::Deur].map(|currency| {
+				(currency, Self::collateral_backed_debt(currency))
+			});
+			for collateral_type in CollateralTypes::<T>::iter_keys() {
+				for (currency, supply) in supplies {
+					let rate = Self::redemption_rate(currency, collateral_type, supply)?;
+					RedemptionRates::<T>::insert(currency, collateral_type, rate);
+					
+					Self::deposit_event(Event::RedemptionRateFixed { currency, collateral_type, rate });
+				}
+			}
+			
+			Ok(())
+		}
+
+		/// Burn stablecoin in exchange for its share of the settled collateral of every type.
+		#[pallet::call_index(17)]
+		#[pallet::weight(T::WeightInfo::redeem(T::MaxCollateralTypes::get()))]
+		pub fn redeem(
+			origin: OriginFor<T>,
+			currency: DebtType,
+			amount: T::Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			
+			ensure!(
+				RedemptionRates::<T>::iter_prefix(currency).next().is_some(),
+				Error::<T>::RedemptionNotAvailable
+			);
+			
+			Self::burn_debt(Self::issuer(), currency, &who, amount)?;
+			
+			for (collateral_type, rate) in RedemptionRates::<T>::iter_prefix(currency) {
+				let collateral: T::Balance = multiply_by_rational_with_rounding(
+					amount.saturated_into::<u128>(),
+					rate,
+					PRICE_PRECISION,
+					Rounding::Down,
+				)
+				.ok_or(Error::<T>::Overflow)?
+				.saturated_into();
+				
+				if collateral.is_zero() {
+					continue;
+				}
+				
+				let ilk = Self::collateral_type(collateral_type)?;
+				Self::pay_out_collateral(ilk.asset, &who, collateral)?;
+				SettledCollateral::<T>::mutate(currency, collateral_type, |total| {
+					*total = total.saturating_sub(collateral)
+				});
+				
+				Self::deposit_event(Event::CollateralRedeemed { who: who.clone(), collateral_type, amount: collateral });
+			}
+			
+			Self::deposit_event(Event::StablecoinRedeemed { who, currency, amount });
+			
+			Ok(())
+		}
+
+		/// Register a new collateral type.
+		#[pallet::call_index(18)]
+		#[pallet::weight(T::WeightInfo::add_collateral_type().saturating_add(Pallet::<T>::accrual_weight()))]
+		pub fn add_collateral_type(
+			origin: OriginFor<T>,
+			id: CollateralId,
+			collateral_type: CollateralType<T::Balance>,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			
+			ensure!(!CollateralTypes::<T>::contains_key(id), Error::<T>::CollateralTypeExists);
+			ensure!(
+				(CollateralTypes::<T>::iter_keys().count() as u32) < T::MaxCollateralTypes::get(),
+				Error::<T>::TooManyCollateralTypes
+			);
+			Self::ensure_valid_risk_parameters(&collateral_type)?;
+			
+			// Fees on the new type only accrue from now on
+			Self::accrue_stability_fees()?;
+			
+			CollateralTypes::<T>::insert(id, &collateral_type);
+			
+			Self::deposit_event(Event::CollateralTypeAdded { id });
+			
+			Ok(())
+		}
+
+		/// Update the risk parameters of a collateral type.
+		///
+		/// The asset of a collateral type cannot change while CDPs hold it.
+		#[pallet::call_index(19)]
+		#[pallet::weight(T::WeightInfo::update_collateral_type().saturating_add(Pallet::<T>::accrual_weight()))]
+		pub fn update_collateral_type(
+			origin: OriginFor<T>,
+			id: CollateralId,
+			collateral_type: CollateralType<T::Balance>,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			
+			let current = Self::collateral_type(id)?;
+			ensure!(current.asset == collateral_type.asset, Error::<T>::InvalidRiskParameters);
+			Self::ensure_valid_risk_parameters(&collateral_type)?;
+			
+			// Charge the old stability fee up to this block
+			Self::accrue_stability_fees()?;
+			
+			CollateralTypes::<T>::insert(id, &collateral_type);
+			
+			Self::deposit_event(Event::CollateralTypeUpdated { id });
+			if current.debt_ceiling != collateral_type.debt_ceiling {
+				Self::deposit_event(Event::CollateralDebtCeilingUpdated { id, ceiling: collateral_type.debt_ceiling });
+			}
+			if current.dust != collateral_type.dust {
+				Self::deposit_event(Event::DustUpdated { id, dust: collateral_type.dust });
+			}
+			
+			Ok(())
+		}
+
+		/// Hand a CDP, with its collateral and debt, to another account.
+		///
+		/// Managers allowed by the previous owner lose access to the CDP.
+		#[pallet::call_index(20)]
+		#[pallet::weight(T::WeightInfo::transfer_cdp_ownership())]
+		pub fn transfer_cdp_ownership(
+			origin: OriginFor<T>,
+			cdp_id: CdpId,
+			new_owner: T::AccountId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			
+			let cdp = Cdps::<T>::get(cdp_id).ok_or(Error::<T>::CdpNotFound)?;
+			Self::ensure_cdp_owner(&who, cdp_id)?;
+			let ilk = Self::collateral_type(cdp.collateral_type)?;
+			
+			// Native collateral is reserved on the owner's account and moves with the CDP
+			if ilk.asset == CollateralAsset::Native {
+				T::Currency::repatriate_reserved(&who, &new_owner, cdp.collateral, BalanceStatus::Reserved)?;
+			}
+			
+			Self::remove_cdp(cdp_id, &who);
+			Cdps::<T>::insert(cdp_id, &cdp);
+			CdpOwner::<T>::insert(cdp_id, &new_owner);
+			OwnerCdps::<T>::insert(&new_owner, cdp_id, ());
+			
+			Self::deposit_event(Event::CdpTransferred { cdp_id, from: who, to: new_owner });
+			
+			Ok(())
+		}
+
+		/// Allow or disallow `manager` to manage a CDP on the owner's behalf.
+		///
+		/// A manager can do anything the owner can, except hand the CDP on or change its
+		/// managers. Collateral is always locked from and returned to the owner, while minted
+		/// stablecoin goes to the manager and repayments are taken from the manager.
+		#[pallet::call_index(21)]
+		#[pallet::weight(T::WeightInfo::allow_cdp_manager())]
+		pub fn allow_cdp_manager(
+			origin: OriginFor<T>,
+			cdp_id: CdpId,
+			manager: T::AccountId,
+			allowed: bool,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			
+			Self::ensure_cdp_owner(&who, cdp_id)?;
+			
+			if allowed {
+				CdpManagers::<T>::insert(cdp_id, &manager, ());
+			} else {
+				CdpManagers::<T>::remove(cdp_id, &manager);
+			}
+			
+			Self::deposit_event(Event::CdpManagerUpdated { cdp_id, manager, allowed });
+			
+			Ok(())
+		}
+
+		/// Cap the total of `currency` owed to the system, or lift the cap with `None`.
+		///
+		/// Lowering the ceiling below the current debt only stops further minting.
+		#[pallet::call_index(22)]
+		#[pallet::weight(T::WeightInfo::set_global_debt_ceiling())]
+		pub fn set_global_debt_ceiling(
+			origin: OriginFor<T>,
+			currency: DebtType,
+			ceiling: Option<T::Balance>,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			
+			GlobalDebtCeilings::<T>::set(currency, ceiling);
+			
+			Self::deposit_event(Event::GlobalDebtCeilingUpdated { currency, ceiling });
+			
+			Ok(())
+		}
+
+		/// Set the most debt, valued in USD, that may be drawn against a collateral type.
+		#[pallet::call_index(23)]
+		#[pallet::weight(T::WeightInfo::set_collateral_debt_ceiling())]
+		pub fn set_collateral_debt_ceiling(
+			origin: OriginFor<T>,
+			id: CollateralId,
+			ceiling: T::Balance,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			
+			let mut collateral_type = Self::collateral_type(id)?;
+			collateral_type.debt_ceiling = ceiling;
+			CollateralTypes::<T>::insert(id, &collateral_type);
+			
+			Self::deposit_event(Event::CollateralDebtCeilingUpdated { id, ceiling });
+			
+			Ok(())
+		}
+
+		/// Set the least debt, valued in USD, that a CDP of a collateral type may carry.
+		///
+		/// Existing CDPs below the new limit can still be repaid in full, but cannot draw more
+		/// debt or withdraw collateral until they are brought above it.
+		#[pallet::call_index(24)]
+		#[pallet::weight(T::WeightInfo::set_dust())]
+		pub fn set_dust(
+			origin: OriginFor<T>,
+			id: CollateralId,
+			dust: T::Balance,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			
+			let mut collateral_type = Self::collateral_type(id)?;
+			collateral_type.dust = dust;
+			CollateralTypes::<T>::insert(id, &collateral_type);
+			
+			Self::deposit_event(Event::DustUpdated { id, dust });
+			
+			Ok(())
+		}
+
+		/// Value CDPs of a collateral type for liquidation at the time-weighted average price
+		/// over `window` milliseconds, or at the delayed oracle price when `None`.
+		///
+		/// Averaging keeps a brief spike in the price from liquidating positions, at the cost of
+		/// reacting more slowly to a lasting move.
+		#[pallet::call_index(25)]
+		#[pallet::weight(T::WeightInfo::set_twap_window())]
+		pub fn set_twap_window(
+			origin: OriginFor<T>,
+			id: CollateralId,
+			window: Option<u64>,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			
+			Self::collateral_type(id)?;
+			TwapWindows::<T>::set(id, window);
+			
+			Self::deposit_event(Event::TwapWindowUpdated { id, window });
+			
+			Ok(())
+		}
+
+		/// Auction off an unsafe CDP on behalf of the offchain worker.
+		#[pallet::call_index(26)]
+		#[pallet::weight(T::WeightInfo::liquidate_unsigned().saturating_add(Pallet::<T>::accrual_weight()))]
+		pub fn liquidate_unsigned(
+			origin: OriginFor<T>,
+			cdp_id: CdpId,
+		) -> DispatchResult {
+			ensure_none(origin)?;
+			
+			Self::do_start_auction(cdp_id)
+		}
+
+		/// Close just enough of an unsafe CDP's debt to bring it back to the minimum collateral
+		/// ratio of its collateral type.
+		///
+		/// The caller burns the same share of the CDP's dUSD and dEUR debt and receives
+		/// collateral worth that debt plus the liquidation penalty. No more than `CloseFactor` of
+		/// the debt is closed at once, so a CDP too far gone to be restored stays open and
+		/// liquidatable.
+		#[pallet::call_index(27)]
+		#[pallet::weight(T::WeightInfo::liquidate_partial().saturating_add(Pallet::<T>::accrual_weight()))]
+		pub fn liquidate_partial(
+			origin: OriginFor<T>,
+			cdp_id: CdpId,
+		) -> DispatchResult {
+			let liquidator = ensure_signed(origin)?;
+			
+			Self::ensure_live()?;
+			
+			Self::accrue_stability_fees()?;
+			
+			let mut cdp = Cdps::<T>::get(cdp_id).ok_or(Error::<T>::CdpNotFound)?;
+			let owner = CdpOwner::<T>::get(cdp_id).ok_or(Error::<T>::CdpNotFound)?;
+			let ilk = Self::collateral_type(cdp.collateral_type)?;
+			
+			ensure!(Self::is_liquidatable(&cdp)?, Error::<T>::CdpNotLiquidatable);
+			
+			let share = Self::partial_liquidation_share(&cdp, &ilk)?;
+			let (dusd_debt, deur_debt) = Self::cdp_debt(&cdp);
+			let dusd_repaid: T::Balance = share.mul_ceil(dusd_debt.saturated_into::<u128>()).saturated_into();
+			let deur_repaid: T::Balance = share.mul_ceil(deur_debt.saturated_into::<u128>()).saturated_into();
+			let collateral_seized: T::Balance = Self::penalized_collateral(&cdp, dusd_repaid, deur_repaid)?
+				.min(cdp.collateral.saturated_into())
+				.saturated_into();
+			
+			let dusd_normalized = Self::normalized_repayment(&cdp, DebtType::Dusd, dusd_repaid, dusd_debt)?;
+			let deur_normalized = Self::normalized_repayment(&cdp, DebtType::Deur, deur_repaid, deur_debt)?;
+			cdp.dusd_debt = cdp.dusd_debt.saturating_sub(dusd_normalized);
+			cdp.deur_debt = cdp.deur_debt.saturating_sub(deur_normalized);
+			cdp.collateral = cdp.collateral.saturating_sub(collateral_seized);
+			cdp.last_update = frame_system::Pallet::<T>::block_number().saturated_into();
+			
+			let (dusd_left, deur_left) = Self::cdp_debt(&cdp);
+			Self::ensure_not_dust(&ilk, dusd_left, deur_left)?;
+			
+			// The liquidator covers the closed debt
+			T::DusdMinter::burn(Self::issuer(), &liquidator, dusd_repaid)?;
+			T::DeurMinter::burn(Self::issuer(), &liquidator, deur_repaid)?;
+			
+			Self::seize_collateral(ilk.asset, &owner, &liquidator, collateral_seized)?;
+			
+			Cdps::<T>::insert(cdp_id, &cdp);
+			TotalCollateral::<T>::mutate(cdp.collateral_type, |total| *total = total.saturating_sub(collateral_seized));
+			NormalizedDebt::<T>::mutate(cdp.collateral_type, DebtType::Dusd, |total| {
+				*total = total.saturating_sub(dusd_normalized)
+			});
+			NormalizedDebt::<T>::mutate(cdp.collateral_type, DebtType::Deur, |total| {
+				*total = total.saturating_sub(deur_normalized)
+			});
+			TotalDusdDebt::<T>::mutate(|total| *total = total.saturating_sub(dusd_repaid));
+			TotalDeurDebt::<T>::mutate(|total| *total = total.saturating_sub(deur_repaid));
+			
+			Self::deposit_event(Event::CdpPartiallyLiquidated {
+				cdp_id,
+				liquidator,
+				dusd_repaid,
+				deur_repaid,
+				collateral_seized,
+			});
+			
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Whether emergency shutdown has been triggered.
+		pub fn is_caged() -> bool {
+			CagedAt::<T>::get().is_some()
+		}
+
+		fn ensure_live() -> Result<(), Error<T>> {
+			ensure!(!Self::is_caged(), Error::<T>::SystemCaged);
+			
+			Ok(())
+		}
+
+		/// The ids of the CDPs held by `owner`.
+		pub fn cdps_of(owner: &T::AccountId) -> Vec<CdpId> {
+			OwnerCdps::<T>::iter_key_prefix(owner).collect()
+		}
+
+		/// A CDP with its debt including stability fees up to the current block.
+		pub fn cdp_info(cdp_id: CdpId) -> Option<CdpInfo<T::AccountId, T::Balance>> {
+			let cdp = Cdps::<T>::get(cdp_id)?;
+			let owner = CdpOwner::<T>::get(cdp_id)?;
+			let collateral_type = CollateralTypes::<T>::get(cdp.collateral_type)?;
+			let (dusd_debt, deur_debt, _) = Self::current_cdp_debt(&cdp, &collateral_type);
+			
+			Some(CdpInfo {
+				owner,
+				collateral_type: cdp.collateral_type,
+				collateral: cdp.collateral,
+				dusd_debt,
+				deur_debt,
+			})
+		}
+
+		/// The collateral ratio of a CDP and how much it can still mint or withdraw, as the
+		/// extrinsics would compute them in the current block.
+		pub fn cdp_health(cdp_id: CdpId) -> Result<CdpHealth<T::Balance>, DispatchError> {
+			let cdp = Cdps::<T>::get(cdp_id).ok_or(Error::<T>::CdpNotFound)?;
+			let collateral_type = Self::collateral_type(cdp.collateral_type)?;
+			let price_feed = collateral_type.price_feed;
+			let (dusd_debt, deur_debt, (dusd_fees, deur_fees)) = Self::current_cdp_debt(&cdp, &collateral_type);
+			let has_debt = !dusd_debt.is_zero() || !deur_debt.is_zero();
+			
+			let usd_price = Self::price(price_feed, DebtType::Dusd, PriceSource::Spot);
+			let eur_price = Self::price(price_feed, DebtType::Deur, PriceSource::Spot);
+			ensure!(!usd_price.is_zero() && !eur_price.is_zero(), Error::<T>::PriceNotAvailable);
+			
+			let collateral = cdp.collateral.saturated_into::<u128>();
+			let debt_usd = Self::debt_value_usd(price_feed, PriceSource::Spot, dusd_debt, deur_debt)?;
+			let collateral_ratio = Self::collateral_ratio(
+				price_feed,
+				PriceSource::Spot,
+				cdp.collateral,
+				dusd_debt,
+				deur_debt,
+			)?;
+			// Without the price liquidations are checked against, none can happen
+			let liquidatable = has_debt &&
+				matches!(
+					Self::collateral_ratio(
+						price_feed,
+						Self::liquidation_source(cdp.collateral_type),
+						cdp.collateral,
+						dusd_debt,
+						deur_debt,
+					),
+					Ok(ratio) if ratio < collateral_type.liquidation_ratio as u128
+				);
+			
+			// The collateral price at which the CDP reaches its liquidation ratio
+			let liquidation_price = if has_debt && !collateral.is_zero() {
+				Some(
+					multiply_by_rational_with_rounding(
+						debt_usd,
+						(collateral_type.liquidation_ratio as u128).saturating_mul(PRICE_PRECISION / 10000),
+						collateral,
+						Rounding::Up,
+					)
+					.ok_or(Error::<T>::Overflow)?,
+				)
+			} else {
+				None
+			};
+			
+			// Debt the collateral could back at the minimum collateral ratio
+			let collateral_value_usd = FixedU128::from_inner(usd_price)
+				.checked_mul_int(collateral)
+				.ok_or(Error::<T>::Overflow)?;
+			let max_debt_usd = multiply_by_rational_with_rounding(
+				collateral_value_usd,
+				10000u128,
+				collateral_type.min_collateral_ratio as u128,
+				Rounding::Down,
+			)
+			.ok_or(Error::<T>::Overflow)?;
+			let headroom_usd = max_debt_usd
+				.saturating_sub(debt_usd)
+				.min(Self::collateral_debt_headroom(cdp.collateral_type, &collateral_type)?);
+			let max_mint = |currency: DebtType| -> Result<T::Balance, Error<T>> {
+				let amount = match currency {
+					DebtType::Dusd => headroom_usd,
+					DebtType::Deur => {
+						multiply_by_rational_with_rounding(headroom_usd, eur_price, usd_price, Rounding::Down)
+							.ok_or(Error::<T>::Overflow)?
+					},
+				};
+				let amount = match GlobalDebtCeilings::<T>::get(currency) {
+					Some(ceiling) => {
+						let total = Self::collateral_backed_debt(currency);
+						amount.min(ceiling.saturating_sub(total).saturated_into())
+					},
+					None => amount,
+				};
+				
+				Ok(amount.saturated_into())
+			};
+			
+			// Collateral needed to keep the debt at the minimum collateral ratio
+			let max_withdraw = if has_debt {
+				let required_value = multiply_by_rational_with_rounding(
+					debt_usd,
+					collateral_type.min_collateral_ratio as u128,
+					10000u128,
+					Rounding::Up,
+				)
+				.ok_or(Error::<T>::Overflow)?;
+				let required = Self::collateral_amount(required_value, usd_price, Rounding::Up)?;
+				collateral.saturating_sub(required).saturated_into()
+			} else {
+				cdp.collateral
+			};
+			
+			Ok(CdpHealth {
+				collateral_ratio: has_debt.then_some(collateral_ratio),
+				liquidatable,
+				liquidation_price,
+				max_mint_dusd: max_mint(DebtType::Dusd)?,
+				max_mint_deur: max_mint(DebtType::Deur)?,
+				max_withdraw,
+				dusd_fees,
+				deur_fees,
+			})
+		}
+
+		/// The CDPs that can be liquidated among the `limit` ids from `start`, ordered from the
+		/// largest shortfall to the smallest.
+		///
+		/// At most `MAX_LIQUIDATABLE_SCAN` ids are checked, so callers page through all CDPs by
+		/// moving `start` on until it reaches `next_cdp_id`. Debt includes stability fees up to
+		/// the current block, as liquidations compound them before checking the collateral ratio.
+		pub fn liquidatable_cdps(start: CdpId, limit: u32) -> Vec<LiquidatableCdp> {
+			let end = start
+				.saturating_add(limit.min(MAX_LIQUIDATABLE_SCAN) as u64)
+				.min(NextCdpId::<T>::get());
+			let mut cdps: Vec<_> = (start..end)
+				.filter_map(|id| Cdps::<T>::get(id).map(|cdp| (id, cdp)))
+				.filter_map(|(id, cdp)| Self::liquidation_shortfall(id, &cdp).ok().flatten())
+				.collect();
+			cdps.sort_by(|a, b| b.shortfall.cmp(&a.shortfall).then(a.id.cmp(&b.id)));
+			
+			cdps
+		}
+
+		/// The id the next CDP opened will get. Every CDP that exists has a lower id.
+		pub fn next_cdp_id() -> CdpId {
+			NextCdpId::<T>::get()
+		}
+
+		/// Up to `MaxAutoLiquidations` unsafe CDPs among the next `LiquidationScanBatch` ids,
+		/// continuing from where the offchain worker of an earlier block stopped, so that each
+		/// worker does bounded work and every CDP is checked in turn.
+		fn offchain_liquidation_candidates() -> Vec<CdpId> {
+			let cursor_ref = StorageValueRef::persistent(OFFCHAIN_SCAN_CURSOR);
+			let next_id = NextCdpId::<T>::get();
+			let mut cursor = cursor_ref.get::<CdpId>().ok().flatten().unwrap_or_default();
+			let mut candidates = Vec::new();
+			for _ in 0..(T::LiquidationScanBatch::get() as u64).min(next_id) {
+				if candidates.len() as u32 >= T::MaxAutoLiquidations::get() {
+					break;
+				}
+				if cursor >= next_id {
+					cursor = 0;
+				}
+				let unsafe_cdp = Cdps::<T>::get(cursor)
+					.is_some_and(|cdp| matches!(Self::is_liquidatable(&cdp), Ok(true)));
+				if unsafe_cdp {
+					candidates.push(cursor);
+				}
+				cursor = cursor.saturating_add(1);
+			}
+			cursor_ref.set(&cursor);
+			
+			candidates
+		}
+
+		/// How far `cdp` has fallen below the liquidation ratio, or `None` if it has not.
+		fn liquidation_shortfall(id: CdpId, cdp: &Cdp<T::Balance>) -> Result<Option<LiquidatableCdp>, Error<T>> {
+			let collateral_type = Self::collateral_type(cdp.collateral_type)?;
+			let (dusd_debt, deur_debt, _) = Self::current_cdp_debt(cdp, &collateral_type);
+			if dusd_debt.is_zero() && deur_debt.is_zero() {
+				return Ok(None);
+			}
+			
+			let price_feed = collateral_type.price_feed;
+			let source = Self::liquidation_source(cdp.collateral_type);
+			let collateral_ratio = Self::collateral_ratio(price_feed, source, cdp.collateral, dusd_debt, deur_debt)?;
+			if collateral_ratio >= collateral_type.liquidation_ratio as u128 {
+				return Ok(None);
+			}
+			
+			let debt_usd = Self::debt_value_usd(price_feed, source, dusd_debt, deur_debt)?;
+			let required_value = multiply_by_rational_with_rounding(
+				debt_usd,
+				collateral_type.liquidation_ratio as u128,
+				10000u128,
+				Rounding::Up,
+			)
+			.ok_or(Error::<T>::Overflow)?;
+			let collateral_value = FixedU128::from_inner(Self::price(price_feed, DebtType::Dusd, source))
+				.checked_mul_int(cdp.collateral.saturated_into::<u128>())
+				.ok_or(Error::<T>::Overflow)?;
+			
+			Ok(Some(LiquidatableCdp {
+				id,
+				collateral_ratio,
+				shortfall: required_value.saturating_sub(collateral_value),
+			}))
+		}
+
+		/// The dUSD and dEUR owed by `cdp` with stability fees compounded up to the current
+		/// block, and the part of each accrued since rates were last compounded.
+		fn current_cdp_debt(
+			cdp: &Cdp<T::Balance>,
+			collateral_type: &CollateralType<T::Balance>,
+		) -> (T::Balance, T::Balance, (T::Balance, T::Balance)) {
+			let (stored_dusd, stored_deur) = Self::cdp_debt(cdp);
+			let debt = |normalized, currency| {
+				Self::denormalize_debt(normalized, Self::current_rate(cdp.collateral_type, currency, collateral_type))
+			};
+			let dusd_debt = debt(cdp.dusd_debt, DebtType::Dusd);
+			let deur_debt = debt(cdp.deur_debt, DebtType::Deur);
+			
+			(
+				dusd_debt,
+				deur_debt,
+				(dusd_debt.saturating_sub(stored_dusd), deur_debt.saturating_sub(stored_deur)),
+			)
+		}
+
+		/// The USD value of debt collateral type `id` can still take on before its ceiling.
+		fn collateral_debt_headroom(
+			id: CollateralId,
+			collateral_type: &CollateralType<T::Balance>,
+		) -> Result<u128, Error<T>> {
+			let (dusd_debt, deur_debt) = Self::collateral_type_debt(id);
+			let debt_value = Self::debt_value_usd(collateral_type.price_feed, PriceSource::Spot, dusd_debt, deur_debt)?;
+			
+			Ok(collateral_type.debt_ceiling.saturated_into::<u128>().saturating_sub(debt_value))
+		}
+
+		fn ensure_cdp_owner(who: &T::AccountId, cdp_id: CdpId) -> Result<(), Error<T>> {
+			let owner = CdpOwner::<T>::get(cdp_id).ok_or(Error::<T>::CdpNotFound)?;
+			ensure!(&owner == who, Error::<T>::NotAuthorized);
+			
+			Ok(())
+		}
+
+		/// Check that `who` owns or manages a CDP, returning its owner.
+		fn ensure_cdp_access(who: &T::AccountId, cdp_id: CdpId) -> Result<T::AccountId, Error<T>> {
+			let owner = CdpOwner::<T>::get(cdp_id).ok_or(Error::<T>::CdpNotFound)?;
+			ensure!(
+				&owner == who || CdpManagers::<T>::contains_key(cdp_id, who),
+				Error::<T>::NotAuthorized
+			);
+			
+			Ok(owner)
+		}
+
+		/// Store a new CDP under the next id and index it under `owner`.
+		fn insert_cdp(owner: &T::AccountId, cdp: &Cdp<T::Balance>) -> CdpId {
+			let cdp_id = NextCdpId::<T>::get();
+			
+			Cdps::<T>::insert(cdp_id, cdp);
+			CdpOwner::<T>::insert(cdp_id, owner);
+			OwnerCdps::<T>::insert(owner, cdp_id, ());
+			NextCdpId::<T>::put(cdp_id.saturating_add(1));
+			
+			cdp_id
+		}
+
+		/// Remove a CDP together with its owner index entry and managers.
+		fn remove_cdp(cdp_id: CdpId, owner: &T::AccountId) {
+			Cdps::<T>::remove(cdp_id);
+			CdpOwner::<T>::remove(cdp_id);
+			OwnerCdps::<T>::remove(owner, cdp_id);
+			let _ = CdpManagers::<T>::clear_prefix(cdp_id, u32::MAX, None);
+			LiquidationQueue::<T>::remove(cdp_id);
+		}
+
+		/// The parameters of a registered collateral type.
+		pub fn collateral_type(id: CollateralId) -> Result<CollateralType<T::Balance>, Error<T>> {
+			CollateralTypes::<T>::get(id).ok_or(Error::<T>::UnknownCollateralType)
+		}
+
+		fn ensure_valid_risk_parameters(collateral_type: &CollateralType<T::Balance>) -> Result<(), Error<T>> {
+			ensure!(
+				collateral_type.liquidation_ratio >= 10000 &&
+					collateral_type.liquidation_ratio <= collateral_type.min_collateral_ratio,
+				Error::<T>::InvalidRiskParameters
+			);
+			
+			Ok(())
+		}
+
+		/// Debt must either be fully repaid or stay at least at the dust limit of its collateral
+		/// type, so that every CDP remains worth liquidating.
+		fn ensure_not_dust(
+			collateral_type: &CollateralType<T::Balance>,
+			dusd_debt: T::Balance,
+			deur_debt: T::Balance,
+		) -> DispatchResult {
+			if collateral_type.dust.is_zero() || (dusd_debt.is_zero() && deur_debt.is_zero()) {
+				return Ok(());
+			}
+			
+			let debt_value = Self::debt_value_usd(collateral_type.price_feed, PriceSource::Spot, dusd_debt, deur_debt)?;
+			ensure!(debt_value >= collateral_type.dust.saturated_into::<u128>(), Error::<T>::DebtBelowDust);
+			
+			Ok(())
+		}
+
+		/// Minting `amount` more of `currency` against collateral type `id` must stay within
+		/// both the global ceiling of the stablecoin and the ceiling of the collateral type.
+		fn ensure_within_debt_ceilings(
+			id: CollateralId,
+			collateral_type: &CollateralType<T::Balance>,
+			currency: DebtType,
+			amount: T::Balance,
+		) -> DispatchResult {
+			if let Some(ceiling) = GlobalDebtCeilings::<T>::get(currency) {
+				let total = Self::collateral_backed_debt(currency);
+				ensure!(total.saturating_add(amount) <= ceiling, Error::<T>::GlobalDebtCeilingExceeded);
+			}
+			
+			let (mut dusd_debt, mut deur_debt) = Self::collateral_type_debt(id);
+			match currency {
+				DebtType::Dusd => dusd_debt = dusd_debt.saturating_add(amount),
+				DebtType::Deur => deur_debt = deur_debt.saturating_add(amount),
+			}
+			let debt_value = Self::debt_value_usd(collateral_type.price_feed, PriceSource::Spot, dusd_debt, deur_debt)?;
+			ensure!(
+				debt_value <= collateral_type.debt_ceiling.saturated_into::<u128>(),
+				Error::<T>::CollateralDebtCeilingExceeded
+			);
+			
+			Ok(())
+		}
+
+		/// The dUSD and dEUR owed by all CDPs of collateral type `id` at the current rates.
+		pub fn collateral_type_debt(id: CollateralId) -> (T::Balance, T::Balance) {
+			let debt = |currency: DebtType| {
+				Self::denormalize_debt(NormalizedDebt::<T>::get(id, currency), Rates::<T>::get(id, currency))
+			};
+			
+			(debt(DebtType::Dusd), debt(DebtType::Deur))
+		}
+
+		/// The price of one unit of collateral on `feed`, quoted in `currency`, or zero if the
+		/// oracle has none. Prices are frozen at the values recorded when the system was caged.
+		pub fn price(feed: PriceFeed, currency: DebtType, source: PriceSource) -> u128 {
+			if Self::is_caged() {
+				return CagePrices::<T>::get(feed, currency);
+			}
+			
+			let pair = Self::asset_pair(feed, currency);
+			let data = match source {
+				PriceSource::Spot => T::Oracle::price(pair),
+				PriceSource::Delayed => T::Oracle::delayed_price(pair),
+				PriceSource::Twap(window) => match T::Oracle::twap(pair, window) {
+					Some(price) => return price,
+					None => T::Oracle::delayed_price(pair),
+				},
+			};
+			data.map(|data| data.price).unwrap_or_default()
+		}
+
+		/// The price liquidations of a collateral type are checked against: its time-weighted
+		/// average if it has opted into one, otherwise the delayed oracle price.
+		pub fn liquidation_source(id: CollateralId) -> PriceSource {
+			TwapWindows::<T>::get(id).map_or(PriceSource::Delayed, PriceSource::Twap)
+		}
+
+		fn asset_pair(feed: PriceFeed, currency: DebtType) -> AssetPair {
+			match (feed, currency) {
+				(PriceFeed::Orm, DebtType::Dusd) => AssetPair::OrmUsd,
+				(PriceFeed::Orm, DebtType::Deur) => AssetPair::OrmEur,
+			}
+		}
+
+		/// New debt and collateral withdrawals must be backed by spot prices no older than
+		/// `MaxPriceAge`.
+		fn ensure_fresh_prices(feed: PriceFeed) -> DispatchResult {
+			let now: u64 = T::UnixTime::now().as_millis().saturated_into();
+			for currency in [DebtType::Dusd, DebtType::Deur] {
+				let data = T::Oracle::price(Self::asset_pair(feed, currency)).ok_or(Error::<T>::PriceNotAvailable)?;
+				ensure!(
+					now.saturating_sub(data.timestamp) <= T::MaxPriceAge::get(),
+					Error::<T>::StalePrice
+				);
+			}
+			
+			Ok(())
+		}
+
+		/// Minting, and withdrawing collateral from CDPs with debt, are paused while the circuit
+		/// breaker of either price on `feed` is tripped.
+		fn ensure_not_halted(feed: PriceFeed) -> DispatchResult {
+			for currency in [DebtType::Dusd, DebtType::Deur] {
+				ensure!(
+					!T::Oracle::is_halted(Self::asset_pair(feed, currency)),
+					Error::<T>::PriceFeedHalted
+				);
+			}
+			
+			Ok(())
+		}
+
+		/// Lock `amount` of collateral belonging to `who` in the CDP system.
+		fn lock_collateral(asset: CollateralAsset, who: &T::AccountId, amount: T::Balance) -> DispatchResult {
+			match asset {
+				CollateralAsset::Native => T::Currency::reserve(who, amount),
+				CollateralAsset::OriumToken => T::OriumToken::transfer(who, &Self::vault_account(), amount),
+			}
+		}
+
+		/// Return `amount` of locked collateral to `who`.
+		fn release_collateral(asset: CollateralAsset, who: &T::AccountId, amount: T::Balance) -> DispatchResult {
+			if amount.is_zero() {
+				return Ok(());
+			}
+			
+			match asset {
+				CollateralAsset::Native => {
+					T::Currency::unreserve(who, amount);
+					Ok(())
+				},
+				CollateralAsset::OriumToken => T::OriumToken::transfer(&Self::vault_account(), who, amount),
+			}
+		}
+
+		/// Hand `amount` of the collateral locked for `owner` to `to`.
+		fn seize_collateral(
+			asset: CollateralAsset,
+			owner: &T::AccountId,
+			to: &T::AccountId,
+			amount: T::Balance,
+		) -> DispatchResult {
+			if amount.is_zero() {
+				return Ok(());
+			}
+			
+			match asset {
+				CollateralAsset::Native => {
+					T::Currency::repatriate_reserved(owner, to, amount, BalanceStatus::Free)?;
+					Ok(())
+				},
+				CollateralAsset::OriumToken => {
+					if to == &Self::vault_account() {
+						return Ok(());
+					}
+					T::OriumToken::transfer(&Self::vault_account(), to, amount)
+				},
+			}
+		}
+
+		/// Pay out collateral set aside in the vault account during shutdown.
+		fn pay_out_collateral(asset: CollateralAsset, to: &T::AccountId, amount: T::Balance) -> DispatchResult {
+			match asset {
+				CollateralAsset::Native => {
+					T::Currency::transfer(&Self::vault_account(), to, amount, ExistenceRequirement::AllowDeath)
+				},
+				CollateralAsset::OriumToken => T::OriumToken::transfer(&Self::vault_account(), to, amount),
+			}
+		}
+
+		/// The collateral worth `debt` at `price`, rounded in favour of stablecoin holders.
+		fn settlement_collateral(debt: T::Balance, price: u128) -> Result<T::Balance, Error<T>> {
+			if debt.is_zero() {
+				return Ok(Zero::zero());
+			}
+			
+			ensure!(!price.is_zero(), Error::<T>::PriceNotAvailable);
+			
+			let collateral = Self::collateral_amount(debt.saturated_into(), price, Rounding::Up)?;
+			
+			collateral.try_into().map_err(|_| Error::<T>::Overflow)
+		}
+
+		/// Settled collateral of `collateral_type` per unit of a stablecoin's outstanding
+		/// `supply`, with 18 decimals.
+		fn redemption_rate(
+			currency: DebtType,
+			collateral_type: CollateralId,
+			supply: T::Balance,
+		) -> Result<u128, Error<T>> {
+			if supply.is_zero() {
+				return Ok(Zero::zero());
+			}
+			
+			multiply_by_rational_with_rounding(
+				SettledCollateral::<T>::get(currency, collateral_type).saturated_into::<u128>(),
+				PRICE_PRECISION,
+				supply.saturated_into::<u128>(),
+				Rounding::Down,
+			)
+			.ok_or(Error::<T>::Overflow)
+		}
+
+		fn check_collateral_ratio(
+			collateral_type: &CollateralType<T::Balance>,
+			collateral: T::Balance,
+			dusd_debt: T::Balance,
+			deur_debt: T::Balance,
+		) -> Result<bool, Error<T>> {
+			let ratio = Self::collateral_ratio(
+				collateral_type.price_feed,
+				PriceSource::Spot,
+				collateral,
+				dusd_debt,
+				deur_debt,
+			)?;
+			
+			Ok(ratio >= collateral_type.min_collateral_ratio as u128)
+		}
+
+		/// The collateral ratio of a position in basis points (10000 = 100%).
+		///
+		/// A position without debt has an unbounded ratio and reports `u128::MAX`.
+		pub fn collateral_ratio(
+			price_feed: PriceFeed,
+			source: PriceSource,
+			collateral: T::Balance,
+			dusd_debt: T::Balance,
+			deur_debt: T::Balance,
+		) -> Result<u128, Error<T>> {
+			let usd_price = Self::price(price_feed, DebtType::Dusd, source);
+			
+			ensure!(!usd_price.is_zero(), Error::<T>::PriceNotAvailable);
+			
+			let collateral_value_usd = FixedU128::from_inner(usd_price)
+				.checked_mul_int(collateral.saturated_into::<u128>())
+				.ok_or(Error::<T>::Overflow)?;
+			
+			let total_debt_usd = Self::debt_value_usd(price_feed, source, dusd_debt, deur_debt)?;
+			
+			if total_debt_usd.is_zero() {
+				return Ok(u128::MAX);
+			}
+			
+			multiply_by_rational_with_rounding(collateral_value_usd, 10000u128, total_debt_usd, Rounding::Down)
+				.ok_or(Error::<T>::Overflow)
+		}
+
+		/// The combined value of dUSD and dEUR debt, expressed in USD at the cross rate implied
+		/// by `price_feed`. dEUR debt is converted rounding up, so that debt is never understated.
+		fn debt_value_usd(
+			price_feed: PriceFeed,
+			source: PriceSource,
+			dusd_debt: T::Balance,
+			deur_debt: T::Balance,
+		) -> Result<u128, Error<T>> {
+			let usd_price = Self::price(price_feed, DebtType::Dusd, source);
+			let eur_price = Self::price(price_feed, DebtType::Deur, source);
+			
+			ensure!(!usd_price.is_zero(), Error::<T>::PriceNotAvailable);
+			ensure!(!eur_price.is_zero(), Error::<T>::PriceNotAvailable);
+			
+			let dusd_debt_value = dusd_debt.saturated_into::<u128>();
+			// Both prices are of the collateral, so one EUR is worth `usd_price / eur_price` USD
+			let deur_debt_value_usd = multiply_by_rational_with_rounding(
+				deur_debt.saturated_into::<u128>(),
+				usd_price,
+				eur_price,
+				Rounding::Up,
+			)
+			.ok_or(Error::<T>::Overflow)?;
+			
+			dusd_debt_value.checked_add(deur_debt_value_usd).ok_or(Error::<T>::Overflow)
+		}
+
+		/// Whether a position has fallen below the liquidation ratio of its collateral type at the
+		/// price its liquidations are checked against.
+		pub fn is_liquidatable(cdp: &Cdp<T::Balance>) -> Result<bool, Error<T>> {
+			if cdp.dusd_debt.is_zero() && cdp.deur_debt.is_zero() {
+				return Ok(false);
+			}
+			
+			let collateral_type = Self::collateral_type(cdp.collateral_type)?;
+			let (dusd_debt, deur_debt) = Self::cdp_debt(cdp);
+			let ratio = Self::collateral_ratio(
+				collateral_type.price_feed,
+				Self::liquidation_source(cdp.collateral_type),
+				cdp.collateral,
+				dusd_debt,
+				deur_debt,
+			)?;
+			
+			Ok(ratio < collateral_type.liquidation_ratio as u128)
+		}
+
+		/// The collateral of `cdp` worth the given debt plus the liquidation penalty, at the price
+		/// its liquidations are checked against.
+		fn penalized_collateral(
+			cdp: &Cdp<T::Balance>,
+			dusd_debt: T::Balance,
+			deur_debt: T::Balance,
+		) -> Result<u128, Error<T>> {
+			let price_feed = Self::collateral_type(cdp.collateral_type)?.price_feed;
+			let source = Self::liquidation_source(cdp.collateral_type);
+			let usd_price = Self::price(price_feed, DebtType::Dusd, source);
+			
+			ensure!(!usd_price.is_zero(), Error::<T>::PriceNotAvailable);
+			
+			let debt_usd = Self::debt_value_usd(price_feed, source, dusd_debt, deur_debt)?;
+			let debt_with_penalty = FixedU128::from_rational(
+				10000u128.saturating_add(T::LiquidationPenalty::get() as u128),
+				10000u128,
+			)
+			.checked_mul_int(debt_usd)
+			.ok_or(Error::<T>::Overflow)?;
+			
+			Self::collateral_amount(debt_with_penalty, usd_price, Rounding::Down)
+		}
+
+		/// The share of its debt a partial liquidation closes on `cdp`.
+		///
+		/// Closing debt worth `x` seizes `x * (1 + penalty)` of collateral, so the ratio is back at
+		/// `min` once `x = (min * debt - collateral) / (min - 1 - penalty)`. The share is rounded
+		/// up and capped at `CloseFactor`, which it also falls back to when the penalty leaves no
+		/// way to reach `min`.
+		fn partial_liquidation_share(
+			cdp: &Cdp<T::Balance>,
+			ilk: &CollateralType<T::Balance>,
+		) -> Result<Perquintill, Error<T>> {
+			let close_factor = Perquintill::from_rational(T::CloseFactor::get().min(10000) as u128, 10000u128);
+			let source = Self::liquidation_source(cdp.collateral_type);
+			let usd_price = Self::price(ilk.price_feed, DebtType::Dusd, source);
+			
+			ensure!(!usd_price.is_zero(), Error::<T>::PriceNotAvailable);
+			
+			let (dusd_debt, deur_debt) = Self::cdp_debt(cdp);
+			let debt_usd = Self::debt_value_usd(ilk.price_feed, source, dusd_debt, deur_debt)?;
+			let collateral_usd = FixedU128::from_inner(usd_price)
+				.checked_mul_int(cdp.collateral.saturated_into::<u128>())
+				.ok_or(Error::<T>::Overflow)?;
+			
+			// Aim a basis point above the minimum, so that rounding never leaves the CDP just short
+			let target_ratio = (ilk.min_collateral_ratio as u128).saturating_add(1);
+			let margin = target_ratio.saturating_sub(10000u128.saturating_add(T::LiquidationPenalty::get() as u128));
+			if margin.is_zero() {
+				return Ok(close_factor);
+			}
+			
+			let shortfall = target_ratio
+				.checked_mul(debt_usd)
+				.ok_or(Error::<T>::Overflow)?
+				.saturating_sub(collateral_usd.checked_mul(10000).ok_or(Error::<T>::Overflow)?);
+			let scaled_debt = margin.checked_mul(debt_usd).ok_or(Error::<T>::Overflow)?;
+			if shortfall >= scaled_debt {
+				return Ok(close_factor);
+			}
+			
+			let share = Perquintill::from_rational_with_rounding(shortfall, scaled_debt, Rounding::Up)
+				.map_err(|_| Error::<T>::Overflow)?;
+			
+			Ok(share.min(close_factor))
+		}
+
+		/// The normalized debt of `currency` that repaying `amount` of the `debt` owed by `cdp`
+		/// clears. Repaying all of it clears any rounding dust as well.
+		fn normalized_repayment(
+			cdp: &Cdp<T::Balance>,
+			currency: DebtType,
+			amount: T::Balance,
+			debt: T::Balance,
+		) -> Result<T::Balance, Error<T>> {
+			let normalized = match currency {
+				DebtType::Dusd => cdp.dusd_debt,
+				DebtType::Deur => cdp.deur_debt,
+			};
+			if amount >= debt {
+				return Ok(normalized);
+			}
+			
+			let rate = Rates::<T>::get(cdp.collateral_type, currency);
+			
+			Ok(Self::normalize_debt(amount, rate, Rounding::Down)?.min(normalized))
+		}
+
+		fn open_auction(
+			owner: &T::AccountId,
+			collateral_type: CollateralId,
+			ilk: &CollateralType<T::Balance>,
+			currency: DebtType,
+			lot: T::Balance,
+			debt: T::Balance,
+		) -> DispatchResult {
+			let tab = debt.saturating_add(
+				debt.saturating_mul(T::LiquidationPenalty::get().into()) / 10000u32.into(),
+			);
+			let top = Self::auction_start_price(collateral_type, ilk.price_feed, currency)?;
+			let id = NextAuctionId::<T>::get();
+			
+			let auction = CollateralAuction {
+				owner: owner.clone(),
+				collateral_type,
+				currency,
+				lot,
+				tab,
+				debt,
+				top,
+				started_at: frame_system::Pallet::<T>::block_number().saturated_into(),
+			};
+			
+			Auctions::<T>::insert(id, &auction);
+			NextAuctionId::<T>::put(id.saturating_add(1));
+			
+			Self::deposit_event(Event::AuctionStarted {
+				id,
+				owner: owner.clone(),
+				collateral_type,
+				currency,
+				lot,
+				tab,
+				top,
+			});
+			
+			Ok(())
+		}
+
+		/// Check up to `LiquidationScanBatch` CDPs left to scan since the last price update,
+		/// queueing those below their liquidation ratio.
+		fn scan_for_liquidations(meter: &mut WeightMeter) {
+			// Cursor, pending count and next id, and then a CDP, its collateral type, rates,
+			// TWAP window and two prices for every check
+			let check_weight = T::DbWeight::get().reads_writes(8, 1);
+			if meter.try_consume(T::DbWeight::get().reads_writes(3, 2)).is_err() {
+				return;
+			}
+			
+			let next_id = NextCdpId::<T>::get();
+			let mut cursor = ScanCursor::<T>::get();
+			let mut pending = PendingScan::<T>::get();
+			let mut checked = 0;
+			while !pending.is_zero() &&
+				checked < T::LiquidationScanBatch::get() &&
+				meter.try_consume(check_weight).is_ok()
+			{
+				if cursor >= next_id {
+					cursor = 0;
+				}
+				if let Some(cdp) = Cdps::<T>::get(cursor) {
+					if matches!(Self::is_liquidatable(&cdp), Ok(true)) {
+						LiquidationQueue::<T>::insert(cursor, true);
+					}
+				}
+				cursor = cursor.saturating_add(1);
+				pending = pending.saturating_sub(1);
+				checked += 1;
+			}
+			
+			ScanCursor::<T>::put(cursor);
+			PendingScan::<T>::put(pending);
+		}
+
+		/// Start the auctions of up to `MaxAutoLiquidations` queued CDPs. CDPs that are no longer
+		/// unsafe are dropped from the queue.
+		fn start_queued_auctions(meter: &mut WeightMeter) {
+			let auction_weight =
+				T::WeightInfo::liquidate_unsigned().saturating_add(T::DbWeight::get().reads_writes(1, 1));
+			// Only the first auction of the block brings stability fees up to date
+			let mut accrual_weight = Self::accrual_weight();
+			
+			for _ in 0..T::MaxAutoLiquidations::get() {
+				if meter.try_consume(auction_weight.saturating_add(accrual_weight)).is_err() {
+					return;
+				}
+				accrual_weight = Weight::zero();
+				let Some(cdp_id) = LiquidationQueue::<T>::iter_keys().next() else { return };
+				
+				LiquidationQueue::<T>::remove(cdp_id);
+				let _ = with_storage_layer(|| Self::do_start_auction(cdp_id));
+			}
+		}
+
+		/// Close an unsafe CDP and auction off its collateral, one auction per debt type.
+		fn do_start_auction(cdp_id: CdpId) -> DispatchResult {
+			Self::ensure_live()?;
+			
+			Self::accrue_stability_fees()?;
+			
+			let cdp = Cdps::<T>::get(cdp_id).ok_or(Error::<T>::CdpNotFound)?;
+			let owner = CdpOwner::<T>::get(cdp_id).ok_or(Error::<T>::CdpNotFound)?;
+			let ilk = Self::collateral_type(cdp.collateral_type)?;
+			
+			ensure!(Self::is_liquidatable(&cdp)?, Error::<T>::CdpNotLiquidatable);
+			
+			let (dusd_debt, deur_debt) = Self::cdp_debt(&cdp);
+			let source = Self::liquidation_source(cdp.collateral_type);
+			let dusd_value = Self::debt_value_usd(ilk.price_feed, source, dusd_debt, Zero::zero())?;
+			let total_value = Self::debt_value_usd(ilk.price_feed, source, dusd_debt, deur_debt)?;
+			
+			let dusd_lot: T::Balance = Perquintill::from_rational(dusd_value, total_value)
+				.mul_floor(cdp.collateral.saturated_into::<u128>())
+				.saturated_into();
+			let deur_lot = cdp.collateral.saturating_sub(dusd_lot);
+			
+			if !dusd_debt.is_zero() {
+				Self::open_auction(&owner, cdp.collateral_type, &ilk, DebtType::Dusd, dusd_lot, dusd_debt)?;
+			}
+			if !deur_debt.is_zero() {
+				Self::open_auction(&owner, cdp.collateral_type, &ilk, DebtType::Deur, deur_lot, deur_debt)?;
+			}
+			
+			Self::remove_cdp(cdp_id, &owner);
+			TotalCollateral::<T>::mutate(cdp.collateral_type, |total| *total = total.saturating_sub(cdp.collateral));
+			NormalizedDebt::<T>::mutate(cdp.collateral_type, DebtType::Dusd, |total| {
+				*total = total.saturating_sub(cdp.dusd_debt)
+			});
+			NormalizedDebt::<T>::mutate(cdp.collateral_type, DebtType::Deur, |total| {
+				*total = total.saturating_sub(cdp.deur_debt)
+			});
+			
+			Ok(())
+		}
+
+		/// Close an auction, returning unsold collateral to the CDP owner.
+		fn finish_auction(
+			id: AuctionId,
+			auction: &CollateralAuction<T::AccountId, T::Balance>,
+			ilk: &CollateralType<T::Balance>,
+		) -> DispatchResult {
+			Self::release_collateral(ilk.asset, &auction.owner, auction.lot)?;
+			Auctions::<T>::remove(id);
+			
+			if !auction.debt.is_zero() {
+				T::Ledger::on_bad_debt(auction.currency, auction.debt);
+			}
+			
+			Self::deposit_event(Event::AuctionFinished {
+				id,
+				collateral_returned: auction.lot,
+				debt_remaining: auction.debt,
+			});
+			
+			Ok(())
+		}
+
+		/// The current price of an auction and whether it has gone stale.
+		pub fn auction_status(auction: &CollateralAuction<T::AccountId, T::Balance>) -> (u128, bool) {
+			let now: u32 = frame_system::Pallet::<T>::block_number().saturated_into();
+			let elapsed = now.saturating_sub(auction.started_at);
+			let price = T::AuctionCurve::price(auction.top, elapsed);
+			
+			let floor = auction.top.saturating_mul(T::AuctionResetRatio::get() as u128) / 10000;
+			let needs_reset =
+				elapsed > T::AuctionResetDuration::get() || price < floor || price.is_zero();
+			
+			(price, needs_reset)
+		}
+
+		/// The liquidation price of collateral in `currency`, marked up by the start buffer.
+		fn auction_start_price(
+			id: CollateralId,
+			price_feed: PriceFeed,
+			currency: DebtType,
+		) -> Result<u128, Error<T>> {
+			let price = Self::price(price_feed, currency, Self::liquidation_source(id));
+			
+			ensure!(!price.is_zero(), Error::<T>::PriceNotAvailable);
+			
+			FixedU128::from_rational(T::AuctionStartBuffer::get() as u128, 10000u128)
+				.checked_mul_int(price)
+				.ok_or(Error::<T>::Overflow)
+		}
+
+		/// The stablecoin value of `collateral` at `price`.
+		fn collateral_value(collateral: T::Balance, price: u128) -> Result<T::Balance, Error<T>> {
+			let value = multiply_by_rational_with_rounding(
+				collateral.saturated_into::<u128>(),
+				price,
+				PRICE_PRECISION,
+				Rounding::Up,
+			)
+			.ok_or(Error::<T>::Overflow)?;
+			
+			value.try_into().map_err(|_| Error::<T>::Overflow)
+		}
+
+		/// The amount of collateral worth `value` stablecoin at `price`.
+		fn collateral_amount(value: u128, price: u128, rounding: Rounding) -> Result<u128, Error<T>> {
+			ensure!(!price.is_zero(), Error::<T>::PriceNotAvailable);
+			
+			multiply_by_rational_with_rounding(value, PRICE_PRECISION, price, rounding).ok_or(Error::<T>::Overflow)
+		}
+
+		/// Burn stablecoin from `who` against outstanding system debt, on behalf of `issuer`.
+		pub fn burn_debt(
+			issuer: Origin<T>,
+			currency: DebtType,
+			who: &T::AccountId,
+			amount: T::Balance,
+		) -> DispatchResult {
+			match currency {
+				DebtType::Dusd => {
+					T::DusdMinter::burn(issuer, who, amount)?;
+					TotalDusdDebt::<T>::mutate(|total| *total = total.saturating_sub(amount));
+				},
+				DebtType::Deur => {
+					T::DeurMinter::burn(issuer, who, amount)?;
+					TotalDeurDebt::<T>::mutate(|total| *total = total.saturating_sub(amount));
+				},
+			}
+			
+			Ok(())
+		}
+
+		/// Issue stablecoin to `who` against new system debt that is backed outside of CDPs, on
+		/// behalf of `issuer`.
+		pub fn mint_debt(
+			issuer: Origin<T>,
+			currency: DebtType,
+			who: &T::AccountId,
+			amount: T::Balance,
+		) -> DispatchResult {
+			if amount.is_zero() {
+				return Ok(());
+			}
+			
+			match currency {
+				DebtType::Dusd => {
+					T::DusdMinter::mint(issuer, who, amount)?;
+					TotalDusdDebt::<T>::mutate(|total| *total = total.saturating_add(amount));
+				},
+				DebtType::Deur => {
+					T::DeurMinter::mint(issuer, who, amount)?;
+					TotalDeurDebt::<T>::mutate(|total| *total = total.saturating_add(amount));
+				},
+			}
+			
+			Ok(())
+		}
+
+		/// Issue `amount` of stablecoin to `who` and `fee` to the surplus account, against
+		/// reserves held outside of CDPs that back both, on behalf of `issuer`.
+		pub fn mint_reserve_backed(
+			issuer: Origin<T>,
+			currency: DebtType,
+			who: &T::AccountId,
+			amount: T::Balance,
+			fee: T::Balance,
+		) -> DispatchResult {
+			Self::mint_debt(issuer.clone(), currency, who, amount)?;
+			Self::mint_surplus(issuer, currency, fee)?;
+			ReserveBackedDebt::<T>::mutate(currency, |total| {
+				*total = total.saturating_add(amount).saturating_add(fee)
+			});
+			
+			Ok(())
+		}
+
+		/// Burn stablecoin from `who` against the reserves it is exchanged for, on behalf of
+		/// `issuer`.
+		pub fn burn_reserve_backed(
+			issuer: Origin<T>,
+			currency: DebtType,
+			who: &T::AccountId,
+			amount: T::Balance,
+		) -> DispatchResult {
+			Self::burn_debt(issuer, currency, who, amount)?;
+			ReserveBackedDebt::<T>::mutate(currency, |total| *total = total.saturating_sub(amount));
+			
+			Ok(())
+		}
+
+		/// Burn `amount` of stablecoin held by the surplus account against outstanding system
+		/// debt, on behalf of `issuer`.
+		///
+		/// Surplus pools the fees of CDPs with those of swaps against reserves, so the burn also
+		/// lowers `ReserveBackedDebt` by the share of all debt that is reserve-backed, keeping it
+		/// in step with the total.
+		pub fn burn_surplus(issuer: Origin<T>, currency: DebtType, amount: T::Balance) -> DispatchResult {
+			let total = match currency {
+				DebtType::Dusd => TotalDusdDebt::<T>::get(),
+				DebtType::Deur => TotalDeurDebt::<T>::get(),
+			};
+			let reserve_backed: T::Balance = multiply_by_rational_with_rounding(
+				amount.saturated_into(),
+				ReserveBackedDebt::<T>::get(currency).saturated_into(),
+				total.saturated_into(),
+				Rounding::Down,
+			)
+			.unwrap_or_default()
+			.saturated_into();
+			
+			Self::burn_debt(issuer, currency, &Self::surplus_account(), amount)?;
+			ReserveBackedDebt::<T>::mutate(currency, |total| *total = total.saturating_sub(reserve_backed));
+			
+			Ok(())
+		}
+
+		/// The `currency` owed to the system that is backed by CDP collateral rather than by
+		/// reserves.
+		pub fn collateral_backed_debt(currency: DebtType) -> T::Balance {
+			let total = match currency {
+				DebtType::Dusd => TotalDusdDebt::<T>::get(),
+				DebtType::Deur => TotalDeurDebt::<T>::get(),
+			};
+			
+			total.saturating_sub(ReserveBackedDebt::<T>::get(currency))
+		}
+
+		/// Issue stablecoin to `to` that nothing backs, such as savings interest. It is recorded
+		/// as bad debt, which the surplus from stability fees heals.
+		pub fn issue_unbacked(
+			issuer: Origin<T>,
+			currency: DebtType,
+			to: &T::AccountId,
+			amount: T::Balance,
+		) -> DispatchResult {
+			if amount.is_zero() {
+				return Ok(());
+			}
+			
+			Self::mint_debt(issuer, currency, to, amount)?;
+			T::Ledger::on_bad_debt(currency, amount);
+			
+			Ok(())
+		}
+
+		/// Issue stablecoin to the surplus account as income of the system, such as swap fees.
+		pub fn mint_surplus(issuer: Origin<T>, currency: DebtType, amount: T::Balance) -> DispatchResult {
+			if amount.is_zero() {
+				return Ok(());
+			}
+			
+			Self::mint_debt(issuer, currency, &Self::surplus_account(), amount)?;
+			T::Ledger::on_surplus(currency, amount);
+			
+			Ok(())
+		}
+
+		/// Put stablecoin burned as a fee back into circulation in the surplus account, leaving
+		/// the supply matching the debt recorded here.
+		pub fn reissue_to_surplus(currency: DebtType, amount: T::Balance) -> DispatchResult {
+			if amount.is_zero() {
+				return Ok(());
+			}
+			
+			let surplus = Self::surplus_account();
+			match currency {
+				DebtType::Dusd => T::DusdMinter::mint(Self::issuer(), &surplus, amount)?,
+				DebtType::Deur => T::DeurMinter::mint(Self::issuer(), &surplus, amount)?,
+			}
+			T::Ledger::on_surplus(currency, amount);
+			
+			Ok(())
+		}
+
+		/// Move stablecoin from `who` into the surplus account.
+		pub fn transfer_to_surplus(
+			issuer: Origin<T>,
+			currency: DebtType,
+			who: &T::AccountId,
+			amount: T::Balance,
+		) -> DispatchResult {
+			if amount.is_zero() {
+				return Ok(());
+			}
+			
+			Self::transfer_stablecoin(issuer, currency, who, &Self::surplus_account(), amount)?;
+			T::Ledger::on_surplus(currency, amount);
+			
+			Ok(())
+		}
+
+		/// Move stablecoin between accounts on behalf of `issuer` without changing its supply.
+		pub fn transfer_stablecoin(
+			issuer: Origin<T>,
+			currency: DebtType,
+			from: &T::AccountId,
+			to: &T::AccountId,
+			amount: T::Balance,
+		) -> DispatchResult {
+			match currency {
+				DebtType::Dusd => T::DusdMinter::transfer(issuer, from, to, amount),
+				DebtType::Deur => T::DeurMinter::transfer(issuer, from, to, amount),
+			}
+		}
+
+		/// The account holding stablecoin issued as stability fees.
+		pub fn surplus_account() -> T::AccountId {
+			T::PalletId::get().into_account_truncating()
+		}
+
+		/// The origin the engine issues stablecoin with, see [`EnsureIssuer`].
+		pub fn issuer() -> Origin<T> {
+			RawOrigin::Issuer(Self::surplus_account())
+		}
+
+		/// The account holding token collateral locked in CDPs and collateral set aside at
+		/// shutdown.
+		pub fn vault_account() -> T::AccountId {
+			T::PalletId::get().into_sub_account_truncating(b"vault")
+		}
+
+		/// The dUSD and dEUR owed by `cdp` at the current rates of its collateral type.
+		pub fn cdp_debt(cdp: &Cdp<T::Balance>) -> (T::Balance, T::Balance) {
+			(
+				Self::denormalize_debt(cdp.dusd_debt, Rates::<T>::get(cdp.collateral_type, DebtType::Dusd)),
+				Self::denormalize_debt(cdp.deur_debt, Rates::<T>::get(cdp.collateral_type, DebtType::Deur)),
+			)
+		}
+
+		fn denormalize_debt(normalized: T::Balance, rate: FixedU128) -> T::Balance {
+			rate.saturating_mul_int(normalized)
+		}
+
+		fn normalize_debt(
+			amount: T::Balance,
+			rate: FixedU128,
+			rounding: Rounding,
+		) -> Result<T::Balance, Error<T>> {
+			let normalized = multiply_by_rational_with_rounding(
+				amount.saturated_into::<u128>(),
+				FixedU128::DIV,
+				rate.into_inner(),
+				rounding,
+			)
+			.ok_or(Error::<T>::Overflow)?;
+			
+			Ok(normalized.saturated_into())
+		}
+
+		/// The most `accrue_stability_fees` can weigh, with every collateral type registered.
+		pub fn accrual_weight() -> Weight {
+			T::WeightInfo::accrue_stability_fees(T::MaxCollateralTypes::get())
+		}
+
+		/// Bring the rate accumulators of every collateral type up to the current block.
+		///
+		/// The growth in outstanding debt is issued to the surplus account, so the stablecoin
+		/// supply keeps matching `TotalDusdDebt` and `TotalDeurDebt`.
+		pub fn accrue_stability_fees() -> DispatchResult {
+			// Debt stops growing once the system is caged
+			if Self::is_caged() {
+				return Ok(());
+			}
+			
+			let now: u32 = frame_system::Pallet::<T>::block_number().saturated_into();
+			let Some(last) = RateUpdatedAt::<T>::get() else {
+				RateUpdatedAt::<T>::put(now);
+				return Ok(());
+			};
+			
+			if now <= last {
+				return Ok(());
+			}
+			
+			let mut dusd_fee: T::Balance = Zero::zero();
+			let mut deur_fee: T::Balance = Zero::zero();
+			for (id, collateral_type) in CollateralTypes::<T>::iter() {
+				let growth = Self::rate_growth(&collateral_type, now.saturating_sub(last));
+				
+				dusd_fee = dusd_fee.saturating_add(Self::compound_rate(id, DebtType::Dusd, growth));
+				deur_fee = deur_fee.saturating_add(Self::compound_rate(id, DebtType::Deur, growth));
+			}
+			RateUpdatedAt::<T>::put(now);
+			
+			if dusd_fee.is_zero() && deur_fee.is_zero() {
+				return Ok(());
+			}
+			
+			let surplus = Self::surplus_account();
+			T::DusdMinter::mint(Self::issuer(), &surplus, dusd_fee)?;
+			T::DeurMinter::mint(Self::issuer(), &surplus, deur_fee)?;
+			TotalDusdDebt::<T>::mutate(|total| *total = total.saturating_add(dusd_fee));
+			TotalDeurDebt::<T>::mutate(|total| *total = total.saturating_add(deur_fee));
+			T::Ledger::on_surplus(DebtType::Dusd, dusd_fee);
+			T::Ledger::on_surplus(DebtType::Deur, deur_fee);
+			
+			Self::deposit_event(Event::StabilityFeeAccrued { dusd_fee, deur_fee });
+			
+			Ok(())
+		}
+
+		/// The factor by which the stability fee of `collateral_type` grows debt over `blocks`.
+		fn rate_growth(collateral_type: &CollateralType<T::Balance>, blocks: u32) -> FixedU128 {
+			let per_block = FixedU128::one().saturating_add(FixedU128::saturating_from_rational(
+				collateral_type.stability_fee,
+				10000u128.saturating_mul(T::BlocksPerYear::get() as u128),
+			));
+			
+			per_block.saturating_pow(blocks as usize)
+		}
+
+		/// The `currency` accumulator of collateral type `id`, compounded up to the current block
+		/// without touching storage.
+		fn current_rate(id: CollateralId, currency: DebtType, collateral_type: &CollateralType<T::Balance>) -> FixedU128 {
+			let rate = Rates::<T>::get(id, currency);
+			let now: u32 = frame_system::Pallet::<T>::block_number().saturated_into();
+			match RateUpdatedAt::<T>::get() {
+				Some(last) if !Self::is_caged() && now > last => {
+					rate.saturating_mul(Self::rate_growth(collateral_type, now.saturating_sub(last)))
+				},
+				_ => rate,
+			}
+		}
+
+		/// Grow the `currency` accumulator of `collateral_type` by `growth`, returning the debt
+		/// accrued on its normalized debt.
+		fn compound_rate(collateral_type: CollateralId, currency: DebtType, growth: FixedU128) -> T::Balance {
+			let normalized_debt = NormalizedDebt::<T>::get(collateral_type, currency);
+			let old_rate = Rates::<T>::get(collateral_type, currency);
+			let new_rate = old_rate.saturating_mul(growth);
+			Rates::<T>::insert(collateral_type, currency, new_rate);
+			
+			Self::denormalize_debt(normalized_debt, new_rate)
+				.saturating_sub(Self::denormalize_debt(normalized_debt, old_rate))
+		}
+	}
+}
+
+/// Reissues the flash mint fees of the stablecoin issued as `C` debt to the system surplus.
+pub struct FlashMintFeeToSurplus<T, C>(core::marker::PhantomData<(T, C)>);
+
+impl<T: Config, C: Get<DebtType>> pallet_stablecoin::OnFlashMintFee<T::Balance> for FlashMintFeeToSurplus<T, C> {
+	fn on_flash_mint_fee(fee: T::Balance) -> DispatchResult {
+		Pallet::<T>::reissue_to_surplus(C::get(), fee)
+	}
+}
+
+/// Succeeds only for the module whose pallet id is `Id` issuing stablecoin through the engine,
+/// e.g. the engine itself with its own `PalletId`.
+pub struct EnsureIssuer<T, Id>(core::marker::PhantomData<(T, Id)>);
+
+impl<T, Id, O> EnsureOrigin<O> for EnsureIssuer<T, Id>
+where
+	T: Config,
+	Id: Get<PalletId>,
+	O: Into<Result<Origin<T>, O>> + From<Origin<T>>,
+{
+	type Success = T::AccountId;
+
+	fn try_origin(o: O) -> Result<Self::Success, O> {
+		let issuer: T::AccountId = Id::get().into_account_truncating();
+		o.into().and_then(|o| match o {
+			RawOrigin::Issuer(who) if who == issuer => Ok(who),
+			r => Err(O::from(r)),
+		})
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn try_successful_origin() -> Result<O, ()> {
+		Ok(O::from(RawOrigin::Issuer(Id::get().into_account_truncating())))
+	}
+}
+
+/// Any price move can push CDPs below their liquidation ratio, so every CDP is checked again.
+impl<T: Config> OnPriceUpdate for Pallet<T> {
+	fn on_price_update(_pair: AssetPair) {
+		PendingScan::<T>::put(NextCdpId::<T>::get());
 	}
 }
@@ -0,0 +1,168 @@
+use super::*;
+use frame_support::{
+	migrations::VersionedMigration, pallet_prelude::*, storage_alias, traits::UncheckedOnRuntimeUpgrade,
+};
+use sp_runtime::traits::Zero;
+use sp_std::vec::Vec;
+
+/// Version 1 holds CDPs of several collateral types, keyed by `CdpId`.
+///
+/// Version 0 is the deployed baseline: one CDP per account, all of it native ORM, with a single
+/// collateral total and the ORM prices stored in this pallet.
+pub mod v1 {
+	use super::*;
+
+	/// The collateral type the baseline CDPs are migrated to.
+	pub const NATIVE_COLLATERAL: CollateralId = 0;
+
+	pub(crate) mod v0 {
+		use super::*;
+
+		/// A baseline CDP, whose collateral is native ORM reserved on the owner's account.
+		#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+		pub struct Cdp<Balance> {
+			pub collateral: Balance,
+			pub dusd_debt: Balance,
+			pub deur_debt: Balance,
+			pub last_update: u32,
+		}
+
+		#[storage_alias]
+		pub type Cdps<T: Config> = StorageMap<
+			Pallet<T>,
+			Blake2_128Concat,
+			<T as frame_system::Config>::AccountId,
+			Cdp<<T as Config>::Balance>,
+			OptionQuery,
+		>;
+
+		#[storage_alias]
+		pub type TotalCollateral<T: Config> = StorageValue<Pallet<T>, <T as Config>::Balance, ValueQuery>;
+
+		#[storage_alias]
+		pub type LiquidationQueue<T: Config> =
+			StorageMap<Pallet<T>, Blake2_128Concat, <T as frame_system::Config>::AccountId, bool, ValueQuery>;
+
+		#[storage_alias]
+		pub type OrmUsdPrice<T: Config> = StorageValue<Pallet<T>, u128, ValueQuery>;
+
+		#[storage_alias]
+		pub type OrmEurPrice<T: Config> = StorageValue<Pallet<T>, u128, ValueQuery>;
+	}
+
+	/// Registers the native collateral type with the parameters `N` unless it already is, gives
+	/// every baseline CDP an id under it and indexes it under its owner. The collateral total
+	/// becomes the native type's, and the prices the pallet used to store are now the oracle's
+	/// and are dropped.
+	pub struct InnerMigrateV0ToV1<T, N>(core::marker::PhantomData<(T, N)>);
+
+	impl<T: Config, N: Get<CollateralType<T::Balance>>> UncheckedOnRuntimeUpgrade
+		for InnerMigrateV0ToV1<T, N>
+	{
+		fn on_runtime_upgrade() -> Weight {
+			// The old and new items share storage prefixes, so every old entry is taken out before
+			// the first new one is written
+			let old: Vec<_> = v0::Cdps::<T>::drain().collect();
+			let total_collateral = v0::TotalCollateral::<T>::take();
+			let queued = v0::LiquidationQueue::<T>::clear(u32::MAX, None).unique;
+			v0::OrmUsdPrice::<T>::kill();
+			v0::OrmEurPrice::<T>::kill();
+			
+			if !CollateralTypes::<T>::contains_key(NATIVE_COLLATERAL) {
+				CollateralTypes::<T>::insert(NATIVE_COLLATERAL, N::get());
+			}
+			TotalCollateral::<T>::mutate(NATIVE_COLLATERAL, |total| {
+				*total = total.saturating_add(total_collateral)
+			});
+			
+			// Rates start at one, so the debt recorded so far is its own normalized debt
+			let mut cdp_id = NextCdpId::<T>::get();
+			let mut dusd_debt: T::Balance = Zero::zero();
+			let mut deur_debt: T::Balance = Zero::zero();
+			for (owner, cdp) in &old {
+				let migrated = Cdp {
+					collateral_type: NATIVE_COLLATERAL,
+					collateral: cdp.collateral,
+					dusd_debt: cdp.dusd_debt,
+					deur_debt: cdp.deur_debt,
+					last_update: cdp.last_update,
+				};
+				Cdps::<T>::insert(cdp_id, &migrated);
+				CdpOwner::<T>::insert(cdp_id, owner);
+				OwnerCdps::<T>::insert(owner, cdp_id, ());
+				dusd_debt = dusd_debt.saturating_add(cdp.dusd_debt);
+				deur_debt = deur_debt.saturating_add(cdp.deur_debt);
+				cdp_id = cdp_id.saturating_add(1);
+			}
+			NormalizedDebt::<T>::mutate(NATIVE_COLLATERAL, DebtType::Dusd, |total| {
+				*total = total.saturating_add(dusd_debt)
+			});
+			NormalizedDebt::<T>::mutate(NATIVE_COLLATERAL, DebtType::Deur, |total| {
+				*total = total.saturating_add(deur_debt)
+			});
+			NextCdpId::<T>::put(cdp_id);
+			
+			// CDPs that were queued for liquidation are found again by the next scan
+			PendingScan::<T>::put(cdp_id);
+			
+			let migrated = old.len() as u64;
+			T::DbWeight::get().reads_writes(
+				migrated.saturating_add(6),
+				migrated.saturating_mul(4).saturating_add(queued as u64).saturating_add(9),
+			)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, sp_runtime::TryRuntimeError> {
+			let cdps: Vec<_> = v0::Cdps::<T>::iter().collect();
+			let total_collateral = v0::TotalCollateral::<T>::get();
+			
+			Ok((cdps, total_collateral).encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), sp_runtime::TryRuntimeError> {
+			let (old, total_collateral) =
+				<(Vec<(T::AccountId, v0::Cdp<T::Balance>)>, T::Balance)>::decode(&mut &state[..])
+					.map_err(|_| "invalid pre-upgrade state")?;
+			
+			ensure!(v0::Cdps::<T>::iter_keys().next().is_none(), "baseline CDPs were left behind");
+			ensure!(Cdps::<T>::iter_keys().count() == old.len(), "CDPs were lost in the migration");
+			for (owner, cdp) in old {
+				let found = OwnerCdps::<T>::iter_key_prefix(&owner).any(|cdp_id| {
+					Cdps::<T>::get(cdp_id).is_some_and(|migrated| {
+						migrated.collateral_type == NATIVE_COLLATERAL &&
+							migrated.collateral == cdp.collateral &&
+							migrated.dusd_debt == cdp.dusd_debt &&
+							migrated.deur_debt == cdp.deur_debt
+					}) && CdpOwner::<T>::get(cdp_id).as_ref() == Some(&owner)
+				});
+				ensure!(found, "a baseline CDP was not migrated to its owner");
+			}
+			ensure!(
+				CollateralTypes::<T>::contains_key(NATIVE_COLLATERAL),
+				"the native collateral type is missing"
+			);
+			ensure!(
+				TotalCollateral::<T>::get(NATIVE_COLLATERAL) == total_collateral,
+				"the collateral total was not carried over"
+			);
+			ensure!(
+				!v0::OrmUsdPrice::<T>::exists() && !v0::OrmEurPrice::<T>::exists(),
+				"old prices were left behind"
+			);
+			
+			Ok(())
+		}
+	}
+
+	/// Migrates the baseline CDPs to id keys under a native collateral type with the parameters
+	/// `N`, only if the on-chain storage version is 0.
+	pub type MigrateV0ToV1<T, N> = VersionedMigration<
+		0,
+		1,
+		InnerMigrateV0ToV1<T, N>,
+		Pallet<T>,
+		<T as frame_system::Config>::DbWeight,
+	>;
+}
@@ -1,44 +1,101 @@
 use super::*;
 use crate::{mock::*, Error, Event};
-use frame_support::{assert_noop, assert_ok, traits::Get};
-use sp_runtime::traits::BadOrigin;
+use frame_support::{
+	assert_noop, assert_ok,
+	instances::{Instance1, Instance2},
+	traits::{Currency, Get, Hooks},
+	weights::Weight,
+};
+use pallet_oracle::AssetPair;
+use sp_runtime::{
+	traits::{BadOrigin, ValidateUnsigned},
+	transaction_validity::{InvalidTransaction, TransactionSource},
+};
+
+/// Prices are quoted with 18 decimals.
+const PRICE_ONE: u128 = 1_000_000_000_000_000_000;
+
+/// Opens the next oracle round for both pairs, which the single feeder settles on its own.
+fn submit_prices(usd: u128, eur: u128) {
+	for (pair, price) in [(AssetPair::OrmUsd, usd), (AssetPair::OrmEur, eur)] {
+		let round = pallet_oracle::Rounds::<Test>::get(pair).id + 1;
+		assert_ok!(Oracle::submit_price(RuntimeOrigin::signed(FEEDER), pair, round, price));
+	}
+}
+
+/// Moves time on by the security delay and releases the queued prices of both pairs.
+fn release_prices() {
+	Timestamp::set_timestamp(Timestamp::get() + DELAY);
+	for pair in [AssetPair::OrmUsd, AssetPair::OrmEur] {
+		let _ = Oracle::poke(RuntimeOrigin::signed(FEEDER), pair);
+	}
+}
+
+/// Sets the spot prices of both pairs and lets them through the security module, so that
+/// liquidations see them as well.
+fn set_prices(usd: u128, eur: u128) {
+	submit_prices(usd, eur);
+	release_prices();
+	release_prices();
+}
 
 #[test]
 fn create_cdp_works() {
 	new_test_ext().execute_with(|| {
 		System::set_block_number(1);
 		
-		assert_ok!(CollateralEngine::update_price(RuntimeOrigin::root(), b"ORM/USD".to_vec(), 100_000)); // $1.00
-		assert_ok!(CollateralEngine::update_price(RuntimeOrigin::root(), b"ORM/EUR".to_vec(), 80_000)); // €0.80
+		set_prices(100_000, 80_000);
 		
 		let _ = Balances::deposit_creating(&1, 10_000);
 		
-		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), 5_000));
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), NATIVE, 5_000));
 		
-		assert!(CollateralEngine::cdps(&1).is_some());
-		let cdp = CollateralEngine::cdps(&1).unwrap();
+		assert!(Cdps::<Test>::get(0).is_some());
+		let cdp = Cdps::<Test>::get(0).unwrap();
 		assert_eq!(cdp.collateral, 5_000);
 		assert_eq!(cdp.dusd_debt, 0);
 		assert_eq!(cdp.deur_debt, 0);
 		
-		System::assert_last_event(Event::CdpCreated { owner: 1, collateral: 5_000 }.into());
+		System::assert_last_event(
+			Event::CdpCreated { cdp_id: 0, owner: 1, collateral_type: NATIVE, collateral: 5_000 }.into(),
+		);
 	});
 }
 
 #[test]
-fn create_cdp_fails_already_exists() {
+fn account_can_open_several_cdps() {
 	new_test_ext().execute_with(|| {
 		System::set_block_number(1);
 		
+		set_prices(PRICE_ONE, PRICE_ONE * 8 / 10);
 		let _ = Balances::deposit_creating(&1, 10_000);
-		assert_ok!(CollateralEngine::update_price(RuntimeOrigin::root(), b"ORM/USD".to_vec(), 100_000));
-		assert_ok!(CollateralEngine::update_price(RuntimeOrigin::root(), b"ORM/EUR".to_vec(), 80_000));
+		let _ = Balances::deposit_creating(&2, 10_000);
 		
-		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), 5_000));
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), NATIVE, 5_000));
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(2), NATIVE, 4_000));
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), NATIVE, 3_000));
 		
+		let mut cdps = CollateralEngine::cdps_of(&1);
+		cdps.sort();
+		assert_eq!(cdps, vec![0, 2]);
+		assert_eq!(CdpOwner::<Test>::get(2), Some(1));
+		assert_eq!(NextCdpId::<Test>::get(), 3);
+		
+		// Each position is isolated from the others
+		assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 2, 2_000));
+		assert_noop!(
+			CollateralEngine::withdraw_collateral(RuntimeOrigin::signed(1), 2, 1),
+			Error::<Test>::CollateralRatioTooLow
+		);
+		assert_ok!(CollateralEngine::withdraw_collateral(RuntimeOrigin::signed(1), 0, 5_000));
+		
+		assert_noop!(
+			CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 1, 100),
+			Error::<Test>::NotAuthorized
+		);
 		assert_noop!(
-			CollateralEngine::create_cdp(RuntimeOrigin::signed(1), 3_000),
-			Error::<Test>::CdpAlreadyExists
+			CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 3, 100),
+			Error::<Test>::CdpNotFound
 		);
 	});
 }
@@ -48,19 +105,20 @@ fn deposit_collateral_works() {
 	new_test_ext().execute_with(|| {
 		System::set_block_number(1);
 		
-		assert_ok!(CollateralEngine::update_price(RuntimeOrigin::root(), b"ORM/USD".to_vec(), 100_000));
-		assert_ok!(CollateralEngine::update_price(RuntimeOrigin::root(), b"ORM/EUR".to_vec(), 80_000));
+		set_prices(100_000, 80_000);
 		let _ = Balances::deposit_creating(&1, 10_000);
-		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), 5_000));
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), NATIVE, 5_000));
 		
-		assert_ok!(CollateralEngine::deposit_collateral(RuntimeOrigin::signed(1), 2_000));
+		assert_ok!(CollateralEngine::deposit_collateral(RuntimeOrigin::signed(1), 0, 2_000));
 		
-		let cdp = CollateralEngine::cdps(&1).unwrap();
+		let cdp = Cdps::<Test>::get(0).unwrap();
 		assert_eq!(cdp.collateral, 7_000);
 		
-		assert_eq!(CollateralEngine::total_collateral(), 7_000);
+		assert_eq!(TotalCollateral::<Test>::get(NATIVE), 7_000);
 		
-		System::assert_last_event(Event::CollateralDeposited { owner: 1, amount: 2_000 }.into());
+		System::assert_last_event(
+			Event::CollateralDeposited { cdp_id: 0, amount: 2_000 }.into(),
+		);
 	});
 }
 
@@ -70,7 +128,7 @@ fn deposit_collateral_fails_no_cdp() {
 		let _ = Balances::deposit_creating(&1, 10_000);
 		
 		assert_noop!(
-			CollateralEngine::deposit_collateral(RuntimeOrigin::signed(1), 2_000),
+			CollateralEngine::deposit_collateral(RuntimeOrigin::signed(1), 0, 2_000),
 			Error::<Test>::CdpNotFound
 		);
 	});
@@ -81,19 +139,20 @@ fn mint_dusd_works() {
 	new_test_ext().execute_with(|| {
 		System::set_block_number(1);
 		
-		assert_ok!(CollateralEngine::update_price(RuntimeOrigin::root(), b"ORM/USD".to_vec(), 100_000));
-		assert_ok!(CollateralEngine::update_price(RuntimeOrigin::root(), b"ORM/EUR".to_vec(), 80_000));
+		set_prices(100_000, 80_000);
 		let _ = Balances::deposit_creating(&1, 10_000);
-		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), 5_000));
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), NATIVE, 5_000));
 		
-		assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 3_000));
+		assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 0, 3_000));
 		
-		let cdp = CollateralEngine::cdps(&1).unwrap();
+		let cdp = Cdps::<Test>::get(0).unwrap();
 		assert_eq!(cdp.dusd_debt, 3_000);
 		
 		assert_eq!(CollateralEngine::total_dusd_debt(), 3_000);
 		
-		System::assert_last_event(Event::DusdMinted { owner: 1, amount: 3_000 }.into());
+		System::assert_last_event(
+			Event::DusdMinted { cdp_id: 0, amount: 3_000 }.into(),
+		);
 	});
 }
 
@@ -102,13 +161,12 @@ fn mint_dusd_fails_insufficient_collateral() {
 	new_test_ext().execute_with(|| {
 		System::set_block_number(1);
 		
-		assert_ok!(CollateralEngine::update_price(RuntimeOrigin::root(), b"ORM/USD".to_vec(), 100_000));
-		assert_ok!(CollateralEngine::update_price(RuntimeOrigin::root(), b"ORM/EUR".to_vec(), 80_000));
+		set_prices(100_000, 80_000);
 		let _ = Balances::deposit_creating(&1, 2_000);
-		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), 1_000));
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), NATIVE, 1_000));
 		
 		assert_noop!(
-			CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 1_000),
+			CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 0, 1_000),
 			Error::<Test>::CollateralRatioTooLow
 		);
 	});
@@ -119,17 +177,18 @@ fn withdraw_collateral_works() {
 	new_test_ext().execute_with(|| {
 		System::set_block_number(1);
 		
-		assert_ok!(CollateralEngine::update_price(RuntimeOrigin::root(), b"ORM/USD".to_vec(), 100_000));
-		assert_ok!(CollateralEngine::update_price(RuntimeOrigin::root(), b"ORM/EUR".to_vec(), 80_000));
+		set_prices(100_000, 80_000);
 		let _ = Balances::deposit_creating(&1, 10_000);
-		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), 5_000));
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), NATIVE, 5_000));
 		
-		assert_ok!(CollateralEngine::withdraw_collateral(RuntimeOrigin::signed(1), 2_000));
+		assert_ok!(CollateralEngine::withdraw_collateral(RuntimeOrigin::signed(1), 0, 2_000));
 		
-		let cdp = CollateralEngine::cdps(&1).unwrap();
+		let cdp = Cdps::<Test>::get(0).unwrap();
 		assert_eq!(cdp.collateral, 3_000);
 		
-		System::assert_last_event(Event::CollateralWithdrawn { owner: 1, amount: 2_000 }.into());
+		System::assert_last_event(
+			Event::CollateralWithdrawn { cdp_id: 0, amount: 2_000 }.into(),
+		);
 	});
 }
 
@@ -138,62 +197,1730 @@ fn withdraw_collateral_fails_with_debt() {
 	new_test_ext().execute_with(|| {
 		System::set_block_number(1);
 		
-		assert_ok!(CollateralEngine::update_price(RuntimeOrigin::root(), b"ORM/USD".to_vec(), 100_000));
-		assert_ok!(CollateralEngine::update_price(RuntimeOrigin::root(), b"ORM/EUR".to_vec(), 80_000));
+		set_prices(100_000, 80_000);
 		let _ = Balances::deposit_creating(&1, 10_000);
-		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), 5_000));
-		assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 3_000));
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), NATIVE, 5_000));
+		assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 0, 3_000));
 		
 		assert_noop!(
-			CollateralEngine::withdraw_collateral(RuntimeOrigin::signed(1), 3_000),
+			CollateralEngine::withdraw_collateral(RuntimeOrigin::signed(1), 0, 3_000),
 			Error::<Test>::CollateralRatioTooLow
 		);
 	});
 }
 
 #[test]
-fn price_update_works() {
+fn prices_come_from_the_oracle() {
 	new_test_ext().execute_with(|| {
 		System::set_block_number(1);
 		
-		assert_ok!(CollateralEngine::update_price(RuntimeOrigin::root(), b"ORM/USD".to_vec(), 120_000));
-		assert_ok!(CollateralEngine::update_price(RuntimeOrigin::root(), b"ORM/EUR".to_vec(), 90_000));
+		assert_eq!(CollateralEngine::price(PriceFeed::Orm, DebtType::Dusd, PriceSource::Spot), 0);
+		let _ = Balances::deposit_creating(&1, 10_000);
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), NATIVE, 5_000));
+		assert_noop!(
+			CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 0, 1_000),
+			Error::<Test>::PriceNotAvailable
+		);
 		
-		assert_eq!(CollateralEngine::orm_usd_price(), 120_000);
-		assert_eq!(CollateralEngine::orm_eur_price(), 90_000);
+		set_prices(120_000, 90_000);
 		
-		System::assert_has_event(Event::PriceUpdated { asset: b"ORM/USD".to_vec(), price: 120_000 }.into());
-		System::assert_has_event(Event::PriceUpdated { asset: b"ORM/EUR".to_vec(), price: 90_000 }.into());
+		assert_eq!(CollateralEngine::price(PriceFeed::Orm, DebtType::Dusd, PriceSource::Spot), 120_000);
+		assert_eq!(CollateralEngine::price(PriceFeed::Orm, DebtType::Deur, PriceSource::Delayed), 90_000);
 	});
 }
 
 #[test]
-fn price_update_fails_for_non_root() {
+fn collateral_ratio_calculation_works() {
 	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		
+		set_prices(100_000, 80_000);
+		
+		let _ = Balances::deposit_creating(&1, 10_000);
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), NATIVE, 5_000));
+		
+		
+		assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 0, 3_000));
+		
 		assert_noop!(
-			CollateralEngine::update_price(RuntimeOrigin::signed(1), b"ORM/USD".to_vec(), 120_000),
-			BadOrigin
+			CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 0, 500),
+			Error::<Test>::CollateralRatioTooLow
 		);
 	});
 }
 
 #[test]
-fn collateral_ratio_calculation_works() {
+fn mint_dusd_credits_tokens() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		
+		set_prices(PRICE_ONE, PRICE_ONE * 8 / 10);
+		let _ = Balances::deposit_creating(&1, 10_000);
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), NATIVE, 5_000));
+		
+		assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 0, 3_000));
+		
+		assert_eq!(pallet_stablecoin::Balances::<Test, Instance1>::get(1), 3_000);
+		assert_eq!(TotalDusdDebt::<Test>::get(), pallet_stablecoin::TotalSupply::<Test, Instance1>::get());
+		System::assert_has_event(
+			pallet_stablecoin::Event::<Test, Instance1>::Mint { to: 1, amount: 3_000, total_supply: 3_000 }.into(),
+		);
+	});
+}
+
+#[test]
+fn only_the_issuer_origin_mints() {
+	new_test_ext().execute_with(|| {
+		let surplus = CollateralEngine::surplus_account();
+		
+		// Signing as the engine's account is not the engine's origin
+		assert_noop!(
+			Dusd::mint_to(RuntimeOrigin::signed(surplus), &1, 1_000),
+			pallet_stablecoin::Error::<Test, Instance1>::NotAuthorized
+		);
+		assert_noop!(
+			Dusd::mint_to(RawOrigin::Issuer(1).into(), &1, 1_000),
+			pallet_stablecoin::Error::<Test, Instance1>::NotAuthorized
+		);
+		assert_ok!(Dusd::mint_to(CollateralEngine::issuer().into(), &1, 1_000));
+		assert_eq!(pallet_stablecoin::Balances::<Test, Instance1>::get(1), 1_000);
+	});
+}
+
+#[test]
+fn stablecoin_supply_tracks_cdp_debt() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		
+		set_prices(PRICE_ONE, PRICE_ONE * 8 / 10);
+		for who in 1..=3u64 {
+			let _ = Balances::deposit_creating(&who, 10_000);
+			assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(who), NATIVE, 6_000));
+			assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(who), who - 1, 1_000 * who as u128));
+		}
+		
+		assert_eq!(TotalDusdDebt::<Test>::get(), 6_000);
+		assert_eq!(TotalDusdDebt::<Test>::get(), pallet_stablecoin::TotalSupply::<Test, Instance1>::get());
+		assert_eq!(TotalDeurDebt::<Test>::get(), pallet_stablecoin::TotalSupply::<Test, Instance2>::get());
+	});
+}
+
+#[test]
+fn flash_mint_fees_are_reissued_to_the_surplus() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		
+		set_prices(PRICE_ONE, PRICE_ONE * 8 / 10);
+		let _ = Balances::deposit_creating(&1, 10_000);
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), NATIVE, 5_000));
+		assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 0, 3_000));
+		
+		let remark = Box::new(RuntimeCall::System(frame_system::Call::remark { remark: vec![] }));
+		assert_ok!(Dusd::flash_mint(RuntimeOrigin::signed(1), 1_000_000, remark));
+		
+		assert_eq!(pallet_stablecoin::Balances::<Test, Instance1>::get(1), 2_100);
+		assert_eq!(pallet_stablecoin::Balances::<Test, Instance1>::get(CollateralEngine::surplus_account()), 900);
+		assert_eq!(TotalDusdDebt::<Test>::get(), 3_000);
+		assert_eq!(TotalDusdDebt::<Test>::get(), pallet_stablecoin::TotalSupply::<Test, Instance1>::get());
+	});
+}
+
+#[test]
+fn failed_mint_issues_no_tokens() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		
+		set_prices(PRICE_ONE, PRICE_ONE * 8 / 10);
+		let _ = Balances::deposit_creating(&1, 2_000);
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), NATIVE, 1_000));
+		
+		assert_noop!(
+			CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 0, 1_000),
+			Error::<Test>::CollateralRatioTooLow
+		);
+		assert_eq!(pallet_stablecoin::TotalSupply::<Test, Instance1>::get(), 0);
+	});
+}
+
+#[test]
+fn repay_dusd_partial_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		
+		set_prices(PRICE_ONE, PRICE_ONE * 8 / 10);
+		let _ = Balances::deposit_creating(&1, 10_000);
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), NATIVE, 5_000));
+		assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 0, 3_000));
+		
+		assert_ok!(CollateralEngine::repay_dusd(RuntimeOrigin::signed(1), 0, 1_000));
+		
+		assert_eq!(Cdps::<Test>::get(0).unwrap().dusd_debt, 2_000);
+		assert_eq!(pallet_stablecoin::Balances::<Test, Instance1>::get(1), 2_000);
+		assert_eq!(TotalDusdDebt::<Test>::get(), 2_000);
+		assert_eq!(TotalDusdDebt::<Test>::get(), pallet_stablecoin::TotalSupply::<Test, Instance1>::get());
+		
+		System::assert_last_event(
+			Event::DusdRepaid { cdp_id: 0, amount: 1_000 }.into(),
+		);
+	});
+}
+
+#[test]
+fn repay_fails_above_debt() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		
+		set_prices(PRICE_ONE, PRICE_ONE * 8 / 10);
+		let _ = Balances::deposit_creating(&1, 10_000);
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), NATIVE, 5_000));
+		assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 0, 1_000));
+		
+		assert_noop!(
+			CollateralEngine::repay_dusd(RuntimeOrigin::signed(1), 0, 1_001),
+			Error::<Test>::InsufficientDebt
+		);
+		assert_noop!(
+			CollateralEngine::repay_deur(RuntimeOrigin::signed(1), 0, 1),
+			Error::<Test>::InsufficientDebt
+		);
+	});
+}
+
+#[test]
+fn repay_fails_without_tokens() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		
+		set_prices(PRICE_ONE, PRICE_ONE * 8 / 10);
+		let _ = Balances::deposit_creating(&1, 10_000);
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), NATIVE, 5_000));
+		assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 0, 1_000));
+		assert_ok!(Dusd::transfer(RuntimeOrigin::signed(1), 2, 600));
+		
+		assert_noop!(
+			CollateralEngine::repay_dusd(RuntimeOrigin::signed(1), 0, 1_000),
+			pallet_stablecoin::Error::<Test, Instance1>::InsufficientBalance
+		);
+	});
+}
+
+#[test]
+fn full_repay_and_close_cdp_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		
+		set_prices(PRICE_ONE, PRICE_ONE * 8 / 10);
+		let _ = Balances::deposit_creating(&1, 10_000);
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), NATIVE, 5_000));
+		assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 0, 3_000));
+		
+		assert_noop!(CollateralEngine::close_cdp(RuntimeOrigin::signed(1), 0), Error::<Test>::CdpHasDebt);
+		
+		assert_ok!(CollateralEngine::repay_dusd(RuntimeOrigin::signed(1), 0, 3_000));
+		assert_ok!(CollateralEngine::close_cdp(RuntimeOrigin::signed(1), 0));
+		
+		assert!(Cdps::<Test>::get(0).is_none());
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), 10_000);
+		assert_eq!(TotalCollateral::<Test>::get(NATIVE), 0);
+		assert_eq!(TotalDusdDebt::<Test>::get(), 0);
+		assert_eq!(pallet_stablecoin::TotalSupply::<Test, Instance1>::get(), 0);
+		
+		System::assert_last_event(
+			Event::CdpClosed { cdp_id: 0, collateral_returned: 5_000 }.into(),
+		);
+	});
+}
+
+#[test]
+fn mint_deur_works() {
 	new_test_ext().execute_with(|| {
 		System::set_block_number(1);
 		
-		assert_ok!(CollateralEngine::update_price(RuntimeOrigin::root(), b"ORM/USD".to_vec(), 100_000)); // $1.00
-		assert_ok!(CollateralEngine::update_price(RuntimeOrigin::root(), b"ORM/EUR".to_vec(), 80_000)); // €0.80
+		set_prices(PRICE_ONE, PRICE_ONE * 8 / 10);
+		let _ = Balances::deposit_creating(&1, 10_000);
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), NATIVE, 6_000));
+		
+		assert_ok!(CollateralEngine::mint_deur(RuntimeOrigin::signed(1), 0, 3_000));
+		
+		let cdp = Cdps::<Test>::get(0).unwrap();
+		assert_eq!(cdp.deur_debt, 3_000);
+		assert_eq!(cdp.dusd_debt, 0);
+		assert_eq!(pallet_stablecoin::Balances::<Test, Instance2>::get(1), 3_000);
+		assert_eq!(TotalDeurDebt::<Test>::get(), pallet_stablecoin::TotalSupply::<Test, Instance2>::get());
+		
+		System::assert_last_event(
+			Event::DeurMinted { cdp_id: 0, amount: 3_000 }.into(),
+		);
+	});
+}
+
+#[test]
+fn mint_deur_respects_combined_ratio() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
 		
+		// An ORM buys $1.00 or €0.80, so 1 EUR of debt is worth 1.0 / 0.8 = 1.25 USD of debt
+		set_prices(PRICE_ONE, PRICE_ONE * 8 / 10);
 		let _ = Balances::deposit_creating(&1, 10_000);
-		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), 5_000));
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), NATIVE, 6_000));
+		assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 0, 2_000));
+		
+		// 2_000 + 1_600 * 1.25 = 4_000 USD of debt against 6_000 USD of collateral is exactly 150%
+		assert_noop!(
+			CollateralEngine::mint_deur(RuntimeOrigin::signed(1), 0, 1_601),
+			Error::<Test>::CollateralRatioTooLow
+		);
+		assert_ok!(CollateralEngine::mint_deur(RuntimeOrigin::signed(1), 0, 1_600));
+		
+		let cdp = Cdps::<Test>::get(0).unwrap();
+		assert_eq!(
+			CollateralEngine::collateral_ratio(PriceFeed::Orm, PriceSource::Spot, cdp.collateral, 2_000, 1_600),
+			Ok(15_000)
+		);
+	});
+}
+
+/// Opens a 250% CDP for account 1 (15_000 ORM against 6_000 dUSD) and hands the minted
+/// dUSD to account 2 so it can act as the liquidator.
+fn setup_liquidatable_position() {
+	set_prices(PRICE_ONE, PRICE_ONE * 8 / 10);
+	let _ = Balances::deposit_creating(&1, 20_000);
+	let _ = Balances::deposit_creating(&2, 1_000);
+	assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), NATIVE, 15_000));
+	assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 0, 6_000));
+	assert_ok!(Dusd::transfer(RuntimeOrigin::signed(1), 2, 6_000));
+}
+
+#[test]
+fn liquidate_fails_at_exact_liquidation_ratio() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		setup_liquidatable_position();
+		
+		// 15_000 * $0.52 = $7_800 against $6_000 of debt is exactly 130%.
+		set_prices(PRICE_ONE * 52 / 100, PRICE_ONE * 8 / 10);
+		
+		assert_noop!(
+			CollateralEngine::liquidate(RuntimeOrigin::signed(2), 0),
+			Error::<Test>::CdpNotLiquidatable
+		);
+	});
+}
+
+#[test]
+fn liquidate_works_below_liquidation_ratio() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		setup_liquidatable_position();
+		
+		// 15_000 * $0.519 = $7_785 against $6_000 of debt is 129.75%.
+		set_prices(PRICE_ONE * 519 / 1000, PRICE_ONE * 8 / 10);
+		
+		assert_ok!(CollateralEngine::liquidate(RuntimeOrigin::signed(2), 0));
+		
+		// $6_000 of debt plus the 13% penalty is $6_780, or 13_063 ORM at $0.519.
+		let collateral_seized = 13_063;
+		assert!(Cdps::<Test>::get(0).is_none());
+		assert_eq!(Balances::free_balance(2), 1_000 + collateral_seized);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), 20_000 - collateral_seized);
+		assert_eq!(pallet_stablecoin::Balances::<Test, Instance1>::get(2), 0);
+		assert_eq!(TotalCollateral::<Test>::get(NATIVE), 0);
+		assert_eq!(TotalDusdDebt::<Test>::get(), 0);
+		assert_eq!(pallet_stablecoin::TotalSupply::<Test, Instance1>::get(), 0);
+		
+		System::assert_last_event(
+			Event::CdpLiquidated { cdp_id: 0, liquidator: 2, collateral_seized }.into(),
+		);
+	});
+}
+
+#[test]
+fn liquidate_caps_seized_collateral() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		setup_liquidatable_position();
+		
+		// The collateral is worth less than the debt, so all of it goes to the liquidator.
+		set_prices(PRICE_ONE * 3 / 10, PRICE_ONE * 8 / 10);
+		
+		assert_ok!(CollateralEngine::liquidate(RuntimeOrigin::signed(2), 0));
+		
+		assert_eq!(Balances::free_balance(2), 1_000 + 15_000);
+		assert_eq!(Balances::total_balance(&1), 5_000);
+	});
+}
+
+#[test]
+fn liquidate_records_the_shortfall_as_bad_debt() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		setup_liquidatable_position();
+		
+		// $4_500 of collateral pays for $4_500 / 1.13 = $3_982.30 of the $6_000 of debt
+		set_prices(PRICE_ONE * 3 / 10, PRICE_ONE * 8 / 10);
+		
+		assert_ok!(CollateralEngine::liquidate(RuntimeOrigin::signed(2), 0));
+		
+		assert_eq!(Balances::free_balance(2), 1_000 + 15_000);
+		assert_eq!(pallet_stablecoin::Balances::<Test, Instance1>::get(2), 2_017);
+		// The rest is still owed by the system until surplus heals it
+		assert_eq!(TotalDusdDebt::<Test>::get(), 2_017);
+		assert_eq!(TotalDusdDebt::<Test>::get(), pallet_stablecoin::TotalSupply::<Test, Instance1>::get());
+	});
+}
+
+#[test]
+fn liquidate_requires_liquidator_to_cover_debt() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		setup_liquidatable_position();
+		let _ = Balances::deposit_creating(&3, 1_000);
 		
+		set_prices(PRICE_ONE / 2, PRICE_ONE * 8 / 10);
 		
-		assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 3_000));
+		assert_noop!(
+			CollateralEngine::liquidate(RuntimeOrigin::signed(3), 0),
+			pallet_stablecoin::Error::<Test, Instance1>::InsufficientBalance
+		);
+	});
+}
+
+#[test]
+fn liquidations_use_the_delayed_price() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		setup_liquidatable_position();
 		
+		// Spot falls to $0.519, which already holds back new debt
+		submit_prices(PRICE_ONE * 519 / 1000, PRICE_ONE * 8 / 10);
 		assert_noop!(
-			CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 500),
+			CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 0, 1),
 			Error::<Test>::CollateralRatioTooLow
 		);
+		
+		// Liquidations wait until the security module has released the new price
+		assert_noop!(
+			CollateralEngine::liquidate(RuntimeOrigin::signed(2), 0),
+			Error::<Test>::CdpNotLiquidatable
+		);
+		release_prices();
+		assert_noop!(
+			CollateralEngine::liquidate(RuntimeOrigin::signed(2), 0),
+			Error::<Test>::CdpNotLiquidatable
+		);
+		release_prices();
+		assert_ok!(CollateralEngine::liquidate(RuntimeOrigin::signed(2), 0));
+	});
+}
+
+#[test]
+fn twap_valuation_smooths_liquidations() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		setup_liquidatable_position();
+		assert_noop!(
+			CollateralEngine::set_twap_window(RuntimeOrigin::signed(1), NATIVE, Some(4 * DELAY)),
+			BadOrigin
+		);
+		assert_noop!(
+			CollateralEngine::set_twap_window(RuntimeOrigin::root(), 7, Some(4 * DELAY)),
+			Error::<Test>::UnknownCollateralType
+		);
+		
+		// $1 has held since time zero; the price halves and is released at 6 * DELAY
+		Timestamp::set_timestamp(4 * DELAY);
+		set_prices(PRICE_ONE / 2, PRICE_ONE * 8 / 10);
+		let cdp = Cdps::<Test>::get(0).unwrap();
+		assert!(CollateralEngine::is_liquidatable(&cdp).unwrap());
+		
+		// Averaged over the last four hours the price is $0.75, a ratio of 187.5%
+		assert_ok!(CollateralEngine::set_twap_window(RuntimeOrigin::root(), NATIVE, Some(4 * DELAY)));
+		System::assert_last_event(
+			Event::TwapWindowUpdated { id: NATIVE, window: Some(4 * DELAY) }.into(),
+		);
+		assert!(!CollateralEngine::is_liquidatable(&cdp).unwrap());
+		assert_noop!(
+			CollateralEngine::liquidate(RuntimeOrigin::signed(2), 0),
+			Error::<Test>::CdpNotLiquidatable
+		);
+		
+		// Once the lower price has held for the whole window the average catches up
+		Timestamp::set_timestamp(9 * DELAY);
+		assert!(CollateralEngine::is_liquidatable(&cdp).unwrap());
+		
+		// Without a window, liquidations go back to the delayed price
+		assert_ok!(CollateralEngine::set_twap_window(RuntimeOrigin::root(), NATIVE, None));
+		assert_eq!(CollateralEngine::liquidation_source(NATIVE), PriceSource::Delayed);
+	});
+}
+
+#[test]
+fn stale_prices_block_minting_and_withdrawals() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		setup_liquidatable_position();
+		assert_ok!(Dusd::transfer(RuntimeOrigin::signed(2), 1, 1_000));
+		
+		// The prices were aggregated at time zero
+		Timestamp::set_timestamp(MAX_PRICE_AGE + 1);
+		assert_noop!(
+			CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 0, 100),
+			Error::<Test>::StalePrice
+		);
+		assert_noop!(
+			CollateralEngine::withdraw_collateral(RuntimeOrigin::signed(1), 0, 100),
+			Error::<Test>::StalePrice
+		);
+		
+		// Paying down debt and adding collateral only make the position safer
+		assert_ok!(CollateralEngine::repay_dusd(RuntimeOrigin::signed(1), 0, 1_000));
+		assert_ok!(CollateralEngine::deposit_collateral(RuntimeOrigin::signed(1), 0, 100));
+		
+		submit_prices(PRICE_ONE, PRICE_ONE * 8 / 10);
+		assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 0, 100));
+		assert_ok!(CollateralEngine::withdraw_collateral(RuntimeOrigin::signed(1), 0, 100));
+	});
+}
+
+#[test]
+fn tripped_circuit_breaker_pauses_minting_and_withdrawals() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		setup_liquidatable_position();
+		assert_ok!(Oracle::set_max_deviation(RuntimeOrigin::root(), Some(1_000))); // 10%
+		
+		submit_prices(PRICE_ONE * 2, PRICE_ONE * 8 / 10);
+		assert_noop!(
+			CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 0, 100),
+			Error::<Test>::PriceFeedHalted
+		);
+		assert_noop!(
+			CollateralEngine::mint_deur(RuntimeOrigin::signed(1), 0, 100),
+			Error::<Test>::PriceFeedHalted
+		);
+		// The doubled price would otherwise free collateral that backs the debt
+		assert_noop!(
+			CollateralEngine::withdraw_collateral(RuntimeOrigin::signed(1), 0, 8_000),
+			Error::<Test>::PriceFeedHalted
+		);
+		
+		assert_ok!(Oracle::reset_circuit_breaker(RuntimeOrigin::root(), AssetPair::OrmUsd));
+		assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 0, 100));
+		assert_ok!(CollateralEngine::withdraw_collateral(RuntimeOrigin::signed(1), 0, 1_000));
+	});
+}
+
+#[test]
+fn stability_fee_accrues_on_dusd_debt() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		
+		set_prices(PRICE_ONE, PRICE_ONE * 8 / 10);
+		let _ = Balances::deposit_creating(&1, 100_000);
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), NATIVE, 50_000));
+		assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 0, 10_000));
+		
+		// One "year" of blocks compounds 5% per year per block: 10_000 * 1.000005^10_000.
+		System::set_block_number(10_001);
+		assert_ok!(CollateralEngine::accrue_stability_fees());
+		
+		let cdp = Cdps::<Test>::get(0).unwrap();
+		assert_eq!(cdp.dusd_debt, 10_000);
+		assert_eq!(CollateralEngine::cdp_debt(&cdp), (10_512, 0));
+		assert_eq!(TotalDusdDebt::<Test>::get(), 10_512);
+		assert_eq!(pallet_stablecoin::Balances::<Test, Instance1>::get(CollateralEngine::surplus_account()), 512);
+		assert_eq!(TotalDusdDebt::<Test>::get(), pallet_stablecoin::TotalSupply::<Test, Instance1>::get());
+		
+		System::assert_last_event(Event::StabilityFeeAccrued { dusd_fee: 512, deur_fee: 0 }.into());
+	});
+}
+
+#[test]
+fn stability_fee_is_charged_on_repayment() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		
+		set_prices(PRICE_ONE, PRICE_ONE * 8 / 10);
+		let _ = Balances::deposit_creating(&1, 100_000);
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), NATIVE, 50_000));
+		assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 0, 10_000));
+		
+		System::set_block_number(10_001);
+		
+		// The minted 10_000 dUSD no longer covers the debt
+		assert_noop!(
+			CollateralEngine::repay_dusd(RuntimeOrigin::signed(1), 0, 10_512),
+			pallet_stablecoin::Error::<Test, Instance1>::InsufficientBalance
+		);
+		
+		assert_ok!(Dusd::transfer(
+			RuntimeOrigin::signed(CollateralEngine::surplus_account()),
+			1,
+			512
+		));
+		assert_ok!(CollateralEngine::repay_dusd(RuntimeOrigin::signed(1), 0, 10_512));
+		
+		assert_eq!(Cdps::<Test>::get(0).unwrap().dusd_debt, 0);
+		assert_eq!(NormalizedDebt::<Test>::get(NATIVE, DebtType::Dusd), 0);
+		assert_eq!(TotalDusdDebt::<Test>::get(), 0);
+		assert_eq!(pallet_stablecoin::TotalSupply::<Test, Instance1>::get(), 0);
+		assert_ok!(CollateralEngine::close_cdp(RuntimeOrigin::signed(1), 0));
+	});
+}
+
+#[test]
+fn debt_minted_later_is_normalized_against_the_rate() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		
+		set_prices(PRICE_ONE, PRICE_ONE * 8 / 10);
+		let _ = Balances::deposit_creating(&1, 100_000);
+		let _ = Balances::deposit_creating(&2, 100_000);
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), NATIVE, 50_000));
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(2), NATIVE, 50_000));
+		assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 0, 10_000));
+		
+		System::set_block_number(10_001);
+		assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(2), 1, 10_512));
+		
+		// Both positions now owe the same amount, although only the first one paid fees
+		let first = CollateralEngine::cdp_debt(&Cdps::<Test>::get(0).unwrap());
+		let second = CollateralEngine::cdp_debt(&Cdps::<Test>::get(1).unwrap());
+		assert_eq!(first.0, 10_512);
+		assert!(second.0 >= 10_512);
+		assert!(Cdps::<Test>::get(1).unwrap().dusd_debt <= 10_000);
+	});
+}
+
+/// Puts account 1's CDP from `setup_liquidatable_position` up for auction at $0.50, with
+/// account 4 holding 7_000 dUSD from a safe CDP of its own.
+fn setup_auction() {
+	setup_liquidatable_position();
+	let _ = Balances::deposit_creating(&4, 30_000);
+	assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(4), NATIVE, 20_000));
+	assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(4), 1, 7_000));
+	
+	set_prices(PRICE_ONE / 2, PRICE_ONE * 8 / 10);
+	assert_ok!(CollateralEngine::start_auction(RuntimeOrigin::signed(3), 0));
+}
+
+#[test]
+fn start_auction_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		setup_auction();
+		
+		// $6_000 of debt plus the 13% penalty, starting at 120% of the $0.50 oracle price.
+		let auction = Auctions::<Test>::get(0).unwrap();
+		assert_eq!(auction.owner, 1);
+		assert_eq!(auction.currency, DebtType::Dusd);
+		assert_eq!(auction.lot, 15_000);
+		assert_eq!(auction.tab, 6_780);
+		assert_eq!(auction.debt, 6_000);
+		assert_eq!(auction.top, PRICE_ONE * 6 / 10);
+		
+		assert!(Cdps::<Test>::get(0).is_none());
+		assert_eq!(Balances::reserved_balance(1), 15_000);
+		assert_eq!(TotalCollateral::<Test>::get(NATIVE), 20_000);
+		
+		System::assert_last_event(
+			Event::AuctionStarted {
+				id: 0,
+				owner: 1,
+				collateral_type: NATIVE,
+				currency: DebtType::Dusd,
+				lot: 15_000,
+				tab: 6_780,
+				top: PRICE_ONE * 6 / 10,
+			}
+			.into(),
+		);
+	});
+}
+
+#[test]
+fn start_auction_fails_for_safe_cdp() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		setup_liquidatable_position();
+		
+		assert_noop!(
+			CollateralEngine::start_auction(RuntimeOrigin::signed(3), 0),
+			Error::<Test>::CdpNotLiquidatable
+		);
+	});
+}
+
+#[test]
+fn take_follows_price_decay() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		setup_auction();
+		
+		assert_noop!(
+			CollateralEngine::take(RuntimeOrigin::signed(2), 0, 5_000, PRICE_ONE / 2),
+			Error::<Test>::AuctionPriceTooHigh
+		);
+		
+		assert_ok!(CollateralEngine::take(RuntimeOrigin::signed(2), 0, 5_000, PRICE_ONE));
+		let auction = Auctions::<Test>::get(0).unwrap();
+		assert_eq!(auction.lot, 10_000);
+		assert_eq!(auction.tab, 3_780);
+		assert_eq!(auction.debt, 3_000);
+		
+		// Halfway through the linear curve the price has halved
+		System::set_block_number(51);
+		assert_ok!(CollateralEngine::take(RuntimeOrigin::signed(2), 0, 20_000, PRICE_ONE));
+		
+		System::assert_has_event(
+			Event::AuctionTaken { id: 0, buyer: 2, collateral: 10_000, paid: 3_000, price: PRICE_ONE * 3 / 10 }
+				.into(),
+		);
+		System::assert_last_event(
+			Event::AuctionFinished { id: 0, collateral_returned: 0, debt_remaining: 0 }.into(),
+		);
+		assert!(Auctions::<Test>::get(0).is_none());
+		assert_eq!(Balances::free_balance(2), 1_000 + 15_000);
+		assert_eq!(Balances::total_balance(&1), 5_000);
+		assert_eq!(TotalDusdDebt::<Test>::get(), 7_000);
+		assert_eq!(TotalDusdDebt::<Test>::get(), pallet_stablecoin::TotalSupply::<Test, Instance1>::get());
+	});
+}
+
+#[test]
+fn leftover_collateral_returns_to_owner() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		setup_auction();
+		
+		// 6_780 dUSD buys 11_300 ORM at $0.60, leaving 3_700 ORM for the owner
+		assert_ok!(CollateralEngine::take(RuntimeOrigin::signed(4), 0, 15_000, PRICE_ONE));
+		
+		System::assert_last_event(
+			Event::AuctionFinished { id: 0, collateral_returned: 3_700, debt_remaining: 0 }.into(),
+		);
+		assert_eq!(Balances::free_balance(4), 10_000 + 11_300);
+		assert_eq!(pallet_stablecoin::Balances::<Test, Instance1>::get(4), 220);
+		assert_eq!(Balances::free_balance(1), 5_000 + 3_700);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(pallet_stablecoin::Balances::<Test, Instance1>::get(CollateralEngine::surplus_account()), 780);
+		assert_eq!(TotalDusdDebt::<Test>::get(), pallet_stablecoin::TotalSupply::<Test, Instance1>::get());
+	});
+}
+
+#[test]
+fn stale_auction_must_be_reset() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		setup_auction();
+		
+		assert_noop!(
+			CollateralEngine::reset_auction(RuntimeOrigin::signed(3), 0),
+			Error::<Test>::AuctionNotStale
+		);
+		
+		// 61 blocks in, the price has fallen below 40% of the starting price
+		System::set_block_number(62);
+		assert_noop!(
+			CollateralEngine::take(RuntimeOrigin::signed(2), 0, 5_000, PRICE_ONE),
+			Error::<Test>::AuctionNeedsReset
+		);
+		
+		set_prices(PRICE_ONE * 4 / 10, PRICE_ONE * 8 / 10);
+		assert_ok!(CollateralEngine::reset_auction(RuntimeOrigin::signed(3), 0));
+		
+		let auction = Auctions::<Test>::get(0).unwrap();
+		assert_eq!(auction.top, PRICE_ONE * 48 / 100);
+		assert_eq!(auction.started_at, 62);
+		assert_ok!(CollateralEngine::take(RuntimeOrigin::signed(2), 0, 5_000, PRICE_ONE));
+	});
+}
+
+#[test]
+fn cage_stops_minting_liquidation_and_price_updates() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		
+		set_prices(PRICE_ONE, PRICE_ONE * 8 / 10);
+		let _ = Balances::deposit_creating(&1, 20_000);
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), NATIVE, 10_000));
+		assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 0, 1_000));
+		
+		assert_noop!(CollateralEngine::cage(RuntimeOrigin::signed(1)), BadOrigin);
+		assert_ok!(CollateralEngine::cage(RuntimeOrigin::root()));
+		System::assert_last_event(Event::Caged { at: 1 }.into());
+		assert_noop!(CollateralEngine::cage(RuntimeOrigin::root()), Error::<Test>::SystemCaged);
+		
+		assert_noop!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 0, 100), Error::<Test>::SystemCaged);
+		assert_noop!(CollateralEngine::mint_deur(RuntimeOrigin::signed(1), 0, 100), Error::<Test>::SystemCaged);
+		assert_noop!(CollateralEngine::create_cdp(RuntimeOrigin::signed(2), NATIVE, 100), Error::<Test>::SystemCaged);
+		assert_noop!(
+			CollateralEngine::deposit_collateral(RuntimeOrigin::signed(1), 0, 100),
+			Error::<Test>::SystemCaged
+		);
+		assert_noop!(CollateralEngine::liquidate(RuntimeOrigin::signed(2), 0), Error::<Test>::SystemCaged);
+		
+		// Prices are frozen at their values when the system was caged
+		set_prices(PRICE_ONE / 2, PRICE_ONE / 2);
+		assert_eq!(CollateralEngine::price(PriceFeed::Orm, DebtType::Dusd, PriceSource::Delayed), PRICE_ONE);
+		assert_eq!(CagePrices::<Test>::get(PriceFeed::Orm, DebtType::Deur), PRICE_ONE * 8 / 10);
+		
+		// Stability fees stop accruing, so the original debt still repays the CDP in full
+		System::set_block_number(10_001);
+		assert_ok!(CollateralEngine::repay_dusd(RuntimeOrigin::signed(1), 0, 1_000));
+		assert_eq!(Cdps::<Test>::get(0).unwrap().dusd_debt, 0);
+	});
+}
+
+#[test]
+fn settled_collateral_is_redeemed_at_the_frozen_prices() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		
+		set_prices(PRICE_ONE, PRICE_ONE * 8 / 10);
+		let _ = Balances::deposit_creating(&1, 40_000);
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), NATIVE, 30_000));
+		assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 0, 6_000));
+		assert_ok!(CollateralEngine::mint_deur(RuntimeOrigin::signed(1), 0, 4_000));
+		assert_ok!(Dusd::transfer(RuntimeOrigin::signed(1), 2, 2_000));
+		
+		assert_noop!(
+			CollateralEngine::settle_cdp(RuntimeOrigin::signed(3), 0),
+			Error::<Test>::SystemNotCaged
+		);
+		assert_ok!(CollateralEngine::cage(RuntimeOrigin::root()));
+		assert_noop!(
+			CollateralEngine::fix_redemption_rates(RuntimeOrigin::signed(3)),
+			Error::<Test>::UnsettledDebt
+		);
+		
+		// 6_000 dUSD at $1.00 and 4_000 dEUR at €0.80 are backed by 6_000 and 5_000 ORM
+		assert_ok!(CollateralEngine::settle_cdp(RuntimeOrigin::signed(3), 0));
+		System::assert_last_event(
+			Event::CdpSettled {
+				cdp_id: 0,
+				dusd_debt: 6_000,
+				deur_debt: 4_000,
+				collateral_seized: 11_000,
+			}
+			.into(),
+		);
+		let cdp = Cdps::<Test>::get(0).unwrap();
+		assert_eq!(cdp.collateral, 19_000);
+		assert_eq!(CollateralEngine::cdp_debt(&cdp), (0, 0));
+		assert_eq!(SettledCollateral::<Test>::get(DebtType::Dusd, NATIVE), 6_000);
+		assert_eq!(SettledCollateral::<Test>::get(DebtType::Deur, NATIVE), 5_000);
+		assert_eq!(Balances::free_balance(CollateralEngine::vault_account()), 11_000);
+		
+		assert_noop!(
+			CollateralEngine::redeem(RuntimeOrigin::signed(2), DebtType::Dusd, 2_000),
+			Error::<Test>::RedemptionNotAvailable
+		);
+		assert_ok!(CollateralEngine::fix_redemption_rates(RuntimeOrigin::signed(3)));
+		assert_eq!(RedemptionRates::<Test>::get(DebtType::Dusd, NATIVE), Some(PRICE_ONE));
+		assert_eq!(RedemptionRates::<Test>::get(DebtType::Deur, NATIVE), Some(PRICE_ONE * 5 / 4));
+		assert_noop!(
+			CollateralEngine::fix_redemption_rates(RuntimeOrigin::signed(3)),
+			Error::<Test>::RedemptionRatesAlreadyFixed
+		);
+		
+		assert_ok!(CollateralEngine::redeem(RuntimeOrigin::signed(2), DebtType::Dusd, 2_000));
+		assert_eq!(Balances::free_balance(2), 2_000);
+		assert_eq!(pallet_stablecoin::Balances::<Test, Instance1>::get(2), 0);
+		
+		assert_ok!(CollateralEngine::redeem(RuntimeOrigin::signed(1), DebtType::Deur, 4_000));
+		System::assert_has_event(
+			Event::CollateralRedeemed { who: 1, collateral_type: NATIVE, amount: 5_000 }.into(),
+		);
+		System::assert_last_event(
+			Event::StablecoinRedeemed { who: 1, currency: DebtType::Deur, amount: 4_000 }.into(),
+		);
+		
+		// The owner keeps the excess collateral
+		assert_ok!(CollateralEngine::close_cdp(RuntimeOrigin::signed(1), 0));
+		assert_eq!(Balances::free_balance(1), 10_000 + 19_000 + 5_000);
+		assert_eq!(SettledCollateral::<Test>::get(DebtType::Dusd, NATIVE), 4_000);
+		assert_eq!(TotalDusdDebt::<Test>::get(), pallet_stablecoin::TotalSupply::<Test, Instance1>::get());
+	});
+}
+
+#[test]
+fn underwater_cdps_lower_the_redemption_rate() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		
+		setup_liquidatable_position();
+		set_prices(PRICE_ONE * 3 / 10, PRICE_ONE * 8 / 10);
+		assert_ok!(CollateralEngine::cage(RuntimeOrigin::root()));
+		
+		// 6_000 dUSD would need 20_000 ORM at $0.30, but only 15_000 is there
+		assert_ok!(CollateralEngine::settle_cdp(RuntimeOrigin::signed(3), 0));
+		assert_eq!(Cdps::<Test>::get(0).unwrap().collateral, 0);
+		
+		assert_ok!(CollateralEngine::fix_redemption_rates(RuntimeOrigin::signed(3)));
+		assert_eq!(RedemptionRates::<Test>::get(DebtType::Dusd, NATIVE), Some(PRICE_ONE * 5 / 2));
+		
+		assert_ok!(CollateralEngine::redeem(RuntimeOrigin::signed(2), DebtType::Dusd, 6_000));
+		assert_eq!(Balances::free_balance(2), 1_000 + 15_000);
+	});
+}
+
+#[test]
+fn reserve_backed_debt_is_left_out_of_the_redemption_rates() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		
+		setup_liquidatable_position();
+		assert_ok!(CollateralEngine::mint_reserve_backed(
+			CollateralEngine::issuer(),
+			DebtType::Dusd,
+			&3,
+			3_990,
+			10
+		));
+		assert_eq!(TotalDusdDebt::<Test>::get(), 10_000);
+		assert_eq!(ReserveBackedDebt::<Test>::get(DebtType::Dusd), 4_000);
+		assert_eq!(CollateralEngine::collateral_backed_debt(DebtType::Dusd), 6_000);
+		assert_ok!(CollateralEngine::cage(RuntimeOrigin::root()));
+		
+		// Only the 6_000 dUSD minted against CDPs shares the collateral set aside for it
+		assert_ok!(CollateralEngine::settle_cdp(RuntimeOrigin::signed(3), 0));
+		assert_ok!(CollateralEngine::fix_redemption_rates(RuntimeOrigin::signed(3)));
+		assert_eq!(RedemptionRates::<Test>::get(DebtType::Dusd, NATIVE), Some(PRICE_ONE));
+		
+		assert_ok!(CollateralEngine::burn_reserve_backed(
+			CollateralEngine::issuer(),
+			DebtType::Dusd,
+			&3,
+			3_990
+		));
+		assert_eq!(ReserveBackedDebt::<Test>::get(DebtType::Dusd), 10);
+		assert_eq!(TotalDusdDebt::<Test>::get(), pallet_stablecoin::TotalSupply::<Test, Instance1>::get());
+	});
+}
+
+#[test]
+fn running_auctions_are_skipped_at_shutdown() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		setup_auction();
+		
+		assert_noop!(
+			CollateralEngine::skip_auction(RuntimeOrigin::signed(3), 0),
+			Error::<Test>::SystemNotCaged
+		);
+		assert_ok!(CollateralEngine::cage(RuntimeOrigin::root()));
+		assert_noop!(
+			CollateralEngine::take(RuntimeOrigin::signed(2), 0, 5_000, PRICE_ONE),
+			Error::<Test>::SystemCaged
+		);
+		assert_noop!(
+			CollateralEngine::fix_redemption_rates(RuntimeOrigin::signed(3)),
+			Error::<Test>::AuctionsOutstanding
+		);
+		
+		// The unsold collateral and the debt go back to the owner, without the penalty
+		assert_ok!(CollateralEngine::skip_auction(RuntimeOrigin::signed(3), 0));
+		System::assert_last_event(
+			Event::AuctionSkipped {
+				id: 0,
+				owner: 1,
+				cdp_id: 2,
+				collateral_returned: 15_000,
+				debt_returned: 6_000,
+			}
+			.into(),
+		);
+		let cdp = Cdps::<Test>::get(2).unwrap();
+		assert_eq!(cdp.collateral, 15_000);
+		assert_eq!(CollateralEngine::cdp_debt(&cdp), (6_000, 0));
+		assert_eq!(CdpOwner::<Test>::get(2), Some(1));
+		assert_eq!(TotalCollateral::<Test>::get(NATIVE), 35_000);
+		
+		assert_ok!(CollateralEngine::settle_cdp(RuntimeOrigin::signed(3), 2));
+		assert_ok!(CollateralEngine::settle_cdp(RuntimeOrigin::signed(3), 1));
+		assert_eq!(Cdps::<Test>::get(2).unwrap().collateral, 3_000);
+		assert_eq!(Cdps::<Test>::get(1).unwrap().collateral, 6_000);
+		
+		assert_ok!(CollateralEngine::fix_redemption_rates(RuntimeOrigin::signed(3)));
+		assert_eq!(RedemptionRates::<Test>::get(DebtType::Dusd, NATIVE), Some(PRICE_ONE * 2));
+	});
+}
+
+#[test]
+fn token_collateral_is_held_in_the_vault() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		
+		set_prices(PRICE_ONE, PRICE_ONE * 8 / 10);
+		assert_ok!(OriumToken::mint(RuntimeOrigin::root(), 1, 10_000));
+		let _ = Balances::deposit_creating(&1, 10_000);
+		
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), TOKEN, 6_000));
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), NATIVE, 3_000));
+		assert_eq!(pallet_orium_token::Balances::<Test>::get(1), 4_000);
+		assert_eq!(pallet_orium_token::Balances::<Test>::get(CollateralEngine::vault_account()), 6_000);
+		assert_eq!(Balances::reserved_balance(1), 3_000);
+		assert_eq!(TotalCollateral::<Test>::get(TOKEN), 6_000);
+		assert_eq!(TotalCollateral::<Test>::get(NATIVE), 3_000);
+		
+		assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 0, 4_000));
+		assert_noop!(
+			CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 1, 2_001),
+			Error::<Test>::CollateralRatioTooLow
+		);
+		assert_eq!(Cdps::<Test>::get(0).unwrap().dusd_debt, 4_000);
+		assert_eq!(Cdps::<Test>::get(1).unwrap().dusd_debt, 0);
+		
+		assert_ok!(CollateralEngine::repay_dusd(RuntimeOrigin::signed(1), 0, 4_000));
+		assert_ok!(CollateralEngine::close_cdp(RuntimeOrigin::signed(1), 0));
+		assert_eq!(pallet_orium_token::Balances::<Test>::get(1), 10_000);
+		assert_eq!(pallet_orium_token::Balances::<Test>::get(CollateralEngine::vault_account()), 0);
+		assert!(Cdps::<Test>::get(1).is_some());
+	});
+}
+
+#[test]
+fn unknown_collateral_type_is_rejected() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		
+		let _ = Balances::deposit_creating(&1, 10_000);
+		
+		assert_noop!(
+			CollateralEngine::create_cdp(RuntimeOrigin::signed(1), 7, 5_000),
+			Error::<Test>::UnknownCollateralType
+		);
+	});
+}
+
+#[test]
+fn stability_fees_follow_the_collateral_type() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		
+		set_prices(PRICE_ONE, PRICE_ONE * 8 / 10);
+		assert_ok!(CollateralEngine::update_collateral_type(
+			RuntimeOrigin::root(),
+			TOKEN,
+			CollateralType { stability_fee: 0, ..collateral_type(CollateralAsset::OriumToken) },
+		));
+		assert_ok!(OriumToken::mint(RuntimeOrigin::root(), 1, 50_000));
+		let _ = Balances::deposit_creating(&1, 100_000);
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), NATIVE, 50_000));
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), TOKEN, 50_000));
+		assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 0, 10_000));
+		assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 1, 10_000));
+		
+		System::set_block_number(10_001);
+		assert_ok!(CollateralEngine::accrue_stability_fees());
+		
+		assert_eq!(CollateralEngine::cdp_debt(&Cdps::<Test>::get(0).unwrap()), (10_512, 0));
+		assert_eq!(CollateralEngine::cdp_debt(&Cdps::<Test>::get(1).unwrap()), (10_000, 0));
+		assert_eq!(TotalDusdDebt::<Test>::get(), 20_512);
+		assert_eq!(TotalDusdDebt::<Test>::get(), pallet_stablecoin::TotalSupply::<Test, Instance1>::get());
+	});
+}
+
+#[test]
+fn collateral_types_are_managed_by_the_admin_origin() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		
+		let params = CollateralType { min_collateral_ratio: 20000, ..collateral_type(CollateralAsset::Native) };
+		
+		assert_noop!(
+			CollateralEngine::add_collateral_type(RuntimeOrigin::signed(1), 2, params.clone()),
+			BadOrigin
+		);
+		assert_noop!(
+			CollateralEngine::add_collateral_type(RuntimeOrigin::root(), NATIVE, params.clone()),
+			Error::<Test>::CollateralTypeExists
+		);
+		assert_noop!(
+			CollateralEngine::add_collateral_type(
+				RuntimeOrigin::root(),
+				2,
+				CollateralType { liquidation_ratio: 25000, ..params.clone() },
+			),
+			Error::<Test>::InvalidRiskParameters
+		);
+		
+		assert_ok!(CollateralEngine::add_collateral_type(RuntimeOrigin::root(), 2, params.clone()));
+		assert_eq!(CollateralTypes::<Test>::get(2), Some(params.clone()));
+		System::assert_last_event(Event::CollateralTypeAdded { id: 2 }.into());
+		
+		assert_noop!(
+			CollateralEngine::update_collateral_type(RuntimeOrigin::signed(1), 2, params.clone()),
+			BadOrigin
+		);
+		assert_noop!(
+			CollateralEngine::update_collateral_type(RuntimeOrigin::root(), 3, params.clone()),
+			Error::<Test>::UnknownCollateralType
+		);
+		assert_ok!(CollateralEngine::update_collateral_type(
+			RuntimeOrigin::root(),
+			2,
+			CollateralType { min_collateral_ratio: 17500, ..params },
+		));
+		assert_eq!(CollateralTypes::<Test>::get(2).unwrap().min_collateral_ratio, 17500);
+		System::assert_last_event(Event::CollateralTypeUpdated { id: 2 }.into());
+	});
+}
+
+#[test]
+fn collateral_types_are_capped() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		
+		let params = collateral_type(CollateralAsset::Native);
+		assert_ok!(CollateralEngine::add_collateral_type(RuntimeOrigin::root(), 2, params.clone()));
+		assert_noop!(
+			CollateralEngine::add_collateral_type(RuntimeOrigin::root(), 3, params),
+			Error::<Test>::TooManyCollateralTypes
+		);
+	});
+}
+
+#[test]
+fn cdp_ownership_can_be_transferred() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		
+		set_prices(PRICE_ONE, PRICE_ONE * 8 / 10);
+		let _ = Balances::deposit_creating(&1, 10_000);
+		let _ = Balances::deposit_creating(&2, 1_000);
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), NATIVE, 6_000));
+		assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 0, 2_000));
+		assert_ok!(CollateralEngine::allow_cdp_manager(RuntimeOrigin::signed(1), 0, 3, true));
+		
+		assert_noop!(
+			CollateralEngine::transfer_cdp_ownership(RuntimeOrigin::signed(2), 0, 2),
+			Error::<Test>::NotAuthorized
+		);
+		assert_ok!(CollateralEngine::transfer_cdp_ownership(RuntimeOrigin::signed(1), 0, 2));
+		System::assert_last_event(Event::CdpTransferred { cdp_id: 0, from: 1, to: 2 }.into());
+		
+		// The reserved collateral moves with the CDP, the debt stays on it
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::reserved_balance(2), 6_000);
+		assert_eq!(CdpOwner::<Test>::get(0), Some(2));
+		assert!(CollateralEngine::cdps_of(&1).is_empty());
+		assert_eq!(CollateralEngine::cdps_of(&2), vec![0]);
+		assert_eq!(CollateralEngine::cdp_debt(&Cdps::<Test>::get(0).unwrap()), (2_000, 0));
+		
+		// Neither the previous owner nor its managers keep access
+		assert!(!CdpManagers::<Test>::contains_key(0, 3));
+		assert_noop!(
+			CollateralEngine::withdraw_collateral(RuntimeOrigin::signed(1), 0, 100),
+			Error::<Test>::NotAuthorized
+		);
+		assert_noop!(
+			CollateralEngine::withdraw_collateral(RuntimeOrigin::signed(3), 0, 100),
+			Error::<Test>::NotAuthorized
+		);
+		assert_ok!(CollateralEngine::withdraw_collateral(RuntimeOrigin::signed(2), 0, 100));
+		assert_eq!(Balances::free_balance(2), 1_100);
+	});
+}
+
+#[test]
+fn managers_act_on_behalf_of_the_owner() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		
+		set_prices(PRICE_ONE, PRICE_ONE * 8 / 10);
+		let _ = Balances::deposit_creating(&1, 10_000);
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), NATIVE, 6_000));
+		
+		assert_noop!(
+			CollateralEngine::allow_cdp_manager(RuntimeOrigin::signed(3), 0, 3, true),
+			Error::<Test>::NotAuthorized
+		);
+		assert_noop!(
+			CollateralEngine::mint_dusd(RuntimeOrigin::signed(3), 0, 1_000),
+			Error::<Test>::NotAuthorized
+		);
+		assert_ok!(CollateralEngine::allow_cdp_manager(RuntimeOrigin::signed(1), 0, 3, true));
+		System::assert_last_event(Event::CdpManagerUpdated { cdp_id: 0, manager: 3, allowed: true }.into());
+		
+		// Stablecoin is minted to the manager, collateral is locked from and returned to the owner
+		assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(3), 0, 1_000));
+		assert_eq!(pallet_stablecoin::Balances::<Test, Instance1>::get(3), 1_000);
+		assert_ok!(CollateralEngine::deposit_collateral(RuntimeOrigin::signed(3), 0, 1_000));
+		assert_eq!(Balances::reserved_balance(1), 7_000);
+		assert_ok!(CollateralEngine::withdraw_collateral(RuntimeOrigin::signed(3), 0, 2_000));
+		assert_eq!(Balances::free_balance(1), 5_000);
+		
+		// Managers cannot hand the CDP on
+		assert_noop!(
+			CollateralEngine::transfer_cdp_ownership(RuntimeOrigin::signed(3), 0, 3),
+			Error::<Test>::NotAuthorized
+		);
+		
+		assert_ok!(CollateralEngine::allow_cdp_manager(RuntimeOrigin::signed(1), 0, 3, false));
+		assert_noop!(
+			CollateralEngine::repay_dusd(RuntimeOrigin::signed(3), 0, 1_000),
+			Error::<Test>::NotAuthorized
+		);
+	});
+}
+
+#[test]
+fn migration_gives_existing_cdps_ids() {
+	new_test_ext().execute_with(|| {
+		use crate::migrations::v1::{v0, MigrateV0ToV1};
+		use codec::Encode;
+		use frame_support::{
+			storage::unhashed,
+			traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+		};
+		use sp_io::hashing::{blake2_128, twox_128};
+		
+		frame_support::parameter_types! {
+			pub NativeType: CollateralType<u128> = CollateralType {
+				asset: CollateralAsset::Native,
+				price_feed: PriceFeed::Orm,
+				min_collateral_ratio: 16000,
+				liquidation_ratio: 14000,
+				stability_fee: 0,
+				debt_ceiling: 1_000_000,
+				dust: 0,
+			};
+		}
+		
+		// Writes the bytes the baseline pallet stored, without going through any of its types
+		let raw_key = |item: &[u8], owner: Option<u64>| {
+			let mut key = twox_128(b"CollateralEngine").to_vec();
+			key.extend(twox_128(item));
+			if let Some(owner) = owner {
+				key.extend(blake2_128(&owner.encode()));
+				key.extend(owner.encode());
+			}
+			key
+		};
+		StorageVersion::new(0).put::<CollateralEngine>();
+		CollateralTypes::<Test>::remove(NATIVE);
+		unhashed::put_raw(&raw_key(b"Cdps", Some(1)), &(5_000u128, 1_000u128, 0u128, 1u32).encode());
+		unhashed::put_raw(&raw_key(b"Cdps", Some(2)), &(3_000u128, 500u128, 200u128, 2u32).encode());
+		unhashed::put_raw(&raw_key(b"TotalCollateral", None), &8_000u128.encode());
+		unhashed::put_raw(&raw_key(b"LiquidationQueue", Some(2)), &true.encode());
+		unhashed::put_raw(&raw_key(b"OrmUsdPrice", None), &PRICE_ONE.encode());
+		unhashed::put_raw(&raw_key(b"OrmEurPrice", None), &PRICE_ONE.encode());
+		assert_eq!(v0::Cdps::<Test>::iter().count(), 2);
+		
+		MigrateV0ToV1::<Test, NativeType>::on_runtime_upgrade();
+		
+		assert_eq!(CollateralEngine::on_chain_storage_version(), 1);
+		assert_eq!(NextCdpId::<Test>::get(), 2);
+		assert_eq!(Cdps::<Test>::iter().count(), 2);
+		assert_eq!(CollateralTypes::<Test>::get(NATIVE), Some(NativeType::get()));
+		assert_eq!(TotalCollateral::<Test>::get(NATIVE), 8_000);
+		assert_eq!(unhashed::get_raw(&raw_key(b"TotalCollateral", None)), None);
+		let id = CollateralEngine::cdps_of(&2)[0];
+		assert_eq!(CdpOwner::<Test>::get(id), Some(2));
+		assert_eq!(
+			Cdps::<Test>::get(id),
+			Some(Cdp { collateral_type: NATIVE, collateral: 3_000, dusd_debt: 500, deur_debt: 200, last_update: 2 })
+		);
+		assert_eq!(CollateralEngine::cdps_of(&1).len(), 1);
+		assert_eq!(NormalizedDebt::<Test>::get(NATIVE, DebtType::Dusd), 1_500);
+		assert_eq!(NormalizedDebt::<Test>::get(NATIVE, DebtType::Deur), 200);
+		assert!(!LiquidationQueue::<Test>::get(id));
+		assert_eq!(unhashed::get_raw(&raw_key(b"LiquidationQueue", Some(2))), None);
+		assert_eq!(unhashed::get_raw(&raw_key(b"OrmUsdPrice", None)), None);
+		assert_eq!(unhashed::get_raw(&raw_key(b"OrmEurPrice", None)), None);
+		
+		// Running it again does nothing
+		MigrateV0ToV1::<Test, NativeType>::on_runtime_upgrade();
+		assert_eq!(NextCdpId::<Test>::get(), 2);
+		assert_eq!(Cdps::<Test>::iter().count(), 2);
+		assert_eq!(TotalCollateral::<Test>::get(NATIVE), 8_000);
+	});
+}
+
+#[test]
+fn global_debt_ceiling_caps_issuance() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		
+		set_prices(PRICE_ONE, PRICE_ONE * 8 / 10);
+		let _ = Balances::deposit_creating(&1, 20_000);
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), NATIVE, 20_000));
+		
+		assert_noop!(
+			CollateralEngine::set_global_debt_ceiling(RuntimeOrigin::signed(1), DebtType::Dusd, Some(5_000)),
+			BadOrigin
+		);
+		assert_ok!(CollateralEngine::set_global_debt_ceiling(RuntimeOrigin::root(), DebtType::Dusd, Some(5_000)));
+		System::assert_last_event(
+			Event::GlobalDebtCeilingUpdated { currency: DebtType::Dusd, ceiling: Some(5_000) }.into(),
+		);
+		
+		assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 0, 5_000));
+		assert_noop!(
+			CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 0, 1),
+			Error::<Test>::GlobalDebtCeilingExceeded
+		);
+		
+		// The ceiling only applies to its own stablecoin
+		assert_ok!(CollateralEngine::mint_deur(RuntimeOrigin::signed(1), 0, 1_000));
+		
+		// Repayment makes room again, and the ceiling can be lifted
+		assert_ok!(CollateralEngine::repay_dusd(RuntimeOrigin::signed(1), 0, 1_000));
+		assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 0, 1_000));
+		assert_ok!(CollateralEngine::set_global_debt_ceiling(RuntimeOrigin::root(), DebtType::Dusd, None));
+		assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 0, 1_000));
+	});
+}
+
+#[test]
+fn collateral_debt_ceiling_caps_debt_of_the_type() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		
+		set_prices(PRICE_ONE, PRICE_ONE * 8 / 10);
+		let _ = Balances::deposit_creating(&1, 20_000);
+		let _ = Balances::deposit_creating(&2, 20_000);
+		assert_ok!(OriumToken::mint(RuntimeOrigin::root(), 2, 20_000));
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), NATIVE, 10_000));
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(2), NATIVE, 10_000));
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(2), TOKEN, 10_000));
+		
+		assert_noop!(
+			CollateralEngine::set_collateral_debt_ceiling(RuntimeOrigin::signed(1), NATIVE, 4_000),
+			BadOrigin
+		);
+		assert_noop!(
+			CollateralEngine::set_collateral_debt_ceiling(RuntimeOrigin::root(), 7, 4_000),
+			Error::<Test>::UnknownCollateralType
+		);
+		assert_ok!(CollateralEngine::set_collateral_debt_ceiling(RuntimeOrigin::root(), NATIVE, 4_000));
+		System::assert_last_event(Event::CollateralDebtCeilingUpdated { id: NATIVE, ceiling: 4_000 }.into());
+		assert_eq!(CollateralTypes::<Test>::get(NATIVE).unwrap().debt_ceiling, 4_000);
+		
+		// dUSD and dEUR drawn by every CDP of the type count towards its ceiling, valued in USD
+		assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 0, 2_000));
+		assert_ok!(CollateralEngine::mint_deur(RuntimeOrigin::signed(2), 1, 800));
+		assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(2), 1, 1_000));
+		assert_noop!(
+			CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 0, 1),
+			Error::<Test>::CollateralDebtCeilingExceeded
+		);
+		
+		// Other collateral types are unaffected
+		assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(2), 2, 5_000));
+	});
+}
+
+#[test]
+fn cdps_cannot_be_left_below_dust() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		
+		set_prices(PRICE_ONE, PRICE_ONE * 8 / 10);
+		let _ = Balances::deposit_creating(&1, 20_000);
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), NATIVE, 10_000));
+		
+		assert_noop!(CollateralEngine::set_dust(RuntimeOrigin::signed(1), NATIVE, 1_000), BadOrigin);
+		assert_ok!(CollateralEngine::set_dust(RuntimeOrigin::root(), NATIVE, 1_000));
+		System::assert_last_event(Event::DustUpdated { id: NATIVE, dust: 1_000 }.into());
+		
+		assert_noop!(
+			CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 0, 500),
+			Error::<Test>::DebtBelowDust
+		);
+		assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 0, 1_000));
+		assert_noop!(
+			CollateralEngine::repay_dusd(RuntimeOrigin::signed(1), 0, 600),
+			Error::<Test>::DebtBelowDust
+		);
+		
+		// A CDP left below a raised limit is frozen until it is repaid in full
+		assert_ok!(CollateralEngine::set_dust(RuntimeOrigin::root(), NATIVE, 2_000));
+		assert_noop!(
+			CollateralEngine::withdraw_collateral(RuntimeOrigin::signed(1), 0, 1),
+			Error::<Test>::DebtBelowDust
+		);
+		assert_ok!(CollateralEngine::repay_dusd(RuntimeOrigin::signed(1), 0, 1_000));
+		assert_ok!(CollateralEngine::withdraw_collateral(RuntimeOrigin::signed(1), 0, 10_000));
+	});
+}
+
+/// Amounts from a single unit to a trillion units of the native token.
+const AMOUNTS: [u128; 6] = [1, 999, 6_000, 1_000_000_000_000, 7_777_777_777_777_777, 1_000_000_000_000_000_000_000_000];
+
+/// Prices from a thousandth to a thousand stablecoin per unit of collateral.
+const PRICES: [u128; 5] = [PRICE_ONE / 1000, PRICE_ONE / 3, PRICE_ONE, PRICE_ONE * 7, PRICE_ONE * 1000];
+
+fn spot_ratio(collateral: u128, dusd_debt: u128, deur_debt: u128) -> u128 {
+	CollateralEngine::collateral_ratio(PriceFeed::Orm, PriceSource::Spot, collateral, dusd_debt, deur_debt).unwrap()
+}
+
+#[test]
+fn collateral_ratio_is_monotonic_in_amounts() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		set_prices(PRICE_ONE * 3 / 2, PRICE_ONE * 8 / 10);
+		
+		for pair in AMOUNTS.windows(2) {
+			let (less, more) = (pair[0], pair[1]);
+			for &other in &AMOUNTS {
+				assert!(spot_ratio(less, other, other) <= spot_ratio(more, other, other));
+				assert!(spot_ratio(other, less, other) >= spot_ratio(other, more, other));
+				assert!(spot_ratio(other, other, less) >= spot_ratio(other, other, more));
+				assert!(spot_ratio(other, less, 0) >= spot_ratio(other, more, 0));
+				assert!(spot_ratio(other, 0, less) >= spot_ratio(other, 0, more));
+			}
+		}
+	});
+}
+
+#[test]
+fn collateral_ratio_is_monotonic_in_prices() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		
+		// A dearer collateral raises the ratio; more euros per unit of collateral make the euro
+		// cheaper and lower the value of dEUR debt
+		for &(collateral, dusd_debt, deur_debt) in &[(15_000, 6_000, 0), (15_000, 0, 6_000), (1, 999, 999)] {
+			let mut previous = 0;
+			for &price in &PRICES {
+				set_prices(price, price * 8 / 10);
+				let ratio = spot_ratio(collateral, dusd_debt, deur_debt);
+				assert!(ratio >= previous);
+				previous = ratio;
+			}
+			
+			let mut previous = 0;
+			for &eur_price in &PRICES {
+				set_prices(PRICE_ONE, eur_price);
+				let ratio = spot_ratio(collateral, dusd_debt, deur_debt);
+				assert!(ratio >= previous);
+				previous = ratio;
+			}
+		}
+	});
+}
+
+#[test]
+fn collateral_math_reports_overflow() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		set_prices(PRICE_ONE * 2, PRICE_ONE);
+		
+		let ratio = |collateral, dusd_debt, deur_debt| {
+			CollateralEngine::collateral_ratio(PriceFeed::Orm, PriceSource::Spot, collateral, dusd_debt, deur_debt)
+		};
+		// The collateral is worth more than fits in 128 bits
+		assert_eq!(ratio(u128::MAX, 1, 0), Err(Error::<Test>::Overflow));
+		// dEUR debt converts to more than fits in 128 bits, or adds up past it
+		assert_eq!(ratio(1, 0, u128::MAX), Err(Error::<Test>::Overflow));
+		assert_eq!(ratio(1, u128::MAX, u128::MAX / 4), Err(Error::<Test>::Overflow));
+		// Large values that do fit are still exact
+		assert_eq!(ratio(u128::MAX / 2, u128::MAX / 2, 0), Ok(20_000));
+	});
+}
+
+#[test]
+fn cdp_health_reports_limits() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		set_prices(PRICE_ONE, PRICE_ONE * 8 / 10);
+		let _ = Balances::deposit_creating(&1, 20_000);
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), NATIVE, 15_000));
+		
+		let health = CollateralEngine::cdp_health(0).unwrap();
+		assert_eq!(health.collateral_ratio, None);
+		assert_eq!(health.liquidation_price, None);
+		assert_eq!(health.max_withdraw, 15_000);
+		assert_eq!(health.max_mint_dusd, 10_000);
+		
+		assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 0, 6_000));
+		assert_eq!(
+			CollateralEngine::cdp_health(0),
+			Ok(CdpHealth {
+				collateral_ratio: Some(25_000),
+				liquidatable: false,
+				// $7_800 of collateral against $6_000 of debt is exactly 130%
+				liquidation_price: Some(PRICE_ONE * 52 / 100),
+				max_mint_dusd: 4_000,
+				// At $1.00 and €0.80 per ORM, €3_200 is worth $4_000
+				max_mint_deur: 3_200,
+				max_withdraw: 6_000,
+				dusd_fees: 0,
+				deur_fees: 0,
+			})
+		);
+		
+		// The reported limits are exactly those the extrinsics enforce
+		assert_noop!(
+			CollateralEngine::withdraw_collateral(RuntimeOrigin::signed(1), 0, 6_001),
+			Error::<Test>::CollateralRatioTooLow
+		);
+		assert_noop!(
+			CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 0, 4_001),
+			Error::<Test>::CollateralRatioTooLow
+		);
+		assert_ok!(CollateralEngine::withdraw_collateral(RuntimeOrigin::signed(1), 0, 6_000));
+		
+		assert_eq!(CollateralEngine::cdp_health(1), Err(Error::<Test>::CdpNotFound.into()));
+	});
+}
+
+#[test]
+fn cdp_info_includes_pending_fees() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		set_prices(PRICE_ONE, PRICE_ONE * 8 / 10);
+		let _ = Balances::deposit_creating(&1, 20_000);
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), NATIVE, 15_000));
+		assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 0, 6_000));
+		
+		// A year of the 5% stability fee, not yet compounded into the rates
+		System::set_block_number(10_001);
+		let info = CollateralEngine::cdp_info(0).unwrap();
+		let fees = CollateralEngine::cdp_health(0).unwrap().dusd_fees;
+		assert_eq!(info.owner, 1);
+		assert!(fees > 300);
+		assert_eq!(info.dusd_debt, 6_000 + fees);
+		
+		assert_ok!(CollateralEngine::accrue_stability_fees());
+		assert_eq!(CollateralEngine::cdp_info(0).unwrap().dusd_debt, info.dusd_debt);
+		assert_eq!(CollateralEngine::cdp_health(0).unwrap().dusd_fees, 0);
+		
+		assert_eq!(CollateralEngine::cdp_info(1), None);
+	});
+}
+
+#[test]
+fn liquidatable_cdps_are_ordered_by_shortfall() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		set_prices(PRICE_ONE, PRICE_ONE * 8 / 10);
+		let _ = Balances::deposit_creating(&1, 100_000);
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), NATIVE, 15_000));
+		assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 0, 6_000));
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), NATIVE, 20_000));
+		assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 1, 9_000));
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), NATIVE, 30_000));
+		assert!(CollateralEngine::liquidatable_cdps(0, 10).is_empty());
+		
+		// At $0.40, CDP 0 is $1_800 short of 130% and CDP 1 is $3_700 short
+		set_prices(PRICE_ONE * 4 / 10, PRICE_ONE * 8 / 10);
+		let cdp_0 = LiquidatableCdp { id: 0, collateral_ratio: 10_000, shortfall: 1_800 };
+		let cdp_1 = LiquidatableCdp { id: 1, collateral_ratio: 8_888, shortfall: 3_700 };
+		assert_eq!(CollateralEngine::liquidatable_cdps(0, 10), vec![cdp_1.clone(), cdp_0.clone()]);
+		
+		// A page only checks the ids it covers
+		assert_eq!(CollateralEngine::liquidatable_cdps(0, 1), vec![cdp_0]);
+		assert_eq!(CollateralEngine::liquidatable_cdps(1, 10), vec![cdp_1]);
+		assert!(CollateralEngine::liquidatable_cdps(2, 10).is_empty());
+		assert!(CollateralEngine::liquidatable_cdps(CollateralEngine::next_cdp_id(), u32::MAX).is_empty());
+	});
+}
+
+#[test]
+fn price_updates_queue_unsafe_cdps_for_on_idle() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		setup_liquidatable_position();
+		let _ = Balances::deposit_creating(&3, 100_000);
+		assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(3), NATIVE, 30_000));
+		assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(3), 1, 6_000));
+		
+		// Both CDPs are safe, so a scan queues nothing
+		CollateralEngine::on_idle(1, Weight::MAX);
+		assert_eq!(PendingScan::<Test>::get(), 0);
+		assert_eq!(LiquidationQueue::<Test>::iter_keys().count(), 0);
+		
+		// At $0.40 only CDP 0 falls below 130%, and nothing happens without weight to spare
+		set_prices(PRICE_ONE * 4 / 10, PRICE_ONE * 8 / 10);
+		assert_eq!(PendingScan::<Test>::get(), 2);
+		assert_eq!(CollateralEngine::on_idle(1, Weight::zero()), Weight::zero());
+		assert!(Cdps::<Test>::get(0).is_some());
+		
+		CollateralEngine::on_idle(1, Weight::MAX);
+		assert_eq!(PendingScan::<Test>::get(), 0);
+		assert!(Cdps::<Test>::get(0).is_none());
+		assert!(Cdps::<Test>::get(1).is_some());
+		assert_eq!(Auctions::<Test>::get(0).unwrap().owner, 1);
+		assert_eq!(LiquidationQueue::<Test>::iter_keys().count(), 0);
+	});
+}
+
+#[test]
+fn offchain_worker_submits_unsigned_liquidations() {
+	use codec::Decode;
+	use sp_core::offchain::{testing, OffchainWorkerExt, TransactionPoolExt};
+	
+	let (offchain, _) = testing::TestOffchainExt::new();
+	let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainWorkerExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+	
+	ext.execute_with(|| {
+		System::set_block_number(1);
+		setup_liquidatable_position();
+		
+		CollateralEngine::offchain_worker(1);
+		assert!(pool_state.read().transactions.is_empty());
+		
+		set_prices(PRICE_ONE * 4 / 10, PRICE_ONE * 8 / 10);
+		CollateralEngine::offchain_worker(1);
+		
+		let transaction = pool_state.write().transactions.pop().unwrap();
+		let extrinsic = Extrinsic::decode(&mut &*transaction).unwrap();
+		let call = crate::Call::liquidate_unsigned { cdp_id: 0 };
+		assert_eq!(extrinsic.function, RuntimeCall::CollateralEngine(call.clone()));
+		
+		assert_ok!(CollateralEngine::validate_unsigned(TransactionSource::Local, &call));
+		assert_ok!(CollateralEngine::liquidate_unsigned(RuntimeOrigin::none(), 0));
+		assert!(Auctions::<Test>::get(0).is_some());
+		
+		// The CDP is gone, so a second submission is stale
+		assert_eq!(
+			CollateralEngine::validate_unsigned(TransactionSource::Local, &call),
+			Err(InvalidTransaction::Stale.into())
+		);
+		assert_noop!(CollateralEngine::liquidate_unsigned(RuntimeOrigin::signed(2), 0), BadOrigin);
+	});
+}
+
+#[test]
+fn offchain_worker_continues_its_scan_in_the_next_block() {
+	use codec::Decode;
+	use sp_core::offchain::{testing, OffchainWorkerExt, TransactionPoolExt};
+	
+	let (offchain, _) = testing::TestOffchainExt::new();
+	let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainWorkerExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+	
+	ext.execute_with(|| {
+		System::set_block_number(1);
+		set_prices(PRICE_ONE, PRICE_ONE * 8 / 10);
+		for (cdp_id, owner) in [1u64, 3, 4].into_iter().enumerate() {
+			let _ = Balances::deposit_creating(&owner, 20_000);
+			assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(owner), NATIVE, 15_000));
+			assert_ok!(CollateralEngine::mint_dusd(
+				RuntimeOrigin::signed(owner),
+				cdp_id as CdpId,
+				6_000
+			));
+		}
+		set_prices(PRICE_ONE * 4 / 10, PRICE_ONE * 8 / 10);
+		let submitted = || -> Vec<CdpId> {
+			pool_state
+				.write()
+				.transactions
+				.drain(..)
+				.map(|transaction| match Extrinsic::decode(&mut &*transaction).unwrap().function {
+					RuntimeCall::CollateralEngine(crate::Call::liquidate_unsigned { cdp_id }) =>
+						cdp_id,
+					call => panic!("unexpected call {call:?}"),
+				})
+				.collect()
+		};
+		
+		// All three CDPs are unsafe, but only `MaxAutoLiquidations` are submitted per block and the
+		// next block picks up where this one stopped
+		CollateralEngine::offchain_worker(1);
+		assert_eq!(submitted(), vec![0, 1]);
+		CollateralEngine::offchain_worker(2);
+		assert_eq!(submitted(), vec![2, 0]);
+	});
+}
+
+/// Scales amounts up so that rounding stays out of the way.
+const SCALE: u128 = 1_000_000;
+
+/// Opens a CDP for account 1 holding 15_000 ORM against 3_000 dUSD and 3_000 dEUR, all scaled
+/// by `SCALE`, and hands the stablecoin to account 2.
+fn setup_multi_debt_position() {
+	set_prices(PRICE_ONE, PRICE_ONE * 8 / 10);
+	let _ = Balances::deposit_creating(&1, 20_000 * SCALE);
+	let _ = Balances::deposit_creating(&2, 1_000);
+	assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), NATIVE, 15_000 * SCALE));
+	assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), 0, 3_000 * SCALE));
+	assert_ok!(CollateralEngine::mint_deur(RuntimeOrigin::signed(1), 0, 3_000 * SCALE));
+	assert_ok!(Dusd::transfer(RuntimeOrigin::signed(1), 2, 3_000 * SCALE));
+	assert_ok!(Deur::transfer(RuntimeOrigin::signed(1), 2, 3_000 * SCALE));
+}
+
+#[test]
+fn liquidate_partial_restores_the_minimum_ratio() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		setup_multi_debt_position();
+		
+		assert_noop!(
+			CollateralEngine::liquidate_partial(RuntimeOrigin::signed(2), 0),
+			Error::<Test>::CdpNotLiquidatable
+		);
+		
+		// $8_625 of collateral against $3_000 plus $3_750 of debt is 127.8%. Closing 60% of
+		// both debts, and seizing their value plus the 13% penalty, restores 150%.
+		set_prices(PRICE_ONE * 575 / 1000, PRICE_ONE * 46 / 100);
+		assert_ok!(CollateralEngine::liquidate_partial(RuntimeOrigin::signed(2), 0));
+		
+		let (dusd_repaid, deur_repaid, collateral_seized) = (1_802_125_552, 1_802_125_552, 7_968_529_069);
+		let cdp = Cdps::<Test>::get(0).unwrap();
+		assert_eq!(cdp.collateral, 15_000 * SCALE - collateral_seized);
+		assert_eq!(cdp.dusd_debt, 3_000 * SCALE - dusd_repaid);
+		assert_eq!(cdp.deur_debt, 3_000 * SCALE - deur_repaid);
+		let ratio = CollateralEngine::collateral_ratio(
+			PriceFeed::Orm,
+			PriceSource::Spot,
+			cdp.collateral,
+			cdp.dusd_debt,
+			cdp.deur_debt,
+		)
+		.unwrap();
+		// A basis point above, as the liquidation aims
+		assert_eq!(ratio, 15_001);
+		assert_eq!(CollateralEngine::is_liquidatable(&cdp), Ok(false));
+		
+		assert_eq!(Balances::free_balance(2), 1_000 + collateral_seized);
+		assert_eq!(Balances::reserved_balance(1), cdp.collateral);
+		assert_eq!(pallet_stablecoin::Balances::<Test, Instance1>::get(2), 3_000 * SCALE - dusd_repaid);
+		assert_eq!(pallet_stablecoin::Balances::<Test, Instance2>::get(2), 3_000 * SCALE - deur_repaid);
+		assert_eq!(TotalCollateral::<Test>::get(NATIVE), cdp.collateral);
+		assert_eq!(TotalDusdDebt::<Test>::get(), pallet_stablecoin::TotalSupply::<Test, Instance1>::get());
+		assert_eq!(TotalDeurDebt::<Test>::get(), pallet_stablecoin::TotalSupply::<Test, Instance2>::get());
+		
+		System::assert_last_event(
+			Event::CdpPartiallyLiquidated { cdp_id: 0, liquidator: 2, dusd_repaid, deur_repaid, collateral_seized }
+				.into(),
+		);
+	});
+}
+
+#[test]
+fn liquidate_partial_is_capped_by_the_close_factor() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		setup_multi_debt_position();
+		
+		// At 111%, below 100% plus the penalty, 150% is out of reach and the close factor
+		// limits the liquidation to 80% of each debt
+		set_prices(PRICE_ONE / 2, PRICE_ONE * 4 / 10);
+		assert_ok!(CollateralEngine::liquidate_partial(RuntimeOrigin::signed(2), 0));
+		
+		let cdp = Cdps::<Test>::get(0).unwrap();
+		assert_eq!(cdp.collateral, 2_796 * SCALE);
+		assert_eq!(cdp.dusd_debt, 600 * SCALE);
+		assert_eq!(cdp.deur_debt, 600 * SCALE);
+		assert_eq!(CollateralEngine::is_liquidatable(&cdp), Ok(true));
+		
+		// What is left can still be liquidated in full
+		assert_ok!(CollateralEngine::liquidate(RuntimeOrigin::signed(2), 0));
+		assert!(Cdps::<Test>::get(0).is_none());
+		assert_eq!(TotalDusdDebt::<Test>::get(), 0);
+		assert_eq!(TotalDeurDebt::<Test>::get(), 0);
 	});
 }
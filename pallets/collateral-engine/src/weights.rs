@@ -1,29 +1,6 @@
-
-//! Autogenerated weights for pallet_template
+//! Weights for pallet_collateral_engine
 //!
-//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 4.0.0-dev
-//! DATE: 2023-04-06, STEPS: `50`, REPEAT: `20`, LOW RANGE: `[]`, HIGH RANGE: `[]`
-//! WORST CASE MAP SIZE: `1000000`
-//! HOSTNAME: `Alexs-MacBook-Pro-2.local`, CPU: `<UNKNOWN>`
-//! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("dev"), DB CACHE: 1024
-
-// Executed Command:
-// ../../target/release/node-template
-// benchmark
-// pallet
-// --chain
-// dev
-// --pallet
-// pallet_template
-// --extrinsic
-// *
-// --steps=50
-// --repeat=20
-// --wasm-execution=compiled
-// --output
-// pallets/template/src/weights.rs
-// --template
-// ../../.maintain/frame-weight-template.hbs
+//! Estimated from the template benchmarks until the pallet is benchmarked on reference hardware.
 
 #![cfg_attr(rustfmt, rustfmt_skip)]
 #![allow(unused_parens)]
@@ -32,59 +9,407 @@
 use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
 use core::marker::PhantomData;
 
-/// Weight functions needed for pallet_template.
+/// Weight functions needed for pallet_collateral_engine.
 pub trait WeightInfo {
-	fn do_something() -> Weight;
-	fn cause_error() -> Weight;
+	fn create_cdp() -> Weight;
+	fn deposit_collateral() -> Weight;
+	fn withdraw_collateral() -> Weight;
+	fn mint_dusd() -> Weight;
+	fn mint_deur() -> Weight;
+	fn repay_dusd() -> Weight;
+	fn repay_deur() -> Weight;
+	fn close_cdp() -> Weight;
+	fn liquidate() -> Weight;
+	fn accrue_stability_fees(c: u32, ) -> Weight;
+	fn start_auction() -> Weight;
+	fn take() -> Weight;
+	fn reset_auction() -> Weight;
+	fn cage(c: u32, ) -> Weight;
+	fn skip_auction() -> Weight;
+	fn settle_cdp() -> Weight;
+	fn fix_redemption_rates(c: u32, ) -> Weight;
+	fn redeem(c: u32, ) -> Weight;
+	fn add_collateral_type() -> Weight;
+	fn update_collateral_type() -> Weight;
+	fn transfer_cdp_ownership() -> Weight;
+	fn allow_cdp_manager() -> Weight;
+	fn set_global_debt_ceiling() -> Weight;
+	fn set_collateral_debt_ceiling() -> Weight;
+	fn set_dust() -> Weight;
+	fn set_twap_window() -> Weight;
+	fn liquidate_unsigned() -> Weight;
+	fn liquidate_partial() -> Weight;
 }
 
-/// Weights for pallet_template using the Substrate node and recommended hardware.
+/// Weights for pallet_collateral_engine using the Substrate node and recommended hardware.
 pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
-	/// Storage: Template Something (r:0 w:1)
-	/// Proof: Template Something (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
-	fn do_something() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `0`
-		//  Estimated: `0`
-		// Minimum execution time: 8_000_000 picoseconds.
-		Weight::from_parts(9_000_000, 0)
+	/// Storage: CollateralEngine CagedAt, CollateralTypes, NextCdpId, TotalCollateral, Cdps, CdpOwner, OwnerCdps, System Account (r:5 w:6)
+	fn create_cdp() -> Weight {
+		Weight::from_parts(38_000_000, 3_593)
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+	}
+	/// Storage: CollateralEngine CagedAt, Cdps, CdpOwner, CdpManagers, CollateralTypes, TotalCollateral, System Account (r:7 w:3)
+	fn deposit_collateral() -> Weight {
+		Weight::from_parts(36_000_000, 3_593)
+			.saturating_add(T::DbWeight::get().reads(7_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: CollateralEngine CagedAt, RateUpdatedAt, Cdps, CdpOwner, CdpManagers, CollateralTypes, Rates, TotalCollateral, Oracle Halted, Prices, System Account (r:14 w:3)
+	fn withdraw_collateral() -> Weight {
+		Weight::from_parts(68_000_000, 6_196)
+			.saturating_add(T::DbWeight::get().reads(14_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: CollateralEngine CagedAt, RateUpdatedAt, Cdps, CdpOwner, CdpManagers, CollateralTypes, Rates, GlobalDebtCeilings, TotalDusdDebt, ReserveBackedDebt, NormalizedDebt, Oracle Halted, Prices, Dusd Balances, TotalSupply (r:19 w:5)
+	fn mint_dusd() -> Weight {
+		Weight::from_parts(82_000_000, 6_196)
+			.saturating_add(T::DbWeight::get().reads(19_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	/// Storage: CollateralEngine CagedAt, RateUpdatedAt, Cdps, CdpOwner, CdpManagers, CollateralTypes, Rates, GlobalDebtCeilings, TotalDeurDebt, ReserveBackedDebt, NormalizedDebt, Oracle Halted, Prices, Deur Balances, TotalSupply (r:19 w:5)
+	fn mint_deur() -> Weight {
+		Weight::from_parts(82_000_000, 6_196)
+			.saturating_add(T::DbWeight::get().reads(19_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	/// Storage: CollateralEngine CagedAt, RateUpdatedAt, Cdps, CdpOwner, CdpManagers, Rates, CollateralTypes, NormalizedDebt, TotalDusdDebt, Oracle Prices, Dusd Balances, TotalSupply (r:14 w:5)
+	fn repay_dusd() -> Weight {
+		Weight::from_parts(62_000_000, 6_196)
+			.saturating_add(T::DbWeight::get().reads(14_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	/// Storage: CollateralEngine CagedAt, RateUpdatedAt, Cdps, CdpOwner, CdpManagers, Rates, CollateralTypes, NormalizedDebt, TotalDeurDebt, Oracle Prices, Deur Balances, TotalSupply (r:14 w:5)
+	fn repay_deur() -> Weight {
+		Weight::from_parts(62_000_000, 6_196)
+			.saturating_add(T::DbWeight::get().reads(14_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	/// Storage: CollateralEngine Cdps, CdpOwner, CdpManagers, CollateralTypes, OwnerCdps, LiquidationQueue, TotalCollateral, System Account (r:6 w:7)
+	fn close_cdp() -> Weight {
+		Weight::from_parts(44_000_000, 3_593)
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(7_u64))
+	}
+	/// Storage: CollateralEngine CagedAt, RateUpdatedAt, Cdps, CdpOwner, CollateralTypes, TwapWindows, Rates, OwnerCdps, CdpManagers, LiquidationQueue, TotalCollateral, NormalizedDebt, TotalDusdDebt, TotalDeurDebt, Oracle DelayedPrices, Dusd Balances, TotalSupply, Deur Balances, TotalSupply, SystemLedger SystemBadDebt, System Account (r:20 w:17)
+	fn liquidate() -> Weight {
+		Weight::from_parts(121_000_000, 6_196)
+			.saturating_add(T::DbWeight::get().reads(20_u64))
+			.saturating_add(T::DbWeight::get().writes(17_u64))
+	}
+	/// Storage: CollateralEngine CagedAt, RateUpdatedAt, CollateralTypes, NormalizedDebt, Rates, TotalDusdDebt, TotalDeurDebt, Dusd Balances, TotalSupply, Deur Balances, TotalSupply, SystemLedger SystemSurplus (r:10 w:9)
+	/// The range of component `c` is `[1, 16]`.
+	fn accrue_stability_fees(c: u32, ) -> Weight {
+		Weight::from_parts(40_000_000, 3_593)
+			.saturating_add(Weight::from_parts(12_000_000, 0).saturating_mul(c.into()))
+			.saturating_add(T::DbWeight::get().reads(10_u64))
+			.saturating_add(T::DbWeight::get().reads((5_u64).saturating_mul(c.into())))
+			.saturating_add(T::DbWeight::get().writes(9_u64))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(c.into())))
+	}
+	/// Storage: CollateralEngine CagedAt, RateUpdatedAt, Cdps, CdpOwner, CollateralTypes, TwapWindows, Rates, NextAuctionId, Auctions, OwnerCdps, CdpManagers, LiquidationQueue, TotalCollateral, NormalizedDebt, Oracle DelayedPrices (r:14 w:11)
+	fn start_auction() -> Weight {
+		Weight::from_parts(96_000_000, 6_196)
+			.saturating_add(T::DbWeight::get().reads(14_u64))
+			.saturating_add(T::DbWeight::get().writes(11_u64))
+	}
+	/// Storage: CollateralEngine CagedAt, Auctions, CollateralTypes, TotalDusdDebt, Dusd Balances, TotalSupply, SystemLedger SystemSurplus, SystemBadDebt, System Account (r:10 w:10)
+	fn take() -> Weight {
+		Weight::from_parts(88_000_000, 6_196)
+			.saturating_add(T::DbWeight::get().reads(10_u64))
+			.saturating_add(T::DbWeight::get().writes(10_u64))
+	}
+	/// Storage: CollateralEngine CagedAt, Auctions, CollateralTypes, TwapWindows, Oracle DelayedPrices (r:5 w:1)
+	fn reset_auction() -> Weight {
+		Weight::from_parts(34_000_000, 3_593)
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: CollateralEngine CagedAt, RateUpdatedAt, CollateralTypes, CagePrices, Oracle Prices (r:4 w:1)
+	/// The range of component `c` is `[1, 16]`.
+	fn cage(c: u32, ) -> Weight {
+		Weight::from_parts(30_000_000, 3_593)
+			.saturating_add(Weight::from_parts(6_000_000, 0).saturating_mul(c.into()))
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(c.into())))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(c.into())))
+	}
+	/// Storage: CollateralEngine CagedAt, Auctions, Rates, NextCdpId, Cdps, CdpOwner, OwnerCdps, TotalCollateral, NormalizedDebt (r:6 w:7)
+	fn skip_auction() -> Weight {
+		Weight::from_parts(46_000_000, 3_593)
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(7_u64))
+	}
+	/// Storage: CollateralEngine CagedAt, Cdps, CdpOwner, CollateralTypes, Rates, CagePrices, SettledCollateral, NormalizedDebt, TotalCollateral, System Account (r:15 w:8)
+	fn settle_cdp() -> Weight {
+		Weight::from_parts(72_000_000, 6_196)
+			.saturating_add(T::DbWeight::get().reads(15_u64))
+			.saturating_add(T::DbWeight::get().writes(8_u64))
+	}
+	/// Storage: CollateralEngine CagedAt, RedemptionRates, Auctions, NormalizedDebt, TotalDusdDebt, TotalDeurDebt, ReserveBackedDebt, CollateralTypes, SettledCollateral (r:9 w:0)
+	/// The range of component `c` is `[1, 16]`.
+	fn fix_redemption_rates(c: u32, ) -> Weight {
+		Weight::from_parts(24_000_000, 3_593)
+			.saturating_add(Weight::from_parts(10_000_000, 0).saturating_mul(c.into()))
+			.saturating_add(T::DbWeight::get().reads(9_u64))
+			.saturating_add(T::DbWeight::get().reads((3_u64).saturating_mul(c.into())))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(c.into())))
+	}
+	/// Storage: CollateralEngine RedemptionRates, CollateralTypes, SettledCollateral, TotalDusdDebt, Dusd Balances, TotalSupply, System Account (r:5 w:4)
+	/// The range of component `c` is `[1, 16]`.
+	fn redeem(c: u32, ) -> Weight {
+		Weight::from_parts(40_000_000, 3_593)
+			.saturating_add(Weight::from_parts(22_000_000, 0).saturating_mul(c.into()))
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().reads((3_u64).saturating_mul(c.into())))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(c.into())))
+	}
+	/// Storage: CollateralEngine CollateralTypes (r:16 w:1)
+	fn add_collateral_type() -> Weight {
+		Weight::from_parts(28_000_000, 3_593)
+			.saturating_add(T::DbWeight::get().reads(16_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: CollateralEngine CollateralTypes (r:1 w:1)
+	fn update_collateral_type() -> Weight {
+		Weight::from_parts(22_000_000, 3_593)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
 			.saturating_add(T::DbWeight::get().writes(1_u64))
 	}
-	/// Storage: Template Something (r:1 w:1)
-	/// Proof: Template Something (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
-	fn cause_error() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `32`
-		//  Estimated: `1489`
-		// Minimum execution time: 6_000_000 picoseconds.
-		Weight::from_parts(6_000_000, 1489)
+	/// Storage: CollateralEngine Cdps, CdpOwner, CollateralTypes, OwnerCdps, CdpManagers, LiquidationQueue, System Account (r:5 w:8)
+	fn transfer_cdp_ownership() -> Weight {
+		Weight::from_parts(52_000_000, 6_196)
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(8_u64))
+	}
+	/// Storage: CollateralEngine CdpOwner, CdpManagers (r:1 w:1)
+	fn allow_cdp_manager() -> Weight {
+		Weight::from_parts(18_000_000, 3_529)
 			.saturating_add(T::DbWeight::get().reads(1_u64))
 			.saturating_add(T::DbWeight::get().writes(1_u64))
 	}
+	/// Storage: CollateralEngine GlobalDebtCeilings (r:0 w:1)
+	fn set_global_debt_ceiling() -> Weight {
+		Weight::from_parts(12_000_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: CollateralEngine CollateralTypes (r:1 w:1)
+	fn set_collateral_debt_ceiling() -> Weight {
+		Weight::from_parts(20_000_000, 3_593)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: CollateralEngine CollateralTypes (r:1 w:1)
+	fn set_dust() -> Weight {
+		Weight::from_parts(20_000_000, 3_593)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: CollateralEngine CollateralTypes, TwapWindows (r:1 w:1)
+	fn set_twap_window() -> Weight {
+		Weight::from_parts(18_000_000, 3_593)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: CollateralEngine CagedAt, RateUpdatedAt, Cdps, CdpOwner, CollateralTypes, TwapWindows, Rates, NextAuctionId, Auctions, OwnerCdps, CdpManagers, LiquidationQueue, TotalCollateral, NormalizedDebt, Oracle DelayedPrices (r:14 w:11)
+	fn liquidate_unsigned() -> Weight {
+		Weight::from_parts(94_000_000, 6_196)
+			.saturating_add(T::DbWeight::get().reads(14_u64))
+			.saturating_add(T::DbWeight::get().writes(11_u64))
+	}
+	/// Storage: CollateralEngine CagedAt, RateUpdatedAt, Cdps, CdpOwner, CollateralTypes, TwapWindows, Rates, TotalCollateral, NormalizedDebt, TotalDusdDebt, TotalDeurDebt, Oracle DelayedPrices, Prices, Dusd Balances, TotalSupply, Deur Balances, TotalSupply, System Account (r:21 w:12)
+	fn liquidate_partial() -> Weight {
+		Weight::from_parts(124_000_000, 6_196)
+			.saturating_add(T::DbWeight::get().reads(21_u64))
+			.saturating_add(T::DbWeight::get().writes(12_u64))
+	}
 }
 
 // For backwards compatibility and tests
 impl WeightInfo for () {
-	/// Storage: Template Something (r:0 w:1)
-	/// Proof: Template Something (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
-	fn do_something() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `0`
-		//  Estimated: `0`
-		// Minimum execution time: 8_000_000 picoseconds.
-		Weight::from_parts(9_000_000, 0)
+	/// Storage: CollateralEngine CagedAt, CollateralTypes, NextCdpId, TotalCollateral, Cdps, CdpOwner, OwnerCdps, System Account (r:5 w:6)
+	fn create_cdp() -> Weight {
+		Weight::from_parts(38_000_000, 3_593)
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
+	}
+	/// Storage: CollateralEngine CagedAt, Cdps, CdpOwner, CdpManagers, CollateralTypes, TotalCollateral, System Account (r:7 w:3)
+	fn deposit_collateral() -> Weight {
+		Weight::from_parts(36_000_000, 3_593)
+			.saturating_add(RocksDbWeight::get().reads(7_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	/// Storage: CollateralEngine CagedAt, RateUpdatedAt, Cdps, CdpOwner, CdpManagers, CollateralTypes, Rates, TotalCollateral, Oracle Halted, Prices, System Account (r:14 w:3)
+	fn withdraw_collateral() -> Weight {
+		Weight::from_parts(68_000_000, 6_196)
+			.saturating_add(RocksDbWeight::get().reads(14_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	/// Storage: CollateralEngine CagedAt, RateUpdatedAt, Cdps, CdpOwner, CdpManagers, CollateralTypes, Rates, GlobalDebtCeilings, TotalDusdDebt, ReserveBackedDebt, NormalizedDebt, Oracle Halted, Prices, Dusd Balances, TotalSupply (r:19 w:5)
+	fn mint_dusd() -> Weight {
+		Weight::from_parts(82_000_000, 6_196)
+			.saturating_add(RocksDbWeight::get().reads(19_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+	/// Storage: CollateralEngine CagedAt, RateUpdatedAt, Cdps, CdpOwner, CdpManagers, CollateralTypes, Rates, GlobalDebtCeilings, TotalDeurDebt, ReserveBackedDebt, NormalizedDebt, Oracle Halted, Prices, Deur Balances, TotalSupply (r:19 w:5)
+	fn mint_deur() -> Weight {
+		Weight::from_parts(82_000_000, 6_196)
+			.saturating_add(RocksDbWeight::get().reads(19_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+	/// Storage: CollateralEngine CagedAt, RateUpdatedAt, Cdps, CdpOwner, CdpManagers, Rates, CollateralTypes, NormalizedDebt, TotalDusdDebt, Oracle Prices, Dusd Balances, TotalSupply (r:14 w:5)
+	fn repay_dusd() -> Weight {
+		Weight::from_parts(62_000_000, 6_196)
+			.saturating_add(RocksDbWeight::get().reads(14_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+	/// Storage: CollateralEngine CagedAt, RateUpdatedAt, Cdps, CdpOwner, CdpManagers, Rates, CollateralTypes, NormalizedDebt, TotalDeurDebt, Oracle Prices, Deur Balances, TotalSupply (r:14 w:5)
+	fn repay_deur() -> Weight {
+		Weight::from_parts(62_000_000, 6_196)
+			.saturating_add(RocksDbWeight::get().reads(14_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+	/// Storage: CollateralEngine Cdps, CdpOwner, CdpManagers, CollateralTypes, OwnerCdps, LiquidationQueue, TotalCollateral, System Account (r:6 w:7)
+	fn close_cdp() -> Weight {
+		Weight::from_parts(44_000_000, 3_593)
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().writes(7_u64))
+	}
+	/// Storage: CollateralEngine CagedAt, RateUpdatedAt, Cdps, CdpOwner, CollateralTypes, TwapWindows, Rates, OwnerCdps, CdpManagers, LiquidationQueue, TotalCollateral, NormalizedDebt, TotalDusdDebt, TotalDeurDebt, Oracle DelayedPrices, Dusd Balances, TotalSupply, Deur Balances, TotalSupply, SystemLedger SystemBadDebt, System Account (r:20 w:17)
+	fn liquidate() -> Weight {
+		Weight::from_parts(121_000_000, 6_196)
+			.saturating_add(RocksDbWeight::get().reads(20_u64))
+			.saturating_add(RocksDbWeight::get().writes(17_u64))
+	}
+	/// Storage: CollateralEngine CagedAt, RateUpdatedAt, CollateralTypes, NormalizedDebt, Rates, TotalDusdDebt, TotalDeurDebt, Dusd Balances, TotalSupply, Deur Balances, TotalSupply, SystemLedger SystemSurplus (r:10 w:9)
+	/// The range of component `c` is `[1, 16]`.
+	fn accrue_stability_fees(c: u32, ) -> Weight {
+		Weight::from_parts(40_000_000, 3_593)
+			.saturating_add(Weight::from_parts(12_000_000, 0).saturating_mul(c.into()))
+			.saturating_add(RocksDbWeight::get().reads(10_u64))
+			.saturating_add(RocksDbWeight::get().reads((5_u64).saturating_mul(c.into())))
+			.saturating_add(RocksDbWeight::get().writes(9_u64))
+			.saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(c.into())))
+	}
+	/// Storage: CollateralEngine CagedAt, RateUpdatedAt, Cdps, CdpOwner, CollateralTypes, TwapWindows, Rates, NextAuctionId, Auctions, OwnerCdps, CdpManagers, LiquidationQueue, TotalCollateral, NormalizedDebt, Oracle DelayedPrices (r:14 w:11)
+	fn start_auction() -> Weight {
+		Weight::from_parts(96_000_000, 6_196)
+			.saturating_add(RocksDbWeight::get().reads(14_u64))
+			.saturating_add(RocksDbWeight::get().writes(11_u64))
+	}
+	/// Storage: CollateralEngine CagedAt, Auctions, CollateralTypes, TotalDusdDebt, Dusd Balances, TotalSupply, SystemLedger SystemSurplus, SystemBadDebt, System Account (r:10 w:10)
+	fn take() -> Weight {
+		Weight::from_parts(88_000_000, 6_196)
+			.saturating_add(RocksDbWeight::get().reads(10_u64))
+			.saturating_add(RocksDbWeight::get().writes(10_u64))
+	}
+	/// Storage: CollateralEngine CagedAt, Auctions, CollateralTypes, TwapWindows, Oracle DelayedPrices (r:5 w:1)
+	fn reset_auction() -> Weight {
+		Weight::from_parts(34_000_000, 3_593)
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: CollateralEngine CagedAt, RateUpdatedAt, CollateralTypes, CagePrices, Oracle Prices (r:4 w:1)
+	/// The range of component `c` is `[1, 16]`.
+	fn cage(c: u32, ) -> Weight {
+		Weight::from_parts(30_000_000, 3_593)
+			.saturating_add(Weight::from_parts(6_000_000, 0).saturating_mul(c.into()))
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(c.into())))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+			.saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(c.into())))
+	}
+	/// Storage: CollateralEngine CagedAt, Auctions, Rates, NextCdpId, Cdps, CdpOwner, OwnerCdps, TotalCollateral, NormalizedDebt (r:6 w:7)
+	fn skip_auction() -> Weight {
+		Weight::from_parts(46_000_000, 3_593)
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().writes(7_u64))
+	}
+	/// Storage: CollateralEngine CagedAt, Cdps, CdpOwner, CollateralTypes, Rates, CagePrices, SettledCollateral, NormalizedDebt, TotalCollateral, System Account (r:15 w:8)
+	fn settle_cdp() -> Weight {
+		Weight::from_parts(72_000_000, 6_196)
+			.saturating_add(RocksDbWeight::get().reads(15_u64))
+			.saturating_add(RocksDbWeight::get().writes(8_u64))
+	}
+	/// Storage: CollateralEngine CagedAt, RedemptionRates, Auctions, NormalizedDebt, TotalDusdDebt, TotalDeurDebt, ReserveBackedDebt, CollateralTypes, SettledCollateral (r:9 w:0)
+	/// The range of component `c` is `[1, 16]`.
+	fn fix_redemption_rates(c: u32, ) -> Weight {
+		Weight::from_parts(24_000_000, 3_593)
+			.saturating_add(Weight::from_parts(10_000_000, 0).saturating_mul(c.into()))
+			.saturating_add(RocksDbWeight::get().reads(9_u64))
+			.saturating_add(RocksDbWeight::get().reads((3_u64).saturating_mul(c.into())))
+			.saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(c.into())))
+	}
+	/// Storage: CollateralEngine RedemptionRates, CollateralTypes, SettledCollateral, TotalDusdDebt, Dusd Balances, TotalSupply, System Account (r:5 w:4)
+	/// The range of component `c` is `[1, 16]`.
+	fn redeem(c: u32, ) -> Weight {
+		Weight::from_parts(40_000_000, 3_593)
+			.saturating_add(Weight::from_parts(22_000_000, 0).saturating_mul(c.into()))
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().reads((3_u64).saturating_mul(c.into())))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+			.saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(c.into())))
+	}
+	/// Storage: CollateralEngine CollateralTypes (r:16 w:1)
+	fn add_collateral_type() -> Weight {
+		Weight::from_parts(28_000_000, 3_593)
+			.saturating_add(RocksDbWeight::get().reads(16_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: CollateralEngine CollateralTypes (r:1 w:1)
+	fn update_collateral_type() -> Weight {
+		Weight::from_parts(22_000_000, 3_593)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
 			.saturating_add(RocksDbWeight::get().writes(1_u64))
 	}
-	/// Storage: Template Something (r:1 w:1)
-	/// Proof: Template Something (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
-	fn cause_error() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `32`
-		//  Estimated: `1489`
-		// Minimum execution time: 6_000_000 picoseconds.
-		Weight::from_parts(6_000_000, 1489)
+	/// Storage: CollateralEngine Cdps, CdpOwner, CollateralTypes, OwnerCdps, CdpManagers, LiquidationQueue, System Account (r:5 w:8)
+	fn transfer_cdp_ownership() -> Weight {
+		Weight::from_parts(52_000_000, 6_196)
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(8_u64))
+	}
+	/// Storage: CollateralEngine CdpOwner, CdpManagers (r:1 w:1)
+	fn allow_cdp_manager() -> Weight {
+		Weight::from_parts(18_000_000, 3_529)
 			.saturating_add(RocksDbWeight::get().reads(1_u64))
 			.saturating_add(RocksDbWeight::get().writes(1_u64))
 	}
+	/// Storage: CollateralEngine GlobalDebtCeilings (r:0 w:1)
+	fn set_global_debt_ceiling() -> Weight {
+		Weight::from_parts(12_000_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: CollateralEngine CollateralTypes (r:1 w:1)
+	fn set_collateral_debt_ceiling() -> Weight {
+		Weight::from_parts(20_000_000, 3_593)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: CollateralEngine CollateralTypes (r:1 w:1)
+	fn set_dust() -> Weight {
+		Weight::from_parts(20_000_000, 3_593)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: CollateralEngine CollateralTypes, TwapWindows (r:1 w:1)
+	fn set_twap_window() -> Weight {
+		Weight::from_parts(18_000_000, 3_593)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: CollateralEngine CagedAt, RateUpdatedAt, Cdps, CdpOwner, CollateralTypes, TwapWindows, Rates, NextAuctionId, Auctions, OwnerCdps, CdpManagers, LiquidationQueue, TotalCollateral, NormalizedDebt, Oracle DelayedPrices (r:14 w:11)
+	fn liquidate_unsigned() -> Weight {
+		Weight::from_parts(94_000_000, 6_196)
+			.saturating_add(RocksDbWeight::get().reads(14_u64))
+			.saturating_add(RocksDbWeight::get().writes(11_u64))
+	}
+	/// Storage: CollateralEngine CagedAt, RateUpdatedAt, Cdps, CdpOwner, CollateralTypes, TwapWindows, Rates, TotalCollateral, NormalizedDebt, TotalDusdDebt, TotalDeurDebt, Oracle DelayedPrices, Prices, Dusd Balances, TotalSupply, Deur Balances, TotalSupply, System Account (r:21 w:12)
+	fn liquidate_partial() -> Weight {
+		Weight::from_parts(124_000_000, 6_196)
+			.saturating_add(RocksDbWeight::get().reads(21_u64))
+			.saturating_add(RocksDbWeight::get().writes(12_u64))
+	}
 }
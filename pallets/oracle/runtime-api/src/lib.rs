@@ -0,0 +1,17 @@
+//! Runtime API of the oracle pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use pallet_oracle::AssetPair;
+
+sp_api::decl_runtime_apis! {
+	/// Queries the price history recorded by the oracle.
+	pub trait OracleApi {
+		/// The time-weighted average price of `pair` over the last `window` milliseconds, or
+		/// `None` if the recorded history does not cover the window.
+		fn twap(pair: AssetPair, window: u64) -> Option<u128>;
+
+		/// The cumulative price of `pair` and the unix time, in milliseconds, it runs up to.
+		fn cumulative_price(pair: AssetPair) -> Option<(u128, u64)>;
+	}
+}
@@ -0,0 +1,103 @@
+//! Benchmarking setup for pallet-oracle
+
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as Oracle;
+use alloc::vec;
+use frame_benchmarking::v2::*;
+use frame_support::{traits::Get, BoundedVec};
+use frame_system::RawOrigin;
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	#[benchmark]
+	fn submit_price(f: Linear<1, 32>) {
+		// `f - 1` feeders have already submitted to the open round, so aggregating takes the
+		// median of `f` prices
+		for i in 1..f {
+			let feeder: T::AccountId = account("feeder", i, 0);
+			Feeders::<T>::insert(&feeder, ());
+			Submissions::<T>::insert(AssetPair::OrmUsd, &feeder, u128::from(i));
+		}
+		Rounds::<T>::insert(AssetPair::OrmUsd, Round { id: 1, started_at: 0, submissions: f - 1 });
+		MinQuorum::<T>::put(1);
+		let caller: T::AccountId = whitelisted_caller();
+		Feeders::<T>::insert(&caller, ());
+		#[extrinsic_call]
+		submit_price(RawOrigin::Signed(caller), AssetPair::OrmUsd, 1, 1_000);
+
+		assert_eq!(Prices::<T>::get(AssetPair::OrmUsd).map(|data| data.round), Some(1));
+	}
+
+	#[benchmark]
+	fn add_feeder() {
+		let feeder: T::AccountId = account("feeder", 0, 0);
+		#[extrinsic_call]
+		add_feeder(RawOrigin::Root, feeder.clone());
+
+		assert!(Feeders::<T>::contains_key(feeder));
+	}
+
+	#[benchmark]
+	fn remove_feeder() {
+		let feeder: T::AccountId = account("feeder", 0, 0);
+		Feeders::<T>::insert(&feeder, ());
+		#[extrinsic_call]
+		remove_feeder(RawOrigin::Root, feeder.clone());
+
+		assert!(!Feeders::<T>::contains_key(feeder));
+	}
+
+	#[benchmark]
+	fn set_min_quorum() {
+		Feeders::<T>::insert(whitelisted_caller::<T::AccountId>(), ());
+		#[extrinsic_call]
+		set_min_quorum(RawOrigin::Root, 1);
+
+		assert_eq!(MinQuorum::<T>::get(), 1);
+	}
+
+	#[benchmark]
+	fn poke() {
+		let price = PriceData { price: 1_000, round: 1, updated_at: 0, timestamp: 0 };
+		Prices::<T>::insert(AssetPair::OrmUsd, price);
+		let caller: T::AccountId = whitelisted_caller();
+		#[extrinsic_call]
+		poke(RawOrigin::Signed(caller), AssetPair::OrmUsd);
+
+		assert!(DelayedPrices::<T>::contains_key(AssetPair::OrmUsd));
+	}
+
+	#[benchmark]
+	fn set_max_deviation() {
+		#[extrinsic_call]
+		set_max_deviation(RawOrigin::Root, Some(1_000));
+
+		assert_eq!(MaxDeviation::<T>::get(), Some(1_000));
+	}
+
+	#[benchmark]
+	fn reset_circuit_breaker() {
+		Halted::<T>::insert(AssetPair::OrmUsd, true);
+		#[extrinsic_call]
+		reset_circuit_breaker(RawOrigin::Root, AssetPair::OrmUsd);
+
+		assert!(!Halted::<T>::get(AssetPair::OrmUsd));
+	}
+
+	#[benchmark]
+	fn set_price_sources() {
+		let long = || BoundedVec::truncate_from(vec![b'a'; T::MaxSourceLength::get() as usize]);
+		let source = HttpSource { url: long(), path: long() };
+		let sources = HttpSources::<T>::truncate_from(vec![source; T::MaxSources::get() as usize]);
+		#[extrinsic_call]
+		set_price_sources(RawOrigin::Root, AssetPair::OrmUsd, sources);
+
+		assert_eq!(PriceSources::<T>::get(AssetPair::OrmUsd).len() as u32, T::MaxSources::get());
+	}
+
+	impl_benchmark_test_suite!(Oracle, crate::mock::new_test_ext(), crate::mock::Test);
+}
@@ -0,0 +1,842 @@
+//! # Oracle Pallet
+//!
+//! Supplies the prices the ORIUM stablecoin system values collateral at.
+//!
+//! ## Overview
+//!
+//! Prices are submitted by a whitelist of **feeders** managed by `AdminOrigin`. Each asset pair
+//! is priced in **rounds**: a feeder opens the next round by submitting to it, and every other
+//! feeder may submit once to the round that is open. As soon as a round has at least
+//! `MinQuorum` submissions, the median of its submissions becomes the price of the pair, and
+//! later submissions to the same round refine that median.
+//!
+//! A new round can only be opened once the current one has reached quorum, or once it has been
+//! open for `RoundTimeout` blocks without doing so. Submissions to a round that failed to reach
+//! quorum are discarded, and the last aggregated price stays in effect.
+//!
+//! ## Oracle security module
+//!
+//! Aggregated prices also pass through a delay of `SecurityDelay` before they are offered as the
+//! **delayed price** that liquidations act on. The module holds a current and a next price: once
+//! the delay has passed since the next price was queued, it becomes current and the latest
+//! aggregated price is queued in its place. This happens on aggregation, or through `poke` when
+//! feeders are idle. A bad price therefore cannot liquidate CDPs before governance has had the
+//! time to react to it.
+//!
+//! ## Circuit breaker
+//!
+//! When a single aggregation moves the price of a pair by more than `MaxDeviation`, the pair is
+//! **halted**: the security module stops advancing and consumers are expected to stop issuing
+//! debt against it, until `AdminOrigin` resets the breaker.
+//!
+//! ## Price history
+//!
+//! Every aggregated price is recorded as an **observation** along with the cumulative price, the
+//! sum of each price weighted by the milliseconds it was in effect. The last `MaxObservations`
+//! observations of each pair are kept, from which the time-weighted average price over any window
+//! they cover is derived: the difference between the cumulative prices at both ends of the
+//! window, divided by its length.
+//!
+//! ## Offchain worker
+//!
+//! Every `FetchInterval` blocks the offchain worker of each node reads the price of every pair
+//! from the HTTP sources set by `AdminOrigin`, each with the JSON path its price is found at. The
+//! median of the sources that answered is submitted as a signed `submit_price` transaction by
+//! every feeder key the node holds in its keystore under [`KEY_TYPE`].
+//!
+//! Other pallets read prices through the [`PriceProvider`] trait.
+
+// We make sure this pallet uses `no_std` for compiling to Wasm.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+// Re-export pallet items so that they can be accessed from the crate namespace.
+pub use pallet::*;
+
+// FRAME pallets require their own "mock runtimes" to be able to run unit tests. This module
+// contains a mock runtime specific for testing this pallet's functionality.
+#[cfg(test)]
+mod mock;
+
+// This module contains the unit tests for this pallet.
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod offchain;
+pub mod weights;
+pub use weights::*;
+
+use sp_runtime::KeyTypeId;
+
+/// The key type of the keys feeders sign offchain price submissions with.
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"orcl");
+
+/// The application crypto of feeder keys.
+pub mod crypto {
+	use super::KEY_TYPE;
+	use sp_runtime::{
+		app_crypto::{app_crypto, sr25519},
+		MultiSignature, MultiSigner,
+	};
+
+	app_crypto!(sr25519, KEY_TYPE);
+
+	/// Signs price submissions with an sr25519 feeder key.
+	pub struct OracleAuthId;
+
+	impl frame_system::offchain::AppCrypto<MultiSigner, MultiSignature> for OracleAuthId {
+		type RuntimeAppPublic = Public;
+		type GenericSignature = sp_core::sr25519::Signature;
+		type GenericPublic = sp_core::sr25519::Public;
+	}
+}
+
+/// Read access to aggregated prices.
+pub trait PriceProvider {
+	/// The latest aggregated price of `pair`, if one has been agreed on.
+	fn price(pair: AssetPair) -> Option<PriceData>;
+	/// The price of `pair` currently released by the oracle security module.
+	fn delayed_price(pair: AssetPair) -> Option<PriceData>;
+	/// Whether a sudden move in the price of `pair` has tripped the circuit breaker.
+	fn is_halted(pair: AssetPair) -> bool;
+	/// The time-weighted average price of `pair` over the last `window` milliseconds, if the
+	/// recorded price history covers all of it.
+	fn twap(pair: AssetPair, window: u64) -> Option<u128>;
+	/// Publish `price` as both the aggregated and the released price of `pair`, so that
+	/// benchmarks of consuming pallets can value collateral.
+	#[cfg(feature = "runtime-benchmarks")]
+	fn set_price(pair: AssetPair, price: u128);
+}
+
+/// Notified whenever a price the oracle reports moves.
+pub trait OnPriceUpdate {
+	/// A new price of `pair` has been aggregated or released by the security module.
+	fn on_price_update(pair: AssetPair);
+}
+
+impl OnPriceUpdate for () {
+	fn on_price_update(_: AssetPair) {}
+}
+
+// All pallet logic is defined in its own module and must be annotated by the `pallet` attribute.
+#[frame_support::pallet]
+pub mod pallet {
+	// Import various useful types required by all FRAME pallets.
+	use super::*;
+	use frame_support::{pallet_prelude::*, traits::UnixTime};
+	use frame_system::{
+		offchain::{AppCrypto, CreateSignedTransaction, SendSignedTransaction, Signer},
+		pallet_prelude::*,
+	};
+	use sp_runtime::{
+		helpers_128bit::multiply_by_rational_with_rounding,
+		traits::{IdentifyAccount, SaturatedConversion, Saturating, Zero},
+		Rounding, RuntimeAppPublic,
+	};
+	use alloc::vec::Vec;
+
+	// The `Pallet` struct serves as a placeholder to implement traits, methods and dispatchables
+	// (`Call`s) in this pallet.
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	/// The pallet's configuration trait.
+	#[pallet::config]
+	pub trait Config: CreateSignedTransaction<Call<Self>> + frame_system::Config {
+		/// The overarching runtime event type.
+		#[allow(deprecated)]
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		/// A type representing the weights required by the dispatchables of this pallet.
+		type WeightInfo: WeightInfo;
+		/// The origin that manages feeders and the quorum.
+		type AdminOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		/// The most feeders that can be authorized at once.
+		#[pallet::constant]
+		type MaxFeeders: Get<u32>;
+		/// The number of blocks after which a round that has not reached quorum can be replaced.
+		#[pallet::constant]
+		type RoundTimeout: Get<u32>;
+		/// The source of the time prices are stamped with.
+		type UnixTime: UnixTime;
+		/// How long, in milliseconds, an aggregated price is queued in the security module
+		/// before it becomes the delayed price.
+		#[pallet::constant]
+		type SecurityDelay: Get<u64>;
+		/// The keys the offchain worker signs price submissions with.
+		type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
+		/// The number of blocks between two runs of the offchain worker.
+		#[pallet::constant]
+		type FetchInterval: Get<u32>;
+		/// The most HTTP sources an asset pair can be priced from.
+		#[pallet::constant]
+		type MaxSources: Get<u32>;
+		/// The longest URL or JSON path of an HTTP source, in bytes.
+		#[pallet::constant]
+		type MaxSourceLength: Get<u32>;
+		/// The number of aggregated prices kept per asset pair for time-weighted averages.
+		#[pallet::constant]
+		type MaxObservations: Get<u32>;
+		/// Notified of new aggregated and delayed prices.
+		type OnPriceUpdate: OnPriceUpdate;
+	}
+
+	/// The asset pairs the oracle prices.
+	#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub enum AssetPair {
+		/// ORM priced in US dollars.
+		OrmUsd,
+		/// ORM priced in euros.
+		OrmEur,
+	}
+
+	pub type RoundId = u32;
+
+	/// The round currently open for an asset pair.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct Round {
+		/// Zero until the first round is opened.
+		pub id: RoundId,
+		/// The block the round was opened.
+		pub started_at: u32,
+		/// The number of feeders that have submitted to the round.
+		pub submissions: u32,
+	}
+
+	/// An aggregated price.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct PriceData {
+		/// The median of the submissions to `round`, with 18 decimals.
+		pub price: u128,
+		pub round: RoundId,
+		/// The block the price was last aggregated.
+		pub updated_at: u32,
+		/// The unix time, in milliseconds, the price was last aggregated.
+		pub timestamp: u64,
+	}
+
+	/// The prices held by the oracle security module for an asset pair.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct DelayedPrice {
+		/// The price released to consumers.
+		pub current: PriceData,
+		/// The price that becomes current once the delay has passed.
+		pub next: PriceData,
+		/// The unix time, in milliseconds, `next` was queued.
+		pub queued_at: u64,
+	}
+
+	/// An aggregated price recorded in the price history of an asset pair.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct Observation {
+		/// The block the price was aggregated.
+		pub block: u32,
+		/// The unix time, in milliseconds, the price was aggregated.
+		pub timestamp: u64,
+		pub price: u128,
+		/// The sum of all earlier prices of the pair, each multiplied by the milliseconds it was
+		/// in effect.
+		pub cumulative: u128,
+	}
+
+	/// An HTTP endpoint the offchain worker reads a price from.
+	#[derive(
+		Encode, Decode, CloneNoBound, PartialEqNoBound, EqNoBound, RuntimeDebugNoBound, TypeInfo, MaxEncodedLen,
+	)]
+	#[scale_info(skip_type_params(S))]
+	#[codec(mel_bound())]
+	pub struct HttpSource<S: Get<u32>> {
+		/// The URL to `GET`.
+		pub url: BoundedVec<u8, S>,
+		/// The dot-separated path of the price in the JSON response, such as `orium.usd` or
+		/// `data.0.price`.
+		pub path: BoundedVec<u8, S>,
+	}
+
+	pub type HttpSourceOf<T> = HttpSource<<T as Config>::MaxSourceLength>;
+
+	pub type HttpSources<T> = BoundedVec<HttpSourceOf<T>, <T as Config>::MaxSources>;
+
+	/// Accounts allowed to submit prices.
+	#[pallet::storage]
+	pub type Feeders<T: Config> = CountedStorageMap<_, Blake2_128Concat, T::AccountId, (), OptionQuery>;
+
+	#[pallet::type_value]
+	pub fn DefaultMinQuorum() -> u32 {
+		1
+	}
+
+	/// The number of submissions a round needs before its median is used.
+	#[pallet::storage]
+	pub type MinQuorum<T> = StorageValue<_, u32, ValueQuery, DefaultMinQuorum>;
+
+	/// The round open for each asset pair.
+	#[pallet::storage]
+	pub type Rounds<T> = StorageMap<_, Twox64Concat, AssetPair, Round, ValueQuery>;
+
+	/// Prices submitted to the open round of each asset pair, by feeder.
+	#[pallet::storage]
+	pub type Submissions<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, AssetPair, Blake2_128Concat, T::AccountId, u128, OptionQuery>;
+
+	/// The latest aggregated price of each asset pair.
+	#[pallet::storage]
+	pub type Prices<T> = StorageMap<_, Twox64Concat, AssetPair, PriceData, OptionQuery>;
+
+	/// The oracle security module of each asset pair.
+	#[pallet::storage]
+	pub type DelayedPrices<T> = StorageMap<_, Twox64Concat, AssetPair, DelayedPrice, OptionQuery>;
+
+	/// The largest move of a price in a single aggregation, in basis points, before its pair is
+	/// halted. No limit if unset.
+	#[pallet::storage]
+	pub type MaxDeviation<T> = StorageValue<_, u32, OptionQuery>;
+
+	/// Asset pairs halted by the circuit breaker.
+	#[pallet::storage]
+	pub type Halted<T> = StorageMap<_, Twox64Concat, AssetPair, bool, ValueQuery>;
+
+	/// The most recent aggregated prices of each asset pair, oldest first. Once full, the oldest
+	/// observation makes room for each new one.
+	#[pallet::storage]
+	pub type Observations<T: Config> =
+		StorageMap<_, Twox64Concat, AssetPair, BoundedVec<Observation, T::MaxObservations>, ValueQuery>;
+
+	/// The HTTP sources the offchain worker prices each asset pair from.
+	#[pallet::storage]
+	pub type PriceSources<T: Config> = StorageMap<_, Twox64Concat, AssetPair, HttpSources<T>, ValueQuery>;
+
+	#[pallet::genesis_config]
+	#[derive(frame_support::DefaultNoBound)]
+	pub struct GenesisConfig<T: Config> {
+		/// Feeders authorized at genesis.
+		pub feeders: Vec<T::AccountId>,
+		/// The quorum at genesis. Zero keeps the default of a single submission.
+		pub min_quorum: u32,
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+		fn build(&self) {
+			assert!(self.feeders.len() as u32 <= T::MaxFeeders::get(), "too many feeders");
+			assert!(self.min_quorum as usize <= self.feeders.len(), "quorum exceeds the number of feeders");
+			
+			for feeder in &self.feeders {
+				Feeders::<T>::insert(feeder, ());
+			}
+			if !self.min_quorum.is_zero() {
+				MinQuorum::<T>::put(self.min_quorum);
+			}
+		}
+	}
+
+	/// Events that functions in this pallet can emit.
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		FeederAdded {
+			who: T::AccountId,
+		},
+		FeederRemoved {
+			who: T::AccountId,
+		},
+		MinQuorumSet {
+			quorum: u32,
+		},
+		PriceSubmitted {
+			pair: AssetPair,
+			round: RoundId,
+			feeder: T::AccountId,
+			price: u128,
+		},
+		PriceAggregated {
+			pair: AssetPair,
+			round: RoundId,
+			price: u128,
+		},
+		DelayedPriceUpdated {
+			pair: AssetPair,
+			current: u128,
+			next: u128,
+		},
+		MaxDeviationSet {
+			deviation: Option<u32>,
+		},
+		CircuitBreakerTripped {
+			pair: AssetPair,
+			previous: u128,
+			price: u128,
+		},
+		CircuitBreakerReset {
+			pair: AssetPair,
+		},
+		PriceSourcesSet {
+			pair: AssetPair,
+			sources: u32,
+		},
+	}
+
+	/// Errors that can be returned by this pallet.
+	#[pallet::error]
+	pub enum Error<T> {
+		NotFeeder,
+		AlreadyFeeder,
+		TooManyFeeders,
+		InvalidQuorum,
+		InsufficientFeeders,
+		InvalidPrice,
+		InvalidRound,
+		RoundNotFinished,
+		AlreadySubmitted,
+		PriceNotAvailable,
+		DelayNotElapsed,
+		PairHalted,
+	}
+
+	/// The pallet's dispatchable functions.
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Submit the price of `pair` for `round`.
+		///
+		/// `round` is either the open round or the one after it, which opens a new round if the
+		/// open one has reached quorum or timed out.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::submit_price(T::MaxFeeders::get()))]
+		pub fn submit_price(
+			origin: OriginFor<T>,
+			pair: AssetPair,
+			round: RoundId,
+			price: u128,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			
+			ensure!(Feeders::<T>::contains_key(&who), Error::<T>::NotFeeder);
+			ensure!(!price.is_zero(), Error::<T>::InvalidPrice);
+			
+			let now: u32 = frame_system::Pallet::<T>::block_number().saturated_into();
+			let mut current = Rounds::<T>::get(pair);
+			if round == current.id.saturating_add(1) {
+				ensure!(Self::can_open_round(&current, now), Error::<T>::RoundNotFinished);
+				
+				let _ = Submissions::<T>::clear_prefix(pair, T::MaxFeeders::get(), None);
+				current = Round { id: round, started_at: now, submissions: 0 };
+			} else {
+				ensure!(round == current.id && !round.is_zero(), Error::<T>::InvalidRound);
+			}
+			
+			ensure!(!Submissions::<T>::contains_key(pair, &who), Error::<T>::AlreadySubmitted);
+			
+			Submissions::<T>::insert(pair, &who, price);
+			current.submissions = current.submissions.saturating_add(1);
+			Rounds::<T>::insert(pair, &current);
+			
+			Self::deposit_event(Event::PriceSubmitted { pair, round, feeder: who, price });
+			
+			if current.submissions >= MinQuorum::<T>::get() {
+				let price = Self::median(Submissions::<T>::iter_prefix_values(pair).collect());
+				let data = PriceData { price, round, updated_at: now, timestamp: Self::now() };
+				let previous = Prices::<T>::mutate(pair, |latest| latest.replace(data.clone()));
+				
+				Self::deposit_event(Event::PriceAggregated { pair, round, price });
+				
+				Self::record_observation(pair, &data);
+				
+				if let Some(previous) = previous {
+					Self::check_deviation(pair, previous.price, price);
+				}
+				Self::advance_security_module(pair, data);
+				T::OnPriceUpdate::on_price_update(pair);
+			}
+			
+			Ok(())
+		}
+
+		/// Authorize `who` to submit prices.
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::add_feeder())]
+		pub fn add_feeder(
+			origin: OriginFor<T>,
+			who: T::AccountId,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			
+			ensure!(!Feeders::<T>::contains_key(&who), Error::<T>::AlreadyFeeder);
+			ensure!(Feeders::<T>::count() < T::MaxFeeders::get(), Error::<T>::TooManyFeeders);
+			
+			Feeders::<T>::insert(&who, ());
+			
+			Self::deposit_event(Event::FeederAdded { who });
+			
+			Ok(())
+		}
+
+		/// Revoke the authorization of `who` to submit prices.
+		///
+		/// Prices `who` already submitted to open rounds still count towards them.
+		#[pallet::call_index(2)]
+		#[pallet::weight(T::WeightInfo::remove_feeder())]
+		pub fn remove_feeder(
+			origin: OriginFor<T>,
+			who: T::AccountId,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			
+			ensure!(Feeders::<T>::contains_key(&who), Error::<T>::NotFeeder);
+			// Rounds must remain able to reach quorum
+			ensure!(
+				Feeders::<T>::count().saturating_sub(1) >= MinQuorum::<T>::get(),
+				Error::<T>::InsufficientFeeders
+			);
+			
+			Feeders::<T>::remove(&who);
+			
+			Self::deposit_event(Event::FeederRemoved { who });
+			
+			Ok(())
+		}
+
+		/// Set the number of submissions a round needs before its median is used.
+		#[pallet::call_index(3)]
+		#[pallet::weight(T::WeightInfo::set_min_quorum())]
+		pub fn set_min_quorum(
+			origin: OriginFor<T>,
+			quorum: u32,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			
+			ensure!(!quorum.is_zero() && quorum <= Feeders::<T>::count(), Error::<T>::InvalidQuorum);
+			
+			MinQuorum::<T>::put(quorum);
+			
+			Self::deposit_event(Event::MinQuorumSet { quorum });
+			
+			Ok(())
+		}
+
+		/// Advance the security module of `pair` once its delay has passed, queueing the latest
+		/// aggregated price.
+		#[pallet::call_index(4)]
+		#[pallet::weight(T::WeightInfo::poke())]
+		pub fn poke(
+			origin: OriginFor<T>,
+			pair: AssetPair,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+			
+			ensure!(!Halted::<T>::get(pair), Error::<T>::PairHalted);
+			let latest = Prices::<T>::get(pair).ok_or(Error::<T>::PriceNotAvailable)?;
+			ensure!(Self::advance_security_module(pair, latest), Error::<T>::DelayNotElapsed);
+			T::OnPriceUpdate::on_price_update(pair);
+			
+			Ok(())
+		}
+
+		/// Set the largest move of a price in a single aggregation, in basis points, before the
+		/// circuit breaker halts its pair. `None` disables the breaker.
+		#[pallet::call_index(5)]
+		#[pallet::weight(T::WeightInfo::set_max_deviation())]
+		pub fn set_max_deviation(
+			origin: OriginFor<T>,
+			deviation: Option<u32>,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			
+			MaxDeviation::<T>::set(deviation);
+			
+			Self::deposit_event(Event::MaxDeviationSet { deviation });
+			
+			Ok(())
+		}
+
+		/// Lift the halt of `pair` once its price has been checked.
+		///
+		/// The security module resumes from the latest aggregated price, which is released
+		/// after the usual delay.
+		#[pallet::call_index(6)]
+		#[pallet::weight(T::WeightInfo::reset_circuit_breaker())]
+		pub fn reset_circuit_breaker(
+			origin: OriginFor<T>,
+			pair: AssetPair,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			
+			Halted::<T>::remove(pair);
+			
+			Self::deposit_event(Event::CircuitBreakerReset { pair });
+			
+			Ok(())
+		}
+
+		/// Set the HTTP sources the offchain worker prices `pair` from. Without sources the
+		/// offchain worker leaves `pair` alone.
+		#[pallet::call_index(7)]
+		#[pallet::weight(T::WeightInfo::set_price_sources())]
+		pub fn set_price_sources(
+			origin: OriginFor<T>,
+			pair: AssetPair,
+			sources: HttpSources<T>,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			
+			let count = sources.len() as u32;
+			PriceSources::<T>::insert(pair, sources);
+			
+			Self::deposit_event(Event::PriceSourcesSet { pair, sources: count });
+			
+			Ok(())
+		}
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		/// Fetch the price of every pair from its HTTP sources and submit it with the local
+		/// feeder keys.
+		fn offchain_worker(block_number: BlockNumberFor<T>) {
+			let now: u32 = block_number.saturated_into();
+			let interval = T::FetchInterval::get();
+			if interval.is_zero() || now % interval != 0 {
+				return;
+			}
+			
+			for pair in [AssetPair::OrmUsd, AssetPair::OrmEur] {
+				Self::fetch_and_submit(pair, now);
+			}
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// The time-weighted average price of `pair` over the last `window` milliseconds.
+		///
+		/// `None` if the oldest observation kept is more recent than the start of the window. A
+		/// window of zero yields the latest price.
+		pub fn twap(pair: AssetPair, window: u64) -> Option<u128> {
+			let observations = Observations::<T>::get(pair);
+			let latest = observations.last()?;
+			if window.is_zero() {
+				return Some(latest.price);
+			}
+			
+			let now = Self::now();
+			let start = now.checked_sub(window)?;
+			// The price in effect at the start of the window
+			let first = observations.iter().rev().find(|observation| observation.timestamp <= start)?;
+			let cumulative = Self::cumulative_at(latest, now).saturating_sub(Self::cumulative_at(first, start));
+			
+			Some(cumulative / window as u128)
+		}
+
+		/// The cumulative price of `pair` and the unix time, in milliseconds, it runs up to.
+		///
+		/// Two readings of the accumulator give the time-weighted average price between them.
+		pub fn cumulative_price(pair: AssetPair) -> Option<(u128, u64)> {
+			let now = Self::now();
+			
+			Observations::<T>::get(pair).last().map(|latest| (Self::cumulative_at(latest, now), now))
+		}
+
+		/// The cumulative price at `timestamp`, assuming the price of `observation` held since.
+		fn cumulative_at(observation: &Observation, timestamp: u64) -> u128 {
+			let elapsed = timestamp.saturating_sub(observation.timestamp) as u128;
+			
+			observation.cumulative.saturating_add(observation.price.saturating_mul(elapsed))
+		}
+
+		/// Add the aggregated price `data` of `pair` to its price history.
+		///
+		/// A price aggregated at the same time as the latest observation replaces it.
+		fn record_observation(pair: AssetPair, data: &PriceData) {
+			Observations::<T>::mutate(pair, |observations| {
+				let cumulative = match observations.last_mut() {
+					Some(latest) if latest.timestamp == data.timestamp => {
+						latest.block = data.updated_at;
+						latest.price = data.price;
+						return;
+					},
+					Some(latest) => Self::cumulative_at(latest, data.timestamp),
+					None => Zero::zero(),
+				};
+				
+				if !observations.is_empty() && observations.len() as u32 >= T::MaxObservations::get() {
+					observations.remove(0);
+				}
+				let _ = observations.try_push(Observation {
+					block: data.updated_at,
+					timestamp: data.timestamp,
+					price: data.price,
+					cumulative,
+				});
+			});
+		}
+
+		/// The current unix time in milliseconds.
+		fn now() -> u64 {
+			T::UnixTime::now().as_millis().saturated_into()
+		}
+
+		/// Halt `pair` if its price moved from `previous` to `price` by more than the allowed
+		/// deviation.
+		fn check_deviation(pair: AssetPair, previous: u128, price: u128) {
+			let Some(max_deviation) = MaxDeviation::<T>::get() else { return };
+			if previous.is_zero() || Halted::<T>::get(pair) {
+				return;
+			}
+			
+			let deviation = multiply_by_rational_with_rounding(
+				price.abs_diff(previous),
+				10_000,
+				previous,
+				Rounding::Down,
+			)
+			.unwrap_or(u128::MAX);
+			if deviation > max_deviation as u128 {
+				Halted::<T>::insert(pair, true);
+				
+				Self::deposit_event(Event::CircuitBreakerTripped { pair, previous, price });
+			}
+		}
+
+		/// Move the next price of the security module of `pair` to current and queue `latest`,
+		/// if the delay has passed. The first price of a pair is released straight away.
+		///
+		/// Returns whether the module advanced.
+		fn advance_security_module(pair: AssetPair, latest: PriceData) -> bool {
+			if Halted::<T>::get(pair) {
+				return false;
+			}
+			
+			let now = Self::now();
+			let delayed = match DelayedPrices::<T>::get(pair) {
+				None => DelayedPrice { current: latest.clone(), next: latest, queued_at: now },
+				Some(delayed) if now >= delayed.queued_at.saturating_add(T::SecurityDelay::get()) =>
+					DelayedPrice { current: delayed.next, next: latest, queued_at: now },
+				Some(_) => return false,
+			};
+			
+			Self::deposit_event(Event::DelayedPriceUpdated {
+				pair,
+				current: delayed.current.price,
+				next: delayed.next.price,
+			});
+			DelayedPrices::<T>::insert(pair, delayed);
+			
+			true
+		}
+
+		/// Whether the round after `current` may be opened at block `now`.
+		fn can_open_round(current: &Round, now: u32) -> bool {
+			current.id.is_zero() ||
+				current.submissions >= MinQuorum::<T>::get() ||
+				now >= current.started_at.saturating_add(T::RoundTimeout::get())
+		}
+
+		/// The round `who` can submit the price of `pair` to at block `now`, if any.
+		fn submittable_round(pair: AssetPair, who: &T::AccountId, now: u32) -> Option<RoundId> {
+			let current = Rounds::<T>::get(pair);
+			if Self::can_open_round(&current, now) {
+				Some(current.id.saturating_add(1))
+			} else if !Submissions::<T>::contains_key(pair, who) {
+				Some(current.id)
+			} else {
+				None
+			}
+		}
+
+		/// The feeder keys in the local keystore that can submit the price of `pair` at block
+		/// `now`.
+		fn local_feeders(pair: AssetPair, now: u32) -> Vec<T::Public> {
+			<T::AuthorityId as AppCrypto<T::Public, T::Signature>>::RuntimeAppPublic::all()
+				.into_iter()
+				.map(|key| {
+					let generic: <T::AuthorityId as AppCrypto<T::Public, T::Signature>>::GenericPublic = key.into();
+					let public: T::Public = generic.into();
+					public
+				})
+				.filter(|public| {
+					let who = public.clone().into_account();
+					Feeders::<T>::contains_key(&who) && Self::submittable_round(pair, &who, now).is_some()
+				})
+				.collect()
+		}
+
+		/// Submit the median of the HTTP sources of `pair` with every local feeder key that
+		/// can. Sources that fail to answer with a price are left out.
+		fn fetch_and_submit(pair: AssetPair, now: u32) {
+			let sources = PriceSources::<T>::get(pair);
+			let feeders = Self::local_feeders(pair, now);
+			if sources.is_empty() || feeders.is_empty() {
+				return;
+			}
+			
+			let prices: Vec<u128> = sources
+				.iter()
+				.filter_map(|source| offchain::fetch_price(&source.url, &source.path).ok())
+				.collect();
+			if prices.is_empty() {
+				return;
+			}
+			let price = Self::median(prices);
+			
+			let _ = Signer::<T, T::AuthorityId>::all_accounts()
+				.with_filter(feeders)
+				.send_signed_transaction(|account| Call::submit_price {
+					pair,
+					round: Self::submittable_round(pair, &account.id, now).unwrap_or_default(),
+					price,
+				});
+		}
+
+		/// The median of `prices`.
+		///
+		/// With an even number of prices this is the mean of the two middle prices.
+		fn median(mut prices: Vec<u128>) -> u128 {
+			prices.sort_unstable();
+			
+			let mid = prices.len() / 2;
+			match prices.len() {
+				0 => Zero::zero(),
+				len if len % 2 == 1 => prices[mid],
+				_ => {
+					let (low, high) = (prices[mid - 1], prices[mid]);
+					low.saturating_add(high.saturating_sub(low) / 2)
+				},
+			}
+		}
+	}
+
+	impl<T: Config> PriceProvider for Pallet<T> {
+		fn price(pair: AssetPair) -> Option<PriceData> {
+			Prices::<T>::get(pair)
+		}
+
+		fn delayed_price(pair: AssetPair) -> Option<PriceData> {
+			DelayedPrices::<T>::get(pair).map(|delayed| delayed.current)
+		}
+
+		fn is_halted(pair: AssetPair) -> bool {
+			Halted::<T>::get(pair)
+		}
+
+		fn twap(pair: AssetPair, window: u64) -> Option<u128> {
+			Self::twap(pair, window)
+		}
+
+		#[cfg(feature = "runtime-benchmarks")]
+		fn set_price(pair: AssetPair, price: u128) {
+			let data = PriceData {
+				price,
+				round: Rounds::<T>::get(pair).id,
+				updated_at: frame_system::Pallet::<T>::block_number().saturated_into(),
+				timestamp: Self::now(),
+			};
+			
+			Self::record_observation(pair, &data);
+			DelayedPrices::<T>::insert(
+				pair,
+				DelayedPrice { current: data.clone(), next: data.clone(), queued_at: data.timestamp },
+			);
+			Prices::<T>::insert(pair, data);
+		}
+	}
+}
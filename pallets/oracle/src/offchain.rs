@@ -0,0 +1,98 @@
+//! Reading prices from HTTP sources for the offchain worker.
+
+use alloc::{string::ToString, vec::Vec};
+use serde_json::Value;
+use sp_runtime::offchain::{http, Duration};
+
+/// The number of decimals prices are quoted with.
+pub const PRICE_DECIMALS: u32 = 18;
+
+/// How long, in milliseconds, a source has to answer.
+const FETCH_TIMEOUT: u64 = 3_000;
+
+/// Why a price could not be read from a source.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FetchError {
+	/// The request could not be sent or timed out.
+	Http,
+	/// The source answered with a status other than 200.
+	Status(u16),
+	/// The response is not JSON, or has no positive price at the configured path.
+	InvalidResponse,
+}
+
+impl From<http::Error> for FetchError {
+	fn from(_: http::Error) -> Self {
+		FetchError::Http
+	}
+}
+
+/// `GET` the JSON document at `url` and read the price found at `path`.
+pub fn fetch_price(url: &[u8], path: &[u8]) -> Result<u128, FetchError> {
+	let url = core::str::from_utf8(url).map_err(|_| FetchError::Http)?;
+	let body = http_get(url)?;
+	
+	extract_price(&body, path).ok_or(FetchError::InvalidResponse)
+}
+
+fn http_get(url: &str) -> Result<Vec<u8>, FetchError> {
+	let deadline = sp_io::offchain::timestamp().add(Duration::from_millis(FETCH_TIMEOUT));
+	let pending = http::Request::get(url).deadline(deadline).send().map_err(|_| FetchError::Http)?;
+	let response = pending.try_wait(deadline).map_err(|_| FetchError::Http)??;
+	if response.code != 200 {
+		return Err(FetchError::Status(response.code));
+	}
+	
+	Ok(response.body().collect())
+}
+
+/// The price at the dot-separated `path` of the JSON document `body`, scaled to
+/// `PRICE_DECIMALS`.
+///
+/// Path segments name object fields, or index arrays when the value is an array. The price
+/// itself may be a JSON number or a string holding a decimal number.
+pub fn extract_price(body: &[u8], path: &[u8]) -> Option<u128> {
+	let document: Value = serde_json::from_slice(body).ok()?;
+	let path = core::str::from_utf8(path).ok()?;
+	
+	let mut value = &document;
+	for segment in path.split('.').filter(|segment| !segment.is_empty()) {
+		value = match value {
+			Value::Object(fields) => fields.get(segment)?,
+			Value::Array(items) => items.get(segment.parse::<usize>().ok()?)?,
+			_ => return None,
+		};
+	}
+	
+	let price = match value {
+		Value::Number(number) => parse_decimal(&number.to_string())?,
+		Value::String(text) => parse_decimal(text)?,
+		_ => return None,
+	};
+	(price > 0).then_some(price)
+}
+
+/// Parse a non-negative decimal number, optionally in exponent notation, into a fixed-point
+/// value with `PRICE_DECIMALS` decimals. Digits beyond that precision are truncated.
+pub fn parse_decimal(text: &str) -> Option<u128> {
+	let (number, exponent) = match text.find(['e', 'E']) {
+		Some(index) => (&text[..index], text[index + 1..].parse::<i32>().ok()?),
+		None => (text, 0),
+	};
+	let (integer, fraction) = number.split_once('.').unwrap_or((number, ""));
+	if integer.is_empty() && fraction.is_empty() {
+		return None;
+	}
+	
+	let mut digits: u128 = 0;
+	for digit in integer.chars().chain(fraction.chars()) {
+		digits = digits.checked_mul(10)?.checked_add(digit.to_digit(10)? as u128)?;
+	}
+	
+	let scale = (PRICE_DECIMALS as i32).checked_add(exponent)?.checked_sub(fraction.len() as i32)?;
+	if scale >= 0 {
+		digits.checked_mul(10u128.checked_pow(scale as u32)?)
+	} else {
+		Some(10u128.checked_pow(scale.unsigned_abs()).map_or(0, |divisor| digits / divisor))
+	}
+}
@@ -0,0 +1,435 @@
+use crate::{
+	mock::*, AssetPair, Error, Event, Feeders, Halted, HttpSource, HttpSourceOf, HttpSources, MinQuorum,
+	Observations, PriceData, PriceProvider, PriceSources, Prices, Rounds,
+};
+use codec::Decode;
+use frame_support::{assert_noop, assert_ok, traits::Hooks};
+use sp_core::offchain::{testing, OffchainDbExt, OffchainWorkerExt, TransactionPoolExt};
+use sp_runtime::{generic::Preamble, testing::UintAuthorityId, traits::BadOrigin};
+
+fn submit(feeder: u64, pair: AssetPair, round: u32, price: u128) {
+	assert_ok!(Oracle::submit_price(RuntimeOrigin::signed(feeder), pair, round, price));
+}
+
+#[test]
+fn price_is_aggregated_once_quorum_is_reached() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		
+		submit(1, AssetPair::OrmUsd, 1, 100);
+		System::assert_last_event(
+			Event::PriceSubmitted { pair: AssetPair::OrmUsd, round: 1, feeder: 1, price: 100 }.into(),
+		);
+		assert_eq!(Oracle::price(AssetPair::OrmUsd), None);
+		
+		// Two submissions: the mean of both
+		submit(2, AssetPair::OrmUsd, 1, 110);
+		System::assert_last_event(Event::PriceAggregated { pair: AssetPair::OrmUsd, round: 1, price: 105 }.into());
+		assert_eq!(Prices::<Test>::get(AssetPair::OrmUsd), Some(PriceData { price: 105, round: 1, updated_at: 1, timestamp: 0 }));
+		
+		// Later submissions to the same round refine the median
+		submit(3, AssetPair::OrmUsd, 1, 500);
+		assert_eq!(Oracle::price(AssetPair::OrmUsd).map(|data| data.price), Some(110));
+		
+		// Pairs are priced independently
+		assert_eq!(Oracle::price(AssetPair::OrmEur), None);
+	});
+}
+
+#[test]
+fn only_feeders_submit_once_per_round() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		
+		assert_noop!(
+			Oracle::submit_price(RuntimeOrigin::signed(4), AssetPair::OrmUsd, 1, 100),
+			Error::<Test>::NotFeeder
+		);
+		assert_noop!(
+			Oracle::submit_price(RuntimeOrigin::signed(1), AssetPair::OrmUsd, 1, 0),
+			Error::<Test>::InvalidPrice
+		);
+		
+		submit(1, AssetPair::OrmUsd, 1, 100);
+		assert_noop!(
+			Oracle::submit_price(RuntimeOrigin::signed(1), AssetPair::OrmUsd, 1, 120),
+			Error::<Test>::AlreadySubmitted
+		);
+		
+		// Only the open round and the one after it are accepted
+		assert_noop!(
+			Oracle::submit_price(RuntimeOrigin::signed(2), AssetPair::OrmUsd, 0, 100),
+			Error::<Test>::InvalidRound
+		);
+		assert_noop!(
+			Oracle::submit_price(RuntimeOrigin::signed(2), AssetPair::OrmUsd, 3, 100),
+			Error::<Test>::InvalidRound
+		);
+	});
+}
+
+#[test]
+fn next_round_opens_after_quorum_or_timeout() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		
+		submit(1, AssetPair::OrmUsd, 1, 100);
+		assert_noop!(
+			Oracle::submit_price(RuntimeOrigin::signed(2), AssetPair::OrmUsd, 2, 100),
+			Error::<Test>::RoundNotFinished
+		);
+		submit(2, AssetPair::OrmUsd, 1, 100);
+		
+		// Round 1 reached quorum, so round 2 can open and discards its submissions
+		System::set_block_number(2);
+		submit(1, AssetPair::OrmUsd, 2, 200);
+		assert_eq!(Rounds::<Test>::get(AssetPair::OrmUsd).submissions, 1);
+		assert_noop!(
+			Oracle::submit_price(RuntimeOrigin::signed(2), AssetPair::OrmUsd, 1, 100),
+			Error::<Test>::InvalidRound
+		);
+		assert_eq!(Oracle::price(AssetPair::OrmUsd).map(|data| data.price), Some(100));
+		
+		// Round 2 never reaches quorum and is replaced once it times out
+		System::set_block_number(11);
+		assert_noop!(
+			Oracle::submit_price(RuntimeOrigin::signed(3), AssetPair::OrmUsd, 3, 300),
+			Error::<Test>::RoundNotFinished
+		);
+		System::set_block_number(12);
+		submit(3, AssetPair::OrmUsd, 3, 300);
+		submit(1, AssetPair::OrmUsd, 3, 310);
+		assert_eq!(Prices::<Test>::get(AssetPair::OrmUsd), Some(PriceData { price: 305, round: 3, updated_at: 12, timestamp: 0 }));
+	});
+}
+
+#[test]
+fn feeders_are_managed_by_the_admin_origin() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		
+		assert_noop!(Oracle::add_feeder(RuntimeOrigin::signed(1), 4), BadOrigin);
+		assert_noop!(Oracle::add_feeder(RuntimeOrigin::root(), 1), Error::<Test>::AlreadyFeeder);
+		assert_ok!(Oracle::add_feeder(RuntimeOrigin::root(), 4));
+		System::assert_last_event(Event::FeederAdded { who: 4 }.into());
+		assert_ok!(Oracle::add_feeder(RuntimeOrigin::root(), 5));
+		assert_noop!(Oracle::add_feeder(RuntimeOrigin::root(), 6), Error::<Test>::TooManyFeeders);
+		
+		submit(4, AssetPair::OrmEur, 1, 80);
+		
+		assert_noop!(Oracle::remove_feeder(RuntimeOrigin::signed(1), 4), BadOrigin);
+		assert_ok!(Oracle::remove_feeder(RuntimeOrigin::root(), 4));
+		System::assert_last_event(Event::FeederRemoved { who: 4 }.into());
+		assert_noop!(Oracle::remove_feeder(RuntimeOrigin::root(), 4), Error::<Test>::NotFeeder);
+		assert_noop!(
+			Oracle::submit_price(RuntimeOrigin::signed(4), AssetPair::OrmEur, 1, 80),
+			Error::<Test>::NotFeeder
+		);
+		assert!(!Feeders::<Test>::contains_key(4));
+	});
+}
+
+#[test]
+fn quorum_stays_within_the_feeder_count() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		
+		assert_noop!(Oracle::set_min_quorum(RuntimeOrigin::signed(1), 3), BadOrigin);
+		assert_noop!(Oracle::set_min_quorum(RuntimeOrigin::root(), 0), Error::<Test>::InvalidQuorum);
+		assert_noop!(Oracle::set_min_quorum(RuntimeOrigin::root(), 4), Error::<Test>::InvalidQuorum);
+		assert_ok!(Oracle::set_min_quorum(RuntimeOrigin::root(), 3));
+		System::assert_last_event(Event::MinQuorumSet { quorum: 3 }.into());
+		assert_eq!(MinQuorum::<Test>::get(), 3);
+		
+		// Removing a feeder would leave rounds unable to reach quorum
+		assert_noop!(Oracle::remove_feeder(RuntimeOrigin::root(), 3), Error::<Test>::InsufficientFeeders);
+		
+		submit(1, AssetPair::OrmUsd, 1, 100);
+		submit(2, AssetPair::OrmUsd, 1, 100);
+		assert_eq!(Oracle::price(AssetPair::OrmUsd), None);
+		submit(3, AssetPair::OrmUsd, 1, 130);
+		assert_eq!(Oracle::price(AssetPair::OrmUsd).map(|data| data.price), Some(100));
+	});
+}
+
+/// Feeders 1 and 2 agree on `price` in the next round of ORM/USD.
+fn aggregate(price: u128) {
+	let round = Rounds::<Test>::get(AssetPair::OrmUsd).id + 1;
+	submit(1, AssetPair::OrmUsd, round, price);
+	submit(2, AssetPair::OrmUsd, round, price);
+}
+
+fn delayed_price() -> Option<u128> {
+	Oracle::delayed_price(AssetPair::OrmUsd).map(|data| data.price)
+}
+
+#[test]
+fn security_module_delays_prices() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		
+		assert_noop!(
+			Oracle::poke(RuntimeOrigin::signed(4), AssetPair::OrmUsd),
+			Error::<Test>::PriceNotAvailable
+		);
+		
+		// The first price is released straight away
+		Timestamp::set_timestamp(1_000);
+		aggregate(100);
+		assert_eq!(Oracle::price(AssetPair::OrmUsd).map(|data| data.timestamp), Some(1_000));
+		assert_eq!(delayed_price(), Some(100));
+		
+		// Later prices wait in the queue
+		Timestamp::set_timestamp(2_000);
+		aggregate(200);
+		assert_eq!(Oracle::price(AssetPair::OrmUsd).map(|data| data.price), Some(200));
+		assert_eq!(delayed_price(), Some(100));
+		assert_noop!(
+			Oracle::poke(RuntimeOrigin::signed(4), AssetPair::OrmUsd),
+			Error::<Test>::DelayNotElapsed
+		);
+		
+		Timestamp::set_timestamp(1_000 + DELAY);
+		assert_ok!(Oracle::poke(RuntimeOrigin::signed(4), AssetPair::OrmUsd));
+		System::assert_last_event(
+			Event::DelayedPriceUpdated { pair: AssetPair::OrmUsd, current: 100, next: 200 }.into(),
+		);
+		assert_eq!(delayed_price(), Some(100));
+		
+		// Aggregating advances the module as well, once the delay has passed again
+		Timestamp::set_timestamp(1_000 + 2 * DELAY);
+		aggregate(300);
+		assert_eq!(delayed_price(), Some(200));
+		System::assert_last_event(
+			Event::DelayedPriceUpdated { pair: AssetPair::OrmUsd, current: 200, next: 300 }.into(),
+		);
+	});
+}
+
+#[test]
+fn circuit_breaker_halts_sudden_moves() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		
+		assert_noop!(Oracle::set_max_deviation(RuntimeOrigin::signed(1), Some(1_000)), BadOrigin);
+		assert_ok!(Oracle::set_max_deviation(RuntimeOrigin::root(), Some(1_000))); // 10%
+		System::assert_last_event(Event::MaxDeviationSet { deviation: Some(1_000) }.into());
+		
+		aggregate(100);
+		aggregate(110);
+		assert!(!Oracle::is_halted(AssetPair::OrmUsd));
+		
+		aggregate(130);
+		System::assert_has_event(
+			Event::CircuitBreakerTripped { pair: AssetPair::OrmUsd, previous: 110, price: 130 }.into(),
+		);
+		assert!(Halted::<Test>::get(AssetPair::OrmUsd));
+		assert_eq!(Oracle::price(AssetPair::OrmUsd).map(|data| data.price), Some(130));
+		
+		// The security module holds on to the price from before the move
+		Timestamp::set_timestamp(DELAY);
+		assert_noop!(Oracle::poke(RuntimeOrigin::signed(4), AssetPair::OrmUsd), Error::<Test>::PairHalted);
+		assert_eq!(delayed_price(), Some(100));
+		
+		assert_noop!(Oracle::reset_circuit_breaker(RuntimeOrigin::signed(1), AssetPair::OrmUsd), BadOrigin);
+		assert_ok!(Oracle::reset_circuit_breaker(RuntimeOrigin::root(), AssetPair::OrmUsd));
+		System::assert_last_event(Event::CircuitBreakerReset { pair: AssetPair::OrmUsd }.into());
+		assert_ok!(Oracle::poke(RuntimeOrigin::signed(4), AssetPair::OrmUsd));
+		System::assert_last_event(
+			Event::DelayedPriceUpdated { pair: AssetPair::OrmUsd, current: 100, next: 130 }.into(),
+		);
+	});
+}
+
+#[test]
+fn twap_weights_prices_by_time() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		
+		assert_eq!(Oracle::twap(AssetPair::OrmUsd, 0), None);
+		assert_eq!(Oracle::cumulative_price(AssetPair::OrmUsd), None);
+		
+		// $1.00 for three seconds, then $2.00 for one
+		Timestamp::set_timestamp(1_000);
+		aggregate(100);
+		Timestamp::set_timestamp(4_000);
+		aggregate(200);
+		Timestamp::set_timestamp(5_000);
+		
+		assert_eq!(Oracle::cumulative_price(AssetPair::OrmUsd), Some((500_000, 5_000)));
+		assert_eq!(Oracle::twap(AssetPair::OrmUsd, 4_000), Some(125));
+		assert_eq!(Oracle::twap(AssetPair::OrmUsd, 2_000), Some(150));
+		assert_eq!(Oracle::twap(AssetPair::OrmUsd, 1_000), Some(200));
+		assert_eq!(Oracle::twap(AssetPair::OrmUsd, 0), Some(200));
+		
+		// The history does not reach back far enough
+		assert_eq!(Oracle::twap(AssetPair::OrmUsd, 4_001), None);
+	});
+}
+
+#[test]
+fn price_history_keeps_the_latest_observations() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		
+		for (second, price) in [(1, 100), (2, 110), (3, 120), (4, 130), (5, 140)] {
+			Timestamp::set_timestamp(second * 1_000);
+			aggregate(price);
+		}
+		
+		let observations = Observations::<Test>::get(AssetPair::OrmUsd);
+		assert_eq!(observations.len(), 4);
+		assert_eq!(observations[0].price, 110);
+		assert_eq!(observations[0].timestamp, 2_000);
+		// The cumulative price still accounts for the dropped observation
+		assert_eq!(observations[0].cumulative, 100_000);
+		
+		assert_eq!(Oracle::twap(AssetPair::OrmUsd, 3_000), Some(120));
+		assert_eq!(Oracle::twap(AssetPair::OrmUsd, 4_000), None);
+		
+		// Refining the median of a round at the same time updates the latest observation
+		let round = Rounds::<Test>::get(AssetPair::OrmUsd).id;
+		submit(3, AssetPair::OrmUsd, round, 200);
+		let observations = Observations::<Test>::get(AssetPair::OrmUsd);
+		assert_eq!(observations.len(), 4);
+		assert_eq!(observations[3].timestamp, 5_000);
+		assert_eq!(observations[2].cumulative, 330_000);
+	});
+}
+
+fn source(url: &str, path: &str) -> HttpSourceOf<Test> {
+	HttpSource {
+		url: url.as_bytes().to_vec().try_into().unwrap(),
+		path: path.as_bytes().to_vec().try_into().unwrap(),
+	}
+}
+
+fn expect_get(state: &mut testing::OffchainState, uri: &str, body: &str) {
+	state.expect_request(testing::PendingRequest {
+		method: "GET".into(),
+		uri: uri.into(),
+		response: Some(body.as_bytes().to_vec()),
+		sent: true,
+		..Default::default()
+	});
+}
+
+#[test]
+fn price_sources_are_managed_by_the_admin_origin() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		
+		let sources: HttpSources<Test> = vec![source("https://a.example/orm", "orm.usd")].try_into().unwrap();
+		assert_noop!(
+			Oracle::set_price_sources(RuntimeOrigin::signed(1), AssetPair::OrmUsd, sources.clone()),
+			BadOrigin
+		);
+		assert_ok!(Oracle::set_price_sources(RuntimeOrigin::root(), AssetPair::OrmUsd, sources.clone()));
+		System::assert_last_event(Event::PriceSourcesSet { pair: AssetPair::OrmUsd, sources: 1 }.into());
+		assert_eq!(PriceSources::<Test>::get(AssetPair::OrmUsd), sources);
+		assert!(PriceSources::<Test>::get(AssetPair::OrmEur).is_empty());
+	});
+}
+
+#[test]
+fn offchain_worker_submits_the_median_of_its_sources() {
+	let (offchain, state) = testing::TestOffchainExt::new();
+	let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainDbExt::new(offchain.clone()));
+	ext.register_extension(OffchainWorkerExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+	
+	ext.execute_with(|| {
+		System::set_block_number(5);
+		UintAuthorityId::set_all_keys(vec![1u64]);
+		let sources = vec![
+			source("https://a.example/orm", "orium.usd"),
+			source("https://b.example/ticker", "data.0.price"),
+			source("https://c.example/orm", "orium.usd"),
+		];
+		assert_ok!(Oracle::set_price_sources(RuntimeOrigin::root(), AssetPair::OrmUsd, sources.try_into().unwrap()));
+		
+		// The third source is down and left out of the median
+		expect_get(&mut state.write(), "https://a.example/orm", r#"{"orium":{"usd":1.25}}"#);
+		expect_get(&mut state.write(), "https://b.example/ticker", r#"{"data":[{"price":"1.35"}]}"#);
+		expect_get(&mut state.write(), "https://c.example/orm", "<html>Bad Gateway</html>");
+		
+		// Nothing happens between fetch intervals
+		Oracle::offchain_worker(4);
+		assert!(pool_state.read().transactions.is_empty());
+		
+		Oracle::offchain_worker(5);
+		
+		let transaction = pool_state.write().transactions.pop().unwrap();
+		assert!(pool_state.read().transactions.is_empty());
+		let extrinsic = Extrinsic::decode(&mut &*transaction).unwrap();
+		assert!(matches!(extrinsic.preamble, Preamble::Signed(1, ..)));
+		assert_eq!(
+			extrinsic.function,
+			RuntimeCall::Oracle(crate::Call::submit_price {
+				pair: AssetPair::OrmUsd,
+				round: 1,
+				price: 1_300_000_000_000_000_000,
+			})
+		);
+	});
+}
+
+#[test]
+fn offchain_worker_only_signs_for_feeders_that_can_submit() {
+	let (offchain, state) = testing::TestOffchainExt::new();
+	let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainDbExt::new(offchain.clone()));
+	ext.register_extension(OffchainWorkerExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+	
+	ext.execute_with(|| {
+		System::set_block_number(5);
+		let sources = vec![source("https://a.example/orm", "orium.usd")];
+		assert_ok!(Oracle::set_price_sources(RuntimeOrigin::root(), AssetPair::OrmUsd, sources.try_into().unwrap()));
+		
+		// Account 4 is not a feeder, so nothing is fetched
+		UintAuthorityId::set_all_keys(vec![4u64]);
+		Oracle::offchain_worker(5);
+		assert!(pool_state.read().transactions.is_empty());
+		
+		// Feeder 1 already submitted to the open round, which has not reached quorum
+		submit(1, AssetPair::OrmUsd, 1, 100);
+		UintAuthorityId::set_all_keys(vec![1u64, 2]);
+		expect_get(&mut state.write(), "https://a.example/orm", r#"{"orium":{"usd":2}}"#);
+		Oracle::offchain_worker(5);
+		
+		let transactions = core::mem::take(&mut pool_state.write().transactions);
+		assert_eq!(transactions.len(), 1);
+		let extrinsic = Extrinsic::decode(&mut &*transactions[0]).unwrap();
+		assert!(matches!(extrinsic.preamble, Preamble::Signed(2, ..)));
+	});
+}
+
+#[test]
+fn prices_are_read_from_json_paths() {
+	use crate::offchain::{extract_price, parse_decimal};
+	
+	assert_eq!(parse_decimal("1"), Some(1_000_000_000_000_000_000));
+	assert_eq!(parse_decimal("0.85"), Some(850_000_000_000_000_000));
+	assert_eq!(parse_decimal(".5"), Some(500_000_000_000_000_000));
+	assert_eq!(parse_decimal("2.5e-3"), Some(2_500_000_000_000_000));
+	assert_eq!(parse_decimal("1E2"), Some(100_000_000_000_000_000_000));
+	// Digits beyond 18 decimals are truncated
+	assert_eq!(parse_decimal("0.1234567890123456789"), Some(123_456_789_012_345_678));
+	assert_eq!(parse_decimal("-1"), None);
+	assert_eq!(parse_decimal("."), None);
+	assert_eq!(parse_decimal("1.2.3"), None);
+	assert_eq!(parse_decimal("1e40"), None);
+	
+	let body = br#"{"orium":{"usd":1.25,"eur":"1.1"},"list":[3,4],"zero":0}"#;
+	assert_eq!(extract_price(body, b"orium.usd"), Some(1_250_000_000_000_000_000));
+	assert_eq!(extract_price(body, b"orium.eur"), Some(1_100_000_000_000_000_000));
+	assert_eq!(extract_price(body, b"list.1"), Some(4_000_000_000_000_000_000));
+	assert_eq!(extract_price(body, b"list.2"), None);
+	assert_eq!(extract_price(body, b"orium"), None);
+	assert_eq!(extract_price(body, b"zero"), None);
+	assert_eq!(extract_price(b"not json", b"orium.usd"), None);
+}
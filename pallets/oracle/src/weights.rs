@@ -0,0 +1,129 @@
+//! Weights for pallet_oracle
+//!
+//! Estimated from the template benchmarks until the pallet is benchmarked on reference hardware.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_oracle.
+pub trait WeightInfo {
+	fn submit_price(f: u32) -> Weight;
+	fn add_feeder() -> Weight;
+	fn remove_feeder() -> Weight;
+	fn set_min_quorum() -> Weight;
+	fn poke() -> Weight;
+	fn set_max_deviation() -> Weight;
+	fn reset_circuit_breaker() -> Weight;
+	fn set_price_sources() -> Weight;
+}
+
+/// Weights for pallet_oracle using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: Oracle Feeders, Rounds, Submissions, MinQuorum, Prices, MaxDeviation, Halted, DelayedPrices, Observations (r:8 w:6)
+	/// The range of component `f` is `[1, 32]`.
+	fn submit_price(f: u32) -> Weight {
+		Weight::from_parts(20_000_000, 3_541)
+			.saturating_add(Weight::from_parts(1_500_000, 2_500).saturating_mul(f.into()))
+			.saturating_add(T::DbWeight::get().reads(8_u64))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(f.into())))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(f.into())))
+	}
+	/// Storage: Oracle Feeders, CounterForFeeders (r:2 w:2)
+	fn add_feeder() -> Weight {
+		Weight::from_parts(12_000_000, 3_541)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: Oracle Feeders, CounterForFeeders, MinQuorum (r:3 w:2)
+	fn remove_feeder() -> Weight {
+		Weight::from_parts(13_000_000, 3_541)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: Oracle CounterForFeeders, MinQuorum (r:1 w:1)
+	fn set_min_quorum() -> Weight {
+		Weight::from_parts(9_000_000, 1_489)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: Oracle Halted, Prices, DelayedPrices (r:3 w:1)
+	fn poke() -> Weight {
+		Weight::from_parts(14_000_000, 3_541)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: Oracle MaxDeviation (r:0 w:1)
+	fn set_max_deviation() -> Weight {
+		Weight::from_parts(8_000_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: Oracle Halted (r:0 w:1)
+	fn reset_circuit_breaker() -> Weight {
+		Weight::from_parts(8_000_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: Oracle PriceSources (r:0 w:1)
+	fn set_price_sources() -> Weight {
+		Weight::from_parts(10_000_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	/// Storage: Oracle Feeders, Rounds, Submissions, MinQuorum, Prices, MaxDeviation, Halted, DelayedPrices, Observations (r:8 w:6)
+	/// The range of component `f` is `[1, 32]`.
+	fn submit_price(f: u32) -> Weight {
+		Weight::from_parts(20_000_000, 3_541)
+			.saturating_add(Weight::from_parts(1_500_000, 2_500).saturating_mul(f.into()))
+			.saturating_add(RocksDbWeight::get().reads(8_u64))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(f.into())))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(f.into())))
+	}
+	/// Storage: Oracle Feeders, CounterForFeeders (r:2 w:2)
+	fn add_feeder() -> Weight {
+		Weight::from_parts(12_000_000, 3_541)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: Oracle Feeders, CounterForFeeders, MinQuorum (r:3 w:2)
+	fn remove_feeder() -> Weight {
+		Weight::from_parts(13_000_000, 3_541)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: Oracle CounterForFeeders, MinQuorum (r:1 w:1)
+	fn set_min_quorum() -> Weight {
+		Weight::from_parts(9_000_000, 1_489)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: Oracle Halted, Prices, DelayedPrices (r:3 w:1)
+	fn poke() -> Weight {
+		Weight::from_parts(14_000_000, 3_541)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: Oracle MaxDeviation (r:0 w:1)
+	fn set_max_deviation() -> Weight {
+		Weight::from_parts(8_000_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: Oracle Halted (r:0 w:1)
+	fn reset_circuit_breaker() -> Weight {
+		Weight::from_parts(8_000_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: Oracle PriceSources (r:0 w:1)
+	fn set_price_sources() -> Weight {
+		Weight::from_parts(10_000_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+}
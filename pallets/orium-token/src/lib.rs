@@ -240,4 +240,22 @@ pub mod pallet {
 			Ok(())
 		}
 	}
+
+	impl<T: Config> Pallet<T> {
+		pub fn transfer_balance(from: &T::AccountId, to: &T::AccountId, amount: T::Balance) -> DispatchResult {
+			let from_balance = Balances::<T>::get(from);
+			ensure!(from_balance >= amount, Error::<T>::InsufficientBalance);
+			
+			Balances::<T>::mutate(from, |balance| *balance = balance.saturating_sub(amount));
+			Balances::<T>::mutate(to, |balance| *balance = balance.saturating_add(amount));
+			
+			Self::deposit_event(Event::Transfer { from: from.clone(), to: to.clone(), amount });
+			
+			Ok(())
+		}
+
+		pub fn balance_of(account: &T::AccountId) -> T::Balance {
+			Balances::<T>::get(account)
+		}
+	}
 }
@@ -0,0 +1,78 @@
+//! Benchmarking setup for pallet-psm
+
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as Psm;
+use frame_benchmarking::v2::*;
+use frame_support::traits::{fungibles::Mutate, EnsureOrigin};
+use frame_system::RawOrigin;
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	/// Swap terms with fees, so that every swap also pays the surplus account.
+	fn parameters<T: Config>() -> PsmParameters<BalanceOf<T>> {
+		PsmParameters { tin: 10, tout: 20, debt_ceiling: 1_000_000u32.into() }
+	}
+
+	/// Creates and whitelists an asset, and gives `who` a million of it.
+	fn whitelisted_asset<T: Config>(who: &T::AccountId) -> AssetIdOf<T> {
+		let asset = T::BenchmarkHelper::create_asset();
+		T::Assets::mint_into(asset.clone(), who, 1_000_000u32.into()).unwrap();
+		Parameters::<T>::insert(&asset, parameters::<T>());
+		asset
+	}
+
+	fn admin_origin<T: Config>() -> Result<T::RuntimeOrigin, BenchmarkError> {
+		T::AdminOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)
+	}
+
+	#[benchmark]
+	fn set_parameters() -> Result<(), BenchmarkError> {
+		let asset = T::BenchmarkHelper::create_asset();
+		let origin = admin_origin::<T>()?;
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, asset.clone(), parameters::<T>());
+
+		assert_eq!(Parameters::<T>::get(&asset), Some(parameters::<T>()));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn remove_asset() -> Result<(), BenchmarkError> {
+		let caller: T::AccountId = whitelisted_caller();
+		let asset = whitelisted_asset::<T>(&caller);
+		let origin = admin_origin::<T>()?;
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, asset.clone());
+
+		assert!(Parameters::<T>::get(&asset).is_none());
+		Ok(())
+	}
+
+	#[benchmark]
+	fn sell_asset() {
+		let caller: T::AccountId = whitelisted_caller();
+		let asset = whitelisted_asset::<T>(&caller);
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller), asset.clone(), 500_000u32.into());
+
+		assert_eq!(Debt::<T>::get(&asset), 500_000u32.into());
+	}
+
+	#[benchmark]
+	fn buy_asset() {
+		let caller: T::AccountId = whitelisted_caller();
+		let asset = whitelisted_asset::<T>(&caller);
+		Psm::<T>::sell_asset(RawOrigin::Signed(caller.clone()).into(), asset.clone(), 500_000u32.into())
+			.unwrap();
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller), asset.clone(), 400_000u32.into());
+
+		assert_eq!(Debt::<T>::get(&asset), 100_000u32.into());
+	}
+
+	impl_benchmark_test_suite!(Psm, crate::mock::new_test_ext(), crate::mock::Test);
+}
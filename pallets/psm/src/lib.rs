@@ -0,0 +1,297 @@
+//! # Peg Stability Module Pallet
+//!
+//! Anchors dUSD to external stable assets by swapping them 1:1.
+//!
+//! ## Overview
+//!
+//! `AdminOrigin` whitelists fungible assets registered in `pallet-assets`, each with its own
+//! fees and debt ceiling. Only assets with the same decimals as dUSD can be whitelisted, as they
+//! are swapped 1:1 without rescaling.
+//!
+//! - `sell_asset` moves an asset into the reserve account and mints the same amount of dUSD,
+//!   less the `tin` fee. The fee is minted to the collateral engine's surplus account.
+//! - `buy_asset` burns dUSD for the same amount of the asset out of the reserve account, plus
+//!   the `tout` fee, which is paid into the surplus account.
+//!
+//! dUSD is minted and burned through the collateral engine, which issues it with
+//! `pallet_stablecoin::mint_to`/`burn_from` and counts it in `TotalDusdDebt`, so the dUSD supply
+//! keeps matching the debt recorded there. The engine also counts it in `ReserveBackedDebt`,
+//! which keeps it out of the CDP debt ceilings and of the redemption rates of CDP collateral.
+//! The dUSD minted against each asset never exceeds its reserves.
+//!
+//! No new dUSD is minted once emergency shutdown has caged the collateral engine. dUSD holders
+//! keep redeeming it for the reserves with `buy_asset` after that, without the `tout` fee.
+
+// We make sure this pallet uses `no_std` for compiling to Wasm.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+// Re-export pallet items so that they can be accessed from the crate namespace.
+pub use pallet::*;
+
+// FRAME pallets require their own "mock runtimes" to be able to run unit tests. This module
+// contains a mock runtime specific for testing this pallet's functionality.
+#[cfg(test)]
+mod mock;
+
+// This module contains the unit tests for this pallet.
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod weights;
+pub use weights::*;
+
+// All pallet logic is defined in its own module and must be annotated by the `pallet` attribute.
+#[frame_support::pallet]
+pub mod pallet {
+	// Import various useful types required by all FRAME pallets.
+	use super::*;
+	use frame_support::{
+		pallet_prelude::*,
+		traits::{
+			fungibles::{metadata::Inspect as MetadataInspect, Inspect, Mutate},
+			tokens::Preservation,
+		},
+		PalletId,
+	};
+	use frame_system::pallet_prelude::*;
+	use pallet_collateral_engine::DebtType;
+	use sp_runtime::traits::{AccountIdConversion, Saturating, Zero};
+
+	pub type BalanceOf<T> = <T as pallet_collateral_engine::Config>::Balance;
+
+	pub type AssetIdOf<T> = <<T as Config>::Assets as Inspect<<T as frame_system::Config>::AccountId>>::AssetId;
+
+	// The `Pallet` struct serves as a placeholder to implement traits, methods and dispatchables
+	// (`Call`s) in this pallet.
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	/// Sets up the assets the benchmarks of this pallet swap dUSD against.
+	#[cfg(feature = "runtime-benchmarks")]
+	pub trait BenchmarkHelper<AssetId> {
+		/// Create a sufficient asset with a minimum balance of one and the decimals of dUSD,
+		/// returning its id.
+		fn create_asset() -> AssetId;
+	}
+
+	/// The pallet's configuration trait.
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_collateral_engine::Config {
+		/// The overarching runtime event type.
+		#[allow(deprecated)]
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		/// A type representing the weights required by the dispatchables of this pallet.
+		type WeightInfo: WeightInfo;
+		/// The fungible assets dUSD can be swapped against.
+		type Assets: Mutate<Self::AccountId, Balance = BalanceOf<Self>> + MetadataInspect<Self::AccountId>;
+		/// The decimals of dUSD, which every whitelisted asset must share.
+		#[pallet::constant]
+		type DusdDecimals: Get<u8>;
+		/// The origin allowed to whitelist assets and set their fees and debt ceilings.
+		type AdminOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		/// The pallet id, used to derive the reserve account holding swapped assets.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+		/// Creates the assets swapped in benchmarks.
+		#[cfg(feature = "runtime-benchmarks")]
+		type BenchmarkHelper: BenchmarkHelper<AssetIdOf<Self>>;
+	}
+
+	/// The terms dUSD is swapped on against one asset.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct PsmParameters<Balance> {
+		/// Fee on swaps of the asset into dUSD, in basis points.
+		pub tin: u32,
+		/// Fee on swaps of dUSD into the asset, in basis points.
+		pub tout: u32,
+		/// The most dUSD that may be minted against the asset.
+		pub debt_ceiling: Balance,
+	}
+
+	/// The whitelisted assets and the terms they are swapped on.
+	#[pallet::storage]
+	pub type Parameters<T: Config> =
+		StorageMap<_, Blake2_128Concat, AssetIdOf<T>, PsmParameters<BalanceOf<T>>, OptionQuery>;
+
+	/// dUSD minted against each asset, which matches the reserves held of it.
+	#[pallet::storage]
+	pub type Debt<T: Config> = StorageMap<_, Blake2_128Concat, AssetIdOf<T>, BalanceOf<T>, ValueQuery>;
+
+	/// Events that functions in this pallet can emit.
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		ParametersUpdated {
+			asset: AssetIdOf<T>,
+			parameters: PsmParameters<BalanceOf<T>>,
+		},
+		AssetRemoved {
+			asset: AssetIdOf<T>,
+		},
+		AssetSold {
+			who: T::AccountId,
+			asset: AssetIdOf<T>,
+			amount: BalanceOf<T>,
+			fee: BalanceOf<T>,
+		},
+		AssetBought {
+			who: T::AccountId,
+			asset: AssetIdOf<T>,
+			amount: BalanceOf<T>,
+			fee: BalanceOf<T>,
+		},
+	}
+
+	/// Errors that can be returned by this pallet.
+	#[pallet::error]
+	pub enum Error<T> {
+		AssetNotWhitelisted,
+		ZeroAmount,
+		DebtCeilingExceeded,
+		InsufficientReserves,
+		/// The asset cannot be removed while dUSD minted against it is outstanding.
+		ReservesOutstanding,
+		/// Fees are in basis points and cannot exceed 100%.
+		FeeTooHigh,
+		SystemCaged,
+		/// The asset does not have the same decimals as dUSD, so it cannot be swapped 1:1.
+		DecimalsMismatch,
+	}
+
+	/// The pallet's dispatchable functions.
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Whitelist an asset, or update the fees and debt ceiling it is swapped with.
+		#[pallet::call_index(0)]
+		#[pallet::weight(<T as Config>::WeightInfo::set_parameters())]
+		pub fn set_parameters(
+			origin: OriginFor<T>,
+			asset: AssetIdOf<T>,
+			parameters: PsmParameters<BalanceOf<T>>,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			
+			ensure!(parameters.tin <= 10000 && parameters.tout <= 10000, Error::<T>::FeeTooHigh);
+			ensure!(
+				T::Assets::decimals(asset.clone()) == T::DusdDecimals::get(),
+				Error::<T>::DecimalsMismatch
+			);
+			
+			Parameters::<T>::insert(&asset, &parameters);
+			
+			Self::deposit_event(Event::ParametersUpdated { asset, parameters });
+			
+			Ok(())
+		}
+
+		/// Take an asset off the whitelist once no dUSD minted against it is outstanding.
+		#[pallet::call_index(1)]
+		#[pallet::weight(<T as Config>::WeightInfo::remove_asset())]
+		pub fn remove_asset(
+			origin: OriginFor<T>,
+			asset: AssetIdOf<T>,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			
+			ensure!(Parameters::<T>::contains_key(&asset), Error::<T>::AssetNotWhitelisted);
+			ensure!(Debt::<T>::get(&asset).is_zero(), Error::<T>::ReservesOutstanding);
+			
+			Parameters::<T>::remove(&asset);
+			
+			Self::deposit_event(Event::AssetRemoved { asset });
+			
+			Ok(())
+		}
+
+		/// Swap `amount` of `asset` for the same amount of dUSD, less the `tin` fee.
+		#[pallet::call_index(2)]
+		#[pallet::weight(<T as Config>::WeightInfo::sell_asset())]
+		pub fn sell_asset(
+			origin: OriginFor<T>,
+			asset: AssetIdOf<T>,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			
+			ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
+			ensure!(!pallet_collateral_engine::Pallet::<T>::is_caged(), Error::<T>::SystemCaged);
+			
+			let parameters = Parameters::<T>::get(&asset).ok_or(Error::<T>::AssetNotWhitelisted)?;
+			let debt = Debt::<T>::get(&asset).saturating_add(amount);
+			ensure!(debt <= parameters.debt_ceiling, Error::<T>::DebtCeilingExceeded);
+			
+			let fee = Self::fee(amount, parameters.tin);
+			T::Assets::transfer(asset.clone(), &who, &Self::reserve_account(), amount, Preservation::Expendable)?;
+			pallet_collateral_engine::Pallet::<T>::mint_reserve_backed(
+				Self::issuer(),
+				DebtType::Dusd,
+				&who,
+				amount.saturating_sub(fee),
+				fee,
+			)?;
+			
+			Debt::<T>::insert(&asset, debt);
+			
+			Self::deposit_event(Event::AssetSold { who, asset, amount, fee });
+			
+			Ok(())
+		}
+
+		/// Swap dUSD for `amount` of `asset`, paying the same amount of dUSD plus the `tout` fee.
+		///
+		/// Once the collateral engine is caged, this redeems dUSD for the reserves at par.
+		#[pallet::call_index(3)]
+		#[pallet::weight(<T as Config>::WeightInfo::buy_asset())]
+		pub fn buy_asset(
+			origin: OriginFor<T>,
+			asset: AssetIdOf<T>,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			
+			ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
+			
+			let parameters = Parameters::<T>::get(&asset).ok_or(Error::<T>::AssetNotWhitelisted)?;
+			let debt = Debt::<T>::get(&asset);
+			ensure!(debt >= amount, Error::<T>::InsufficientReserves);
+			
+			let fee = if pallet_collateral_engine::Pallet::<T>::is_caged() {
+				Zero::zero()
+			} else {
+				Self::fee(amount, parameters.tout)
+			};
+			pallet_collateral_engine::Pallet::<T>::burn_reserve_backed(
+				Self::issuer(),
+				DebtType::Dusd,
+				&who,
+				amount,
+			)?;
+			pallet_collateral_engine::Pallet::<T>::transfer_to_surplus(Self::issuer(), DebtType::Dusd, &who, fee)?;
+			T::Assets::transfer(asset.clone(), &Self::reserve_account(), &who, amount, Preservation::Expendable)?;
+			
+			Debt::<T>::insert(&asset, debt.saturating_sub(amount));
+			
+			Self::deposit_event(Event::AssetBought { who, asset, amount, fee });
+			
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// The account holding the reserves of every whitelisted asset.
+		pub fn reserve_account() -> T::AccountId {
+			T::PalletId::get().into_account_truncating()
+		}
+
+		/// The origin the PSM issues dUSD with, named by its reserve account.
+		pub fn issuer() -> pallet_collateral_engine::Origin<T> {
+			pallet_collateral_engine::RawOrigin::Issuer(Self::reserve_account())
+		}
+
+		fn fee(amount: BalanceOf<T>, rate: u32) -> BalanceOf<T> {
+			amount.saturating_mul(rate.into()) / 10000u32.into()
+		}
+	}
+}
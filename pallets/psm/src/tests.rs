@@ -0,0 +1,188 @@
+use crate::{mock::*, Debt, Error, Event, Parameters, PsmParameters};
+use frame_support::{assert_noop, assert_ok, instances::Instance1};
+use pallet_collateral_engine::{CagedAt, DebtType, ReserveBackedDebt, TotalDusdDebt};
+use pallet_system_ledger::SystemSurplus;
+use sp_runtime::traits::BadOrigin;
+
+fn parameters() -> PsmParameters<u128> {
+	PsmParameters {
+		tin: 10,  // 0.1%
+		tout: 20, // 0.2%
+		debt_ceiling: 600_000,
+	}
+}
+
+/// Registers USDX with 12 decimals in `pallet-assets`.
+fn create_usdx() {
+	assert_ok!(Assets::force_create(RuntimeOrigin::root(), USDX, 1, true, 1));
+	let (name, symbol) = (b"USDX".to_vec(), b"USDX".to_vec());
+	assert_ok!(Assets::force_set_metadata(RuntimeOrigin::root(), USDX, name, symbol, 12, false));
+}
+
+/// Registers USDX in `pallet-assets`, gives account 2 a million of it and whitelists it.
+fn setup_psm() {
+	create_usdx();
+	assert_ok!(Assets::mint(RuntimeOrigin::signed(1), USDX, 2, 1_000_000));
+	assert_ok!(Psm::set_parameters(RuntimeOrigin::root(), USDX, parameters()));
+}
+
+/// Every dUSD in circulation is counted as debt by the collateral engine, and the dUSD minted
+/// against USDX matches the USDX in reserve and the engine's reserve-backed debt.
+fn assert_books_balance() {
+	assert_eq!(pallet_stablecoin::TotalSupply::<Test, Instance1>::get(), TotalDusdDebt::<Test>::get());
+	assert_eq!(Debt::<Test>::get(USDX), Assets::balance(USDX, Psm::reserve_account()));
+	assert_eq!(Debt::<Test>::get(USDX), ReserveBackedDebt::<Test>::get(DebtType::Dusd));
+	assert_eq!(
+		SystemSurplus::<Test>::get(DebtType::Dusd),
+		pallet_stablecoin::Balances::<Test, Instance1>::get(CollateralEngine::surplus_account())
+	);
+}
+
+#[test]
+fn set_parameters_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		create_usdx();
+		
+		assert_noop!(Psm::set_parameters(RuntimeOrigin::signed(1), USDX, parameters()), BadOrigin);
+		assert_noop!(
+			Psm::set_parameters(RuntimeOrigin::root(), USDX, PsmParameters { tin: 10001, ..parameters() }),
+			Error::<Test>::FeeTooHigh
+		);
+		
+		assert_ok!(Psm::set_parameters(RuntimeOrigin::root(), USDX, parameters()));
+		assert_eq!(Parameters::<Test>::get(USDX), Some(parameters()));
+		System::assert_last_event(Event::ParametersUpdated { asset: USDX, parameters: parameters() }.into());
+	});
+}
+
+#[test]
+fn set_parameters_requires_the_decimals_of_dusd() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		
+		// An asset without metadata reports no decimals
+		assert_ok!(Assets::force_create(RuntimeOrigin::root(), USDX, 1, true, 1));
+		assert_noop!(
+			Psm::set_parameters(RuntimeOrigin::root(), USDX, parameters()),
+			Error::<Test>::DecimalsMismatch
+		);
+		
+		let (name, symbol) = (b"USDX".to_vec(), b"USDX".to_vec());
+		assert_ok!(Assets::force_set_metadata(RuntimeOrigin::root(), USDX, name, symbol, 6, false));
+		assert_noop!(
+			Psm::set_parameters(RuntimeOrigin::root(), USDX, parameters()),
+			Error::<Test>::DecimalsMismatch
+		);
+	});
+}
+
+#[test]
+fn sell_asset_mints_dusd_less_the_fee() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		setup_psm();
+		
+		assert_ok!(Psm::sell_asset(RuntimeOrigin::signed(2), USDX, 500_000));
+		
+		assert_eq!(Assets::balance(USDX, 2), 500_000);
+		assert_eq!(Assets::balance(USDX, Psm::reserve_account()), 500_000);
+		assert_eq!(pallet_stablecoin::Balances::<Test, Instance1>::get(2), 499_500);
+		assert_eq!(SystemSurplus::<Test>::get(DebtType::Dusd), 500);
+		assert_eq!(Debt::<Test>::get(USDX), 500_000);
+		assert_eq!(TotalDusdDebt::<Test>::get(), 500_000);
+		assert_books_balance();
+		System::assert_last_event(Event::AssetSold { who: 2, asset: USDX, amount: 500_000, fee: 500 }.into());
+		
+		assert_noop!(
+			Psm::sell_asset(RuntimeOrigin::signed(2), USDX, 100_001),
+			Error::<Test>::DebtCeilingExceeded
+		);
+		assert_noop!(Psm::sell_asset(RuntimeOrigin::signed(2), USDX, 0), Error::<Test>::ZeroAmount);
+		assert_noop!(
+			Psm::sell_asset(RuntimeOrigin::signed(2), USDX + 1, 1_000),
+			Error::<Test>::AssetNotWhitelisted
+		);
+	});
+}
+
+#[test]
+fn buy_asset_burns_dusd_plus_the_fee() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		setup_psm();
+		assert_ok!(Psm::sell_asset(RuntimeOrigin::signed(2), USDX, 500_000));
+		
+		assert_ok!(Psm::buy_asset(RuntimeOrigin::signed(2), USDX, 200_000));
+		
+		assert_eq!(Assets::balance(USDX, 2), 700_000);
+		assert_eq!(pallet_stablecoin::Balances::<Test, Instance1>::get(2), 299_100);
+		assert_eq!(SystemSurplus::<Test>::get(DebtType::Dusd), 900);
+		assert_eq!(Debt::<Test>::get(USDX), 300_000);
+		assert_eq!(TotalDusdDebt::<Test>::get(), 300_000);
+		assert_books_balance();
+		System::assert_last_event(Event::AssetBought { who: 2, asset: USDX, amount: 200_000, fee: 400 }.into());
+		
+		// Only what was sold to the module can be bought back
+		assert_noop!(
+			Psm::buy_asset(RuntimeOrigin::signed(2), USDX, 300_001),
+			Error::<Test>::InsufficientReserves
+		);
+		assert!(Psm::buy_asset(RuntimeOrigin::signed(2), USDX, 299_000).is_err());
+	});
+}
+
+#[test]
+fn remove_asset_requires_no_outstanding_debt() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		setup_psm();
+		assert_ok!(Psm::sell_asset(RuntimeOrigin::signed(2), USDX, 1_000));
+		
+		assert_noop!(Psm::remove_asset(RuntimeOrigin::root(), USDX), Error::<Test>::ReservesOutstanding);
+		
+		// Account 2 buys the reserves back without a fee, using the dUSD it paid the surplus
+		assert_ok!(CollateralEngine::transfer_stablecoin(
+			CollateralEngine::issuer(),
+			DebtType::Dusd,
+			&CollateralEngine::surplus_account(),
+			&2,
+			1
+		));
+		assert_ok!(Psm::set_parameters(RuntimeOrigin::root(), USDX, PsmParameters { tout: 0, ..parameters() }));
+		assert_ok!(Psm::buy_asset(RuntimeOrigin::signed(2), USDX, 1_000));
+		assert_eq!(Debt::<Test>::get(USDX), 0);
+		assert_noop!(Psm::remove_asset(RuntimeOrigin::signed(1), USDX), BadOrigin);
+		assert_ok!(Psm::remove_asset(RuntimeOrigin::root(), USDX));
+		
+		assert_eq!(Parameters::<Test>::get(USDX), None);
+		System::assert_last_event(Event::AssetRemoved { asset: USDX }.into());
+		assert_noop!(
+			Psm::sell_asset(RuntimeOrigin::signed(2), USDX, 1_000),
+			Error::<Test>::AssetNotWhitelisted
+		);
+	});
+}
+
+#[test]
+fn shutdown_stops_sales_but_not_redemptions() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		setup_psm();
+		assert_ok!(Psm::sell_asset(RuntimeOrigin::signed(2), USDX, 500_000));
+		
+		CagedAt::<Test>::put(1);
+		
+		assert_noop!(Psm::sell_asset(RuntimeOrigin::signed(2), USDX, 1_000), Error::<Test>::SystemCaged);
+		
+		// The reserves are redeemed at par
+		assert_ok!(Psm::buy_asset(RuntimeOrigin::signed(2), USDX, 100_000));
+		assert_eq!(pallet_stablecoin::Balances::<Test, Instance1>::get(2), 399_500);
+		assert_eq!(Debt::<Test>::get(USDX), 400_000);
+		assert_books_balance();
+		System::assert_last_event(Event::AssetBought { who: 2, asset: USDX, amount: 100_000, fee: 0 }.into());
+		
+		// dUSD minted against the reserves has no claim on CDP collateral
+		assert_eq!(CollateralEngine::collateral_backed_debt(DebtType::Dusd), 0);
+	});
+}
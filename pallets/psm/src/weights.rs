@@ -0,0 +1,75 @@
+//! Weights for pallet_psm
+//!
+//! Estimated from the template benchmarks until the pallet is benchmarked on reference hardware.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_psm.
+pub trait WeightInfo {
+	fn set_parameters() -> Weight;
+	fn remove_asset() -> Weight;
+	fn sell_asset() -> Weight;
+	fn buy_asset() -> Weight;
+}
+
+/// Weights for pallet_psm using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: Psm Parameters, Assets Metadata (r:1 w:1)
+	fn set_parameters() -> Weight {
+		Weight::from_parts(12_000_000, 3_605)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: Psm Parameters, Debt (r:2 w:1)
+	fn remove_asset() -> Weight {
+		Weight::from_parts(12_000_000, 3_541)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: Psm Parameters, Debt, CollateralEngine CagedAt, TotalDusdDebt, ReserveBackedDebt, Assets Asset, Account, Dusd Balances, TotalSupply, System ledger SystemSurplus (r:12 w:10)
+	fn sell_asset() -> Weight {
+		Weight::from_parts(68_000_000, 6_208)
+			.saturating_add(T::DbWeight::get().reads(12_u64))
+			.saturating_add(T::DbWeight::get().writes(10_u64))
+	}
+	/// Storage: Psm Parameters, Debt, CollateralEngine CagedAt, TotalDusdDebt, ReserveBackedDebt, Assets Asset, Account, Dusd Balances, TotalSupply, System ledger SystemSurplus (r:12 w:10)
+	fn buy_asset() -> Weight {
+		Weight::from_parts(66_000_000, 6_208)
+			.saturating_add(T::DbWeight::get().reads(12_u64))
+			.saturating_add(T::DbWeight::get().writes(10_u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	/// Storage: Psm Parameters, Assets Metadata (r:1 w:1)
+	fn set_parameters() -> Weight {
+		Weight::from_parts(12_000_000, 3_605)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: Psm Parameters, Debt (r:2 w:1)
+	fn remove_asset() -> Weight {
+		Weight::from_parts(12_000_000, 3_541)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: Psm Parameters, Debt, CollateralEngine CagedAt, TotalDusdDebt, ReserveBackedDebt, Assets Asset, Account, Dusd Balances, TotalSupply, System ledger SystemSurplus (r:12 w:10)
+	fn sell_asset() -> Weight {
+		Weight::from_parts(68_000_000, 6_208)
+			.saturating_add(RocksDbWeight::get().reads(12_u64))
+			.saturating_add(RocksDbWeight::get().writes(10_u64))
+	}
+	/// Storage: Psm Parameters, Debt, CollateralEngine CagedAt, TotalDusdDebt, ReserveBackedDebt, Assets Asset, Account, Dusd Balances, TotalSupply, System ledger SystemSurplus (r:12 w:10)
+	fn buy_asset() -> Weight {
+		Weight::from_parts(66_000_000, 6_208)
+			.saturating_add(RocksDbWeight::get().reads(12_u64))
+			.saturating_add(RocksDbWeight::get().writes(10_u64))
+	}
+}
@@ -0,0 +1,19 @@
+//! Runtime API of the savings pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+
+pub use pallet_savings::AccountSavings;
+
+sp_api::decl_runtime_apis! {
+	/// Reports savings with the same math the savings pallet applies on chain.
+	pub trait SavingsApi<AccountId, Balance>
+	where
+		AccountId: Codec,
+		Balance: Codec,
+	{
+		/// The savings of `who` in both stablecoins, with interest accrued up to the current block.
+		fn savings_of(who: AccountId) -> AccountSavings<Balance>;
+	}
+}
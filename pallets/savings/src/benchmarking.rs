@@ -0,0 +1,67 @@
+//! Benchmarking setup for pallet-savings
+
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as Savings;
+use frame_benchmarking::v2::*;
+use frame_system::RawOrigin;
+use pallet_collateral_engine::{DebtType, StablecoinMinter};
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	/// Puts `caller` in the dUSD pot at a 5% rate, with the accumulator last updated at block 1.
+	fn join_pot<T: Config>(caller: &T::AccountId) {
+		frame_system::Pallet::<T>::set_block_number(1u32.into());
+		SavingsRates::<T>::insert(DebtType::Dusd, 500);
+		T::DusdMinter::mint(Pallet::<T>::issuer(), caller, 1_000u32.into()).unwrap();
+		pallet_collateral_engine::TotalDusdDebt::<T>::put(BalanceOf::<T>::from(1_000u32));
+		Pallet::<T>::join(RawOrigin::Signed(caller.clone()).into(), DebtType::Dusd, 1_000u32.into()).unwrap();
+		frame_system::Pallet::<T>::set_block_number(100u32.into());
+	}
+
+	#[benchmark]
+	fn join() {
+		let caller: T::AccountId = whitelisted_caller();
+		join_pot::<T>(&caller);
+		T::DusdMinter::mint(Pallet::<T>::issuer(), &caller, 1_000u32.into()).unwrap();
+		#[extrinsic_call]
+		join(RawOrigin::Signed(caller.clone()), DebtType::Dusd, 1_000u32.into());
+
+		assert!(Pie::<T>::get(DebtType::Dusd, &caller) > 1_000u32.into());
+	}
+
+	#[benchmark]
+	fn exit() {
+		let caller: T::AccountId = whitelisted_caller();
+		join_pot::<T>(&caller);
+		#[extrinsic_call]
+		exit(RawOrigin::Signed(caller.clone()), DebtType::Dusd, 1_000u32.into());
+
+		assert!(T::DusdMinter::balance(&caller) == 1_000u32.into());
+	}
+
+	#[benchmark]
+	fn drip() {
+		let caller: T::AccountId = whitelisted_caller();
+		join_pot::<T>(&caller);
+		#[extrinsic_call]
+		drip_savings(RawOrigin::Signed(caller), DebtType::Dusd);
+
+		assert_eq!(ChiUpdatedAt::<T>::get(DebtType::Dusd), Some(100));
+	}
+
+	#[benchmark]
+	fn set_savings_rate() {
+		let caller: T::AccountId = whitelisted_caller();
+		join_pot::<T>(&caller);
+		#[extrinsic_call]
+		set_savings_rate(RawOrigin::Root, DebtType::Dusd, 100);
+
+		assert_eq!(SavingsRates::<T>::get(DebtType::Dusd), 100);
+	}
+
+	impl_benchmark_test_suite!(Savings, crate::mock::new_test_ext(), crate::mock::Test);
+}
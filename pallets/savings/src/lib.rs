@@ -0,0 +1,355 @@
+//! # Savings Pallet
+//!
+//! The dUSD and dEUR savings rate, the main lever for holding the stablecoins to their peg.
+//!
+//! ## Overview
+//!
+//! Holders `join` the savings pot by locking stablecoin in the pot account and `exit` with
+//! their deposit plus the interest accrued since. Deposits are recorded as **normalized
+//! savings**: the amount divided by a per-stablecoin accumulator, `chi`, that compounds at the
+//! savings rate. An account's savings are worth its normalized savings times the current `chi`.
+//!
+//! Interest is issued to the pot as the accumulator grows and recorded in the system ledger as
+//! bad debt, which the surplus collected as stability fees heals. `AdminOrigin` sets the rate
+//! per stablecoin, in basis points per year, and the accumulator stops growing once emergency
+//! shutdown has caged the collateral engine.
+
+// We make sure this pallet uses `no_std` for compiling to Wasm.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+// Re-export pallet items so that they can be accessed from the crate namespace.
+pub use pallet::*;
+
+// FRAME pallets require their own "mock runtimes" to be able to run unit tests. This module
+// contains a mock runtime specific for testing this pallet's functionality.
+#[cfg(test)]
+mod mock;
+
+// This module contains the unit tests for this pallet.
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod weights;
+pub use weights::*;
+
+// All pallet logic is defined in its own module and must be annotated by the `pallet` attribute.
+#[frame_support::pallet]
+pub mod pallet {
+	// Import various useful types required by all FRAME pallets.
+	use super::*;
+	use frame_support::{pallet_prelude::*, PalletId};
+	use frame_system::pallet_prelude::*;
+	use pallet_collateral_engine::DebtType;
+	use sp_runtime::{
+		helpers_128bit::multiply_by_rational_with_rounding,
+		traits::{AccountIdConversion, One, SaturatedConversion, Saturating, Zero},
+		FixedPointNumber, FixedU128, Rounding,
+	};
+
+	pub type BalanceOf<T> = <T as pallet_collateral_engine::Config>::Balance;
+
+	// The `Pallet` struct serves as a placeholder to implement traits, methods and dispatchables
+	// (`Call`s) in this pallet.
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	/// The pallet's configuration trait.
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_collateral_engine::Config {
+		/// The overarching runtime event type.
+		#[allow(deprecated)]
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		/// A type representing the weights required by the dispatchables of this pallet.
+		type WeightInfo: WeightInfo;
+		/// The origin allowed to set the savings rates.
+		type AdminOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		/// The pallet id, used to derive the pot account holding deposited stablecoin.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+		/// The highest savings rate governance may set, in basis points per year.
+		#[pallet::constant]
+		type MaxSavingsRate: Get<u32>;
+	}
+
+	/// The savings of one account in both stablecoins, including interest up to the current
+	/// block.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct AccountSavings<Balance> {
+		pub dusd: Balance,
+		pub deur: Balance,
+	}
+
+	#[pallet::type_value]
+	pub fn DefaultChi() -> FixedU128 {
+		FixedU128::one()
+	}
+
+	/// The savings rate of each stablecoin, in basis points per year.
+	#[pallet::storage]
+	pub type SavingsRates<T> = StorageMap<_, Twox64Concat, DebtType, u32, ValueQuery>;
+
+	/// The savings accumulator of each stablecoin.
+	#[pallet::storage]
+	pub type Chi<T> = StorageMap<_, Twox64Concat, DebtType, FixedU128, ValueQuery, DefaultChi>;
+
+	/// The block at which each accumulator was last brought up to date.
+	#[pallet::storage]
+	pub type ChiUpdatedAt<T> = StorageMap<_, Twox64Concat, DebtType, u32, OptionQuery>;
+
+	/// Normalized savings per stablecoin and account.
+	#[pallet::storage]
+	pub type Pie<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, DebtType, Blake2_128Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
+
+	/// Total normalized savings per stablecoin.
+	#[pallet::storage]
+	pub type TotalPie<T: Config> = StorageMap<_, Twox64Concat, DebtType, BalanceOf<T>, ValueQuery>;
+
+	/// Events that functions in this pallet can emit.
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		Joined {
+			who: T::AccountId,
+			currency: DebtType,
+			amount: BalanceOf<T>,
+		},
+		Exited {
+			who: T::AccountId,
+			currency: DebtType,
+			amount: BalanceOf<T>,
+		},
+		Dripped {
+			currency: DebtType,
+			chi: FixedU128,
+			interest: BalanceOf<T>,
+		},
+		SavingsRateUpdated {
+			currency: DebtType,
+			rate: u32,
+		},
+	}
+
+	/// Errors that can be returned by this pallet.
+	#[pallet::error]
+	pub enum Error<T> {
+		ZeroAmount,
+		InsufficientSavings,
+		SystemCaged,
+		RateTooHigh,
+		Overflow,
+	}
+
+	/// The pallet's dispatchable functions.
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Lock `amount` of stablecoin in the savings pot.
+		#[pallet::call_index(0)]
+		#[pallet::weight(<T as Config>::WeightInfo::join())]
+		pub fn join(
+			origin: OriginFor<T>,
+			currency: DebtType,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			
+			ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
+			ensure!(!pallet_collateral_engine::Pallet::<T>::is_caged(), Error::<T>::SystemCaged);
+			
+			let chi = Self::drip(currency)?;
+			let pie = Self::normalize(amount, chi, Rounding::Down)?;
+			
+			pallet_collateral_engine::Pallet::<T>::transfer_stablecoin(
+				Self::issuer(),
+				currency,
+				&who,
+				&Self::pot_account(),
+				amount,
+			)?;
+			
+			Pie::<T>::mutate(currency, &who, |total| *total = total.saturating_add(pie));
+			TotalPie::<T>::mutate(currency, |total| *total = total.saturating_add(pie));
+			
+			Self::deposit_event(Event::Joined { who, currency, amount });
+			
+			Ok(())
+		}
+
+		/// Withdraw `amount` of stablecoin from the savings pot.
+		///
+		/// Withdrawing everything an account's savings are worth clears its rounding dust too.
+		#[pallet::call_index(1)]
+		#[pallet::weight(<T as Config>::WeightInfo::exit())]
+		pub fn exit(
+			origin: OriginFor<T>,
+			currency: DebtType,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			
+			ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
+			
+			let chi = Self::drip(currency)?;
+			let held = Pie::<T>::get(currency, &who);
+			let savings = Self::denormalize(held, chi);
+			ensure!(amount <= savings, Error::<T>::InsufficientSavings);
+			
+			let pie = if amount == savings { held } else { Self::normalize(amount, chi, Rounding::Up)?.min(held) };
+			
+			pallet_collateral_engine::Pallet::<T>::transfer_stablecoin(
+				Self::issuer(),
+				currency,
+				&Self::pot_account(),
+				&who,
+				amount,
+			)?;
+			
+			if pie == held {
+				Pie::<T>::remove(currency, &who);
+			} else {
+				Pie::<T>::insert(currency, &who, held.saturating_sub(pie));
+			}
+			TotalPie::<T>::mutate(currency, |total| *total = total.saturating_sub(pie));
+			
+			Self::deposit_event(Event::Exited { who, currency, amount });
+			
+			Ok(())
+		}
+
+		/// Bring the savings accumulator of `currency` up to the current block and issue the
+		/// accrued interest to the pot.
+		#[pallet::call_index(2)]
+		#[pallet::weight(<T as Config>::WeightInfo::drip())]
+		pub fn drip_savings(
+			origin: OriginFor<T>,
+			currency: DebtType,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+			
+			Self::drip(currency)?;
+			
+			Ok(())
+		}
+
+		/// Set the savings rate of `currency`, in basis points per year.
+		///
+		/// Interest accrued so far is issued at the previous rate first.
+		#[pallet::call_index(3)]
+		#[pallet::weight(<T as Config>::WeightInfo::set_savings_rate())]
+		pub fn set_savings_rate(
+			origin: OriginFor<T>,
+			currency: DebtType,
+			rate: u32,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			
+			ensure!(rate <= T::MaxSavingsRate::get(), Error::<T>::RateTooHigh);
+			
+			Self::drip(currency)?;
+			SavingsRates::<T>::insert(currency, rate);
+			
+			Self::deposit_event(Event::SavingsRateUpdated { currency, rate });
+			
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// The account holding the stablecoin locked in the savings pot.
+		pub fn pot_account() -> T::AccountId {
+			T::PalletId::get().into_account_truncating()
+		}
+
+		/// The origin the savings pot moves and issues stablecoin with, named by the pot account.
+		pub fn issuer() -> pallet_collateral_engine::Origin<T> {
+			pallet_collateral_engine::RawOrigin::Issuer(Self::pot_account())
+		}
+
+		/// The savings of `who` in both stablecoins, with interest up to the current block.
+		pub fn savings_of(who: &T::AccountId) -> AccountSavings<BalanceOf<T>> {
+			AccountSavings {
+				dusd: Self::denormalize(Pie::<T>::get(DebtType::Dusd, who), Self::current_chi(DebtType::Dusd)),
+				deur: Self::denormalize(Pie::<T>::get(DebtType::Deur, who), Self::current_chi(DebtType::Deur)),
+			}
+		}
+
+		/// The accumulator of `currency`, compounded up to the current block without touching
+		/// storage.
+		pub fn current_chi(currency: DebtType) -> FixedU128 {
+			let chi = Chi::<T>::get(currency);
+			match ChiUpdatedAt::<T>::get(currency) {
+				Some(last) if !pallet_collateral_engine::Pallet::<T>::is_caged() && Self::now() > last => {
+					chi.saturating_mul(Self::chi_growth(currency, Self::now().saturating_sub(last)))
+				},
+				_ => chi,
+			}
+		}
+
+		/// Bring the accumulator of `currency` up to the current block, issuing the interest
+		/// accrued on all savings to the pot. Returns the new accumulator.
+		pub fn drip(currency: DebtType) -> Result<FixedU128, DispatchError> {
+			let chi = Chi::<T>::get(currency);
+			
+			// Savings stop growing once the system is caged
+			if pallet_collateral_engine::Pallet::<T>::is_caged() {
+				return Ok(chi);
+			}
+			
+			let now = Self::now();
+			let Some(last) = ChiUpdatedAt::<T>::get(currency) else {
+				ChiUpdatedAt::<T>::insert(currency, now);
+				return Ok(chi);
+			};
+			
+			if now <= last {
+				return Ok(chi);
+			}
+			
+			let new_chi = chi.saturating_mul(Self::chi_growth(currency, now.saturating_sub(last)));
+			let total_pie = TotalPie::<T>::get(currency);
+			let interest = Self::denormalize(total_pie, new_chi).saturating_sub(Self::denormalize(total_pie, chi));
+			
+			pallet_collateral_engine::Pallet::<T>::issue_unbacked(Self::issuer(), currency, &Self::pot_account(), interest)?;
+			Chi::<T>::insert(currency, new_chi);
+			ChiUpdatedAt::<T>::insert(currency, now);
+			
+			if new_chi != chi {
+				Self::deposit_event(Event::Dripped { currency, chi: new_chi, interest });
+			}
+			
+			Ok(new_chi)
+		}
+
+		fn chi_growth(currency: DebtType, blocks: u32) -> FixedU128 {
+			let blocks_per_year = <T as pallet_collateral_engine::Config>::BlocksPerYear::get();
+			let per_block = FixedU128::one().saturating_add(FixedU128::saturating_from_rational(
+				SavingsRates::<T>::get(currency),
+				10000u128.saturating_mul(blocks_per_year as u128),
+			));
+			
+			per_block.saturating_pow(blocks as usize)
+		}
+
+		fn normalize(amount: BalanceOf<T>, chi: FixedU128, rounding: Rounding) -> Result<BalanceOf<T>, Error<T>> {
+			let normalized = multiply_by_rational_with_rounding(
+				amount.saturated_into::<u128>(),
+				FixedU128::DIV,
+				chi.into_inner(),
+				rounding,
+			)
+			.ok_or(Error::<T>::Overflow)?;
+			
+			normalized.try_into().map_err(|_| Error::<T>::Overflow)
+		}
+
+		fn denormalize(pie: BalanceOf<T>, chi: FixedU128) -> BalanceOf<T> {
+			chi.saturating_mul_int(pie)
+		}
+
+		fn now() -> u32 {
+			frame_system::Pallet::<T>::block_number().saturated_into()
+		}
+	}
+}
@@ -0,0 +1,278 @@
+use crate as pallet_savings;
+use frame_support::{
+	derive_impl,
+	instances::{Instance1, Instance2},
+	parameter_types,
+	traits::{ConstU128, ConstU32, ConstU64, ConstU8, EitherOfDiverse},
+	PalletId,
+};
+use frame_system::{
+	offchain::{AppCrypto, CreateBare, CreateSignedTransaction, CreateTransactionBase, SigningTypes},
+	EnsureRoot,
+};
+use pallet_collateral_engine::{CollateralAsset, CollateralId, CollateralType, EnsureIssuer, PriceFeed};
+use sp_runtime::{
+	generic::UncheckedExtrinsic,
+	testing::{TestSignature, UintAuthorityId},
+	BuildStorage,
+};
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+#[frame_support::runtime]
+mod runtime {
+	// The main runtime
+	#[runtime::runtime]
+	// Runtime Types to be generated
+	#[runtime::derive(
+		RuntimeCall,
+		RuntimeEvent,
+		RuntimeError,
+		RuntimeOrigin,
+		RuntimeFreezeReason,
+		RuntimeHoldReason,
+		RuntimeSlashReason,
+		RuntimeLockId,
+		RuntimeTask,
+		RuntimeViewFunction
+	)]
+	pub struct Test;
+
+	#[runtime::pallet_index(0)]
+	pub type System = frame_system::Pallet<Test>;
+
+	#[runtime::pallet_index(1)]
+	pub type Balances = pallet_balances::Pallet<Test>;
+
+	#[runtime::pallet_index(2)]
+	pub type CollateralEngine = pallet_collateral_engine::Pallet<Test>;
+
+	#[runtime::pallet_index(3)]
+	pub type Dusd = pallet_stablecoin::Pallet<Test, Instance1>;
+
+	#[runtime::pallet_index(4)]
+	pub type Deur = pallet_stablecoin::Pallet<Test, Instance2>;
+
+	#[runtime::pallet_index(5)]
+	pub type SystemLedger = pallet_system_ledger::Pallet<Test>;
+
+	#[runtime::pallet_index(6)]
+	pub type OriumToken = pallet_orium_token::Pallet<Test>;
+
+	#[runtime::pallet_index(7)]
+	pub type Oracle = pallet_oracle::Pallet<Test>;
+
+	#[runtime::pallet_index(8)]
+	pub type Timestamp = pallet_timestamp::Pallet<Test>;
+
+	#[runtime::pallet_index(9)]
+	pub type Savings = pallet_savings::Pallet<Test>;
+}
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+	type Block = Block;
+	type AccountData = pallet_balances::AccountData<u128>;
+}
+
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
+impl pallet_balances::Config for Test {
+	type Balance = u128;
+	type ExistentialDeposit = ConstU128<1>;
+	type AccountStore = System;
+}
+
+impl pallet_timestamp::Config for Test {
+	type Moment = u64;
+	type OnTimestampSet = ();
+	type MinimumPeriod = ConstU64<1>;
+	type WeightInfo = ();
+}
+
+/// One hour, in milliseconds.
+pub const DELAY: u64 = 3_600_000;
+
+parameter_types! {
+	pub const CollateralEnginePalletId: PalletId = PalletId(*b"orm/cdpe");
+	pub const SavingsPalletId: PalletId = PalletId(*b"orm/save");
+	pub const DusdSymbol: &'static str = "dUSD";
+	pub const DeurSymbol: &'static str = "dEUR";
+	pub const Usd: &'static str = "USD";
+	pub const Eur: &'static str = "EUR";
+}
+
+impl pallet_collateral_engine::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = ();
+	type Balance = u128;
+	type Currency = Balances;
+	type OriumToken = OriumToken;
+	type Oracle = Oracle;
+	type UnixTime = Timestamp;
+	type MaxPriceAge = ConstU64<86_400_000>; // One day
+	type BlocksPerYear = ConstU32<10_000>;
+	type PalletId = CollateralEnginePalletId;
+	type AuctionCurve = pallet_collateral_engine::LinearDecrease<ConstU32<100>>;
+	type AuctionStartBuffer = ConstU32<12000>; // 120%
+	type AuctionResetDuration = ConstU32<80>;
+	type AuctionResetRatio = ConstU32<4000>; // 40%
+	type LiquidationPenalty = ConstU32<1300>; // 13%
+	type CloseFactor = ConstU32<8000>; // 80%
+	type DusdMinter = Dusd;
+	type DeurMinter = Deur;
+	type ShutdownOrigin = EnsureRoot<u64>;
+	type AdminOrigin = EnsureRoot<u64>;
+	type MaxCollateralTypes = ConstU32<3>;
+	type Ledger = SystemLedger;
+	type LiquidationScanBatch = ConstU32<10>;
+	type MaxAutoLiquidations = ConstU32<2>;
+	type UnsignedPriority = ConstU64<100>;
+}
+
+impl pallet_system_ledger::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = ();
+	type AdminOrigin = EnsureRoot<u64>;
+}
+
+impl pallet_savings::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = ();
+	type AdminOrigin = EnsureRoot<u64>;
+	type PalletId = SavingsPalletId;
+	type MaxSavingsRate = ConstU32<2000>; // 20%
+}
+
+/// The modules issuing stablecoin in this mock.
+type Issuers = EitherOfDiverse<
+	EnsureIssuer<Test, CollateralEnginePalletId>,
+	EnsureIssuer<Test, SavingsPalletId>,
+>;
+
+impl pallet_stablecoin::Config<Instance1> for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = ();
+	type Balance = u128;
+	type Symbol = DusdSymbol;
+	type Decimals = ConstU8<12>;
+	type PegCurrency = Usd;
+	type RuntimeCall = RuntimeCall;
+	type MaxFlashMint = ConstU128<1_000_000_000>;
+	type OtherFlashMints = ();
+	type FlashMintFee = ConstU32<9>;
+	type OnFlashMintFee = ();
+	type IssuerOrigin = EitherOfDiverse<EnsureRoot<u64>, Issuers>;
+}
+
+impl pallet_stablecoin::Config<Instance2> for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = ();
+	type Balance = u128;
+	type Symbol = DeurSymbol;
+	type Decimals = ConstU8<12>;
+	type PegCurrency = Eur;
+	type RuntimeCall = RuntimeCall;
+	type MaxFlashMint = ConstU128<1_000_000_000>;
+	type OtherFlashMints = ();
+	type FlashMintFee = ConstU32<9>;
+	type OnFlashMintFee = ();
+	type IssuerOrigin = EitherOfDiverse<EnsureRoot<u64>, Issuers>;
+}
+
+impl pallet_orium_token::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = ();
+	type Balance = u128;
+}
+
+impl pallet_oracle::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = ();
+	type AdminOrigin = EnsureRoot<u64>;
+	type MaxFeeders = ConstU32<4>;
+	type RoundTimeout = ConstU32<10>;
+	type UnixTime = Timestamp;
+	type SecurityDelay = ConstU64<DELAY>;
+	type AuthorityId = TestAuthId;
+	type FetchInterval = ConstU32<0>;
+	type MaxSources = ConstU32<1>;
+	type MaxSourceLength = ConstU32<64>;
+	type MaxObservations = ConstU32<8>;
+	type OnPriceUpdate = CollateralEngine;
+}
+
+/// Feeder keys for the oracle, whose offchain worker is disabled here.
+pub struct TestAuthId;
+
+impl AppCrypto<UintAuthorityId, TestSignature> for TestAuthId {
+	type RuntimeAppPublic = UintAuthorityId;
+	type GenericPublic = UintAuthorityId;
+	type GenericSignature = TestSignature;
+}
+
+impl SigningTypes for Test {
+	type Public = UintAuthorityId;
+	type Signature = TestSignature;
+}
+
+impl<LocalCall> CreateTransactionBase<LocalCall> for Test
+where
+	RuntimeCall: From<LocalCall>,
+{
+	type RuntimeCall = RuntimeCall;
+	type Extrinsic = UncheckedExtrinsic<u64, RuntimeCall, (), ()>;
+}
+
+impl<LocalCall> CreateSignedTransaction<LocalCall> for Test
+where
+	RuntimeCall: From<LocalCall>,
+{
+	fn create_signed_transaction<C: AppCrypto<Self::Public, Self::Signature>>(
+		call: RuntimeCall,
+		_public: UintAuthorityId,
+		account: u64,
+		_nonce: u64,
+	) -> Option<Self::Extrinsic> {
+		Some(UncheckedExtrinsic::new_signed(call, account, (), ()))
+	}
+}
+
+impl<LocalCall> CreateBare<LocalCall> for Test
+where
+	RuntimeCall: From<LocalCall>,
+{
+	fn create_bare(call: RuntimeCall) -> Self::Extrinsic {
+		UncheckedExtrinsic::new_bare(call)
+	}
+}
+
+/// The only oracle feeder, so every submission sets the price.
+pub const FEEDER: u64 = 100;
+
+/// The only collateral type: native ORM at 150% / 130%, with a 10% stability fee.
+pub const NATIVE: CollateralId = 0;
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut storage = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+	pallet_collateral_engine::GenesisConfig::<Test> {
+		collateral_types: vec![(
+			NATIVE,
+			CollateralType {
+				asset: CollateralAsset::Native,
+				price_feed: PriceFeed::Orm,
+				min_collateral_ratio: 15000,
+				liquidation_ratio: 13000,
+				stability_fee: 1000,
+				debt_ceiling: u128::MAX,
+				dust: 0,
+			},
+		)],
+	}
+	.assimilate_storage(&mut storage)
+	.unwrap();
+	pallet_oracle::GenesisConfig::<Test> { feeders: vec![FEEDER], min_quorum: 1 }
+		.assimilate_storage(&mut storage)
+		.unwrap();
+	storage.into()
+}
@@ -0,0 +1,167 @@
+use crate::{mock::*, Chi, ChiUpdatedAt, Error, Event, Pie, SavingsRates, TotalPie};
+use frame_support::{assert_noop, assert_ok, instances::Instance1, traits::Currency};
+use pallet_collateral_engine::{DebtType, NextCdpId, TotalDusdDebt};
+use pallet_oracle::AssetPair;
+use pallet_system_ledger::{SystemBadDebt, SystemSurplus};
+use sp_runtime::{traits::BadOrigin, FixedU128};
+
+/// Prices are quoted with 18 decimals.
+const PRICE_ONE: u128 = 1_000_000_000_000_000_000;
+
+/// Opens the next oracle round for both pairs, which the single feeder settles on its own, and
+/// lets the new prices through the oracle security module.
+fn set_prices(usd: u128, eur: u128) {
+	for (pair, price) in [(AssetPair::OrmUsd, usd), (AssetPair::OrmEur, eur)] {
+		let round = pallet_oracle::Rounds::<Test>::get(pair).id + 1;
+		assert_ok!(Oracle::submit_price(RuntimeOrigin::signed(FEEDER), pair, round, price));
+	}
+	for _ in 0..2 {
+		Timestamp::set_timestamp(Timestamp::get() + DELAY);
+		for pair in [AssetPair::OrmUsd, AssetPair::OrmEur] {
+			let _ = Oracle::poke(RuntimeOrigin::signed(FEEDER), pair);
+		}
+	}
+}
+
+/// Account 1 borrows 2_000_000 dUSD against 6_000_000 ORM and puts half of it in the pot at a
+/// 10% savings rate, the same as the stability fee.
+fn setup_savings() {
+	set_prices(PRICE_ONE, PRICE_ONE * 8 / 10);
+	let _ = Balances::deposit_creating(&1, 6_000_000);
+	let cdp_id = NextCdpId::<Test>::get();
+	assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(1), NATIVE, 6_000_000));
+	assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(1), cdp_id, 2_000_000));
+	
+	assert_ok!(Savings::set_savings_rate(RuntimeOrigin::root(), DebtType::Dusd, 1000));
+	assert_ok!(Savings::join(RuntimeOrigin::signed(1), DebtType::Dusd, 1_000_000));
+}
+
+#[test]
+fn join_locks_stablecoin_in_the_pot() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		setup_savings();
+		
+		assert_eq!(pallet_stablecoin::Balances::<Test, Instance1>::get(1), 1_000_000);
+		assert_eq!(pallet_stablecoin::Balances::<Test, Instance1>::get(Savings::pot_account()), 1_000_000);
+		assert_eq!(Pie::<Test>::get(DebtType::Dusd, 1), 1_000_000);
+		assert_eq!(TotalPie::<Test>::get(DebtType::Dusd), 1_000_000);
+		assert_eq!(Savings::savings_of(&1).dusd, 1_000_000);
+		System::assert_last_event(Event::Joined { who: 1, currency: DebtType::Dusd, amount: 1_000_000 }.into());
+		
+		// The deposit is moved into the pot rather than burned and minted again
+		System::assert_has_event(
+			pallet_stablecoin::Event::<Test, Instance1>::Transfer {
+				from: 1,
+				to: Savings::pot_account(),
+				amount: 1_000_000,
+			}
+			.into(),
+		);
+		assert_eq!(pallet_stablecoin::TotalSupply::<Test, Instance1>::get(), 2_000_000);
+		
+		assert_noop!(Savings::join(RuntimeOrigin::signed(1), DebtType::Dusd, 0), Error::<Test>::ZeroAmount);
+		assert!(Savings::join(RuntimeOrigin::signed(1), DebtType::Dusd, 1_000_001).is_err());
+	});
+}
+
+#[test]
+fn savings_accrue_interest_funded_by_stability_fees() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		setup_savings();
+		
+		// A year at 10%, compounded every block, is worth about 10.517%
+		System::set_block_number(10_001);
+		let savings = Savings::savings_of(&1).dusd;
+		assert!((1_105_160..=1_105_180).contains(&savings), "{savings}");
+		assert_eq!(Chi::<Test>::get(DebtType::Dusd), FixedU128::from_u32(1));
+		
+		assert_ok!(Savings::drip_savings(RuntimeOrigin::signed(2), DebtType::Dusd));
+		let interest = savings - 1_000_000;
+		assert_eq!(ChiUpdatedAt::<Test>::get(DebtType::Dusd), Some(10_001));
+		assert_eq!(pallet_stablecoin::Balances::<Test, Instance1>::get(Savings::pot_account()), savings);
+		assert_eq!(SystemBadDebt::<Test>::get(DebtType::Dusd), interest);
+		assert_eq!(pallet_stablecoin::TotalSupply::<Test, Instance1>::get(), TotalDusdDebt::<Test>::get());
+		
+		// The fees on twice the debt cover the interest, which heals it
+		assert_ok!(CollateralEngine::accrue_stability_fees());
+		assert!(SystemSurplus::<Test>::get(DebtType::Dusd) > interest);
+		assert_ok!(SystemLedger::heal(RuntimeOrigin::signed(2), DebtType::Dusd));
+		assert_eq!(SystemBadDebt::<Test>::get(DebtType::Dusd), 0);
+		
+		assert_noop!(
+			Savings::exit(RuntimeOrigin::signed(1), DebtType::Dusd, savings + 1),
+			Error::<Test>::InsufficientSavings
+		);
+		assert_ok!(Savings::exit(RuntimeOrigin::signed(1), DebtType::Dusd, savings));
+		assert_eq!(pallet_stablecoin::Balances::<Test, Instance1>::get(1), 1_000_000 + savings);
+		assert_eq!(pallet_stablecoin::Balances::<Test, Instance1>::get(Savings::pot_account()), 0);
+		assert!(!Pie::<Test>::contains_key(DebtType::Dusd, 1));
+		assert_eq!(TotalPie::<Test>::get(DebtType::Dusd), 0);
+		System::assert_last_event(Event::Exited { who: 1, currency: DebtType::Dusd, amount: savings }.into());
+	});
+}
+
+#[test]
+fn partial_exit_keeps_the_rest_earning() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		setup_savings();
+		
+		System::set_block_number(5_001);
+		assert_ok!(Savings::exit(RuntimeOrigin::signed(1), DebtType::Dusd, 500_000));
+		let left = Savings::savings_of(&1).dusd;
+		assert!(left > 500_000);
+		
+		System::set_block_number(10_001);
+		assert!(Savings::savings_of(&1).dusd > left);
+		assert_ok!(Savings::exit(RuntimeOrigin::signed(1), DebtType::Dusd, Savings::savings_of(&1).dusd));
+		assert_eq!(TotalPie::<Test>::get(DebtType::Dusd), 0);
+	});
+}
+
+#[test]
+fn rate_changes_apply_from_the_current_block() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		setup_savings();
+		
+		assert_noop!(Savings::set_savings_rate(RuntimeOrigin::signed(1), DebtType::Dusd, 0), BadOrigin);
+		assert_noop!(
+			Savings::set_savings_rate(RuntimeOrigin::root(), DebtType::Dusd, 2001),
+			Error::<Test>::RateTooHigh
+		);
+		
+		// Interest up to block 5_001 is paid at 10%, and nothing accrues after the rate is cut
+		System::set_block_number(5_001);
+		assert_ok!(Savings::set_savings_rate(RuntimeOrigin::root(), DebtType::Dusd, 0));
+		let savings = Savings::savings_of(&1).dusd;
+		assert!(savings > 1_050_000);
+		assert_eq!(SavingsRates::<Test>::get(DebtType::Dusd), 0);
+		System::assert_last_event(Event::SavingsRateUpdated { currency: DebtType::Dusd, rate: 0 }.into());
+		
+		System::set_block_number(10_001);
+		assert_eq!(Savings::savings_of(&1).dusd, savings);
+	});
+}
+
+#[test]
+fn savings_stop_growing_after_shutdown() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		setup_savings();
+		
+		assert_ok!(CollateralEngine::cage(RuntimeOrigin::root()));
+		
+		System::set_block_number(10_001);
+		assert_eq!(Savings::savings_of(&1).dusd, 1_000_000);
+		assert_noop!(
+			Savings::join(RuntimeOrigin::signed(1), DebtType::Dusd, 1_000),
+			Error::<Test>::SystemCaged
+		);
+		
+		assert_ok!(Savings::exit(RuntimeOrigin::signed(1), DebtType::Dusd, 1_000_000));
+		assert_eq!(pallet_stablecoin::Balances::<Test, Instance1>::get(1), 2_000_000);
+	});
+}
@@ -0,0 +1,75 @@
+//! Weights for pallet_savings
+//!
+//! Estimated from the template benchmarks until the pallet is benchmarked on reference hardware.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_savings.
+pub trait WeightInfo {
+	fn join() -> Weight;
+	fn exit() -> Weight;
+	fn drip() -> Weight;
+	fn set_savings_rate() -> Weight;
+}
+
+/// Weights for pallet_savings using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: Savings Chi, ChiUpdatedAt, SavingsRates, TotalPie, Pie, CollateralEngine CagedAt, Dusd Balances (r:8 w:6)
+	fn join() -> Weight {
+		Weight::from_parts(34_000_000, 3_593)
+			.saturating_add(T::DbWeight::get().reads(8_u64))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+	}
+	/// Storage: Savings Chi, ChiUpdatedAt, SavingsRates, TotalPie, Pie, CollateralEngine CagedAt, Dusd Balances (r:8 w:6)
+	fn exit() -> Weight {
+		Weight::from_parts(34_000_000, 3_593)
+			.saturating_add(T::DbWeight::get().reads(8_u64))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+	}
+	/// Storage: Savings Chi, ChiUpdatedAt, SavingsRates, TotalPie, CollateralEngine CagedAt, TotalDusdDebt, Dusd Balances, System ledger SystemBadDebt (r:8 w:5)
+	fn drip() -> Weight {
+		Weight::from_parts(26_000_000, 3_593)
+			.saturating_add(T::DbWeight::get().reads(8_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	/// Storage: Savings SavingsRates and the storage of drip (r:9 w:6)
+	fn set_savings_rate() -> Weight {
+		Weight::from_parts(28_000_000, 3_593)
+			.saturating_add(T::DbWeight::get().reads(9_u64))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	/// Storage: Savings Chi, ChiUpdatedAt, SavingsRates, TotalPie, Pie, CollateralEngine CagedAt, Dusd Balances (r:8 w:6)
+	fn join() -> Weight {
+		Weight::from_parts(34_000_000, 3_593)
+			.saturating_add(RocksDbWeight::get().reads(8_u64))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
+	}
+	/// Storage: Savings Chi, ChiUpdatedAt, SavingsRates, TotalPie, Pie, CollateralEngine CagedAt, Dusd Balances (r:8 w:6)
+	fn exit() -> Weight {
+		Weight::from_parts(34_000_000, 3_593)
+			.saturating_add(RocksDbWeight::get().reads(8_u64))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
+	}
+	/// Storage: Savings Chi, ChiUpdatedAt, SavingsRates, TotalPie, CollateralEngine CagedAt, TotalDusdDebt, Dusd Balances, System ledger SystemBadDebt (r:8 w:5)
+	fn drip() -> Weight {
+		Weight::from_parts(26_000_000, 3_593)
+			.saturating_add(RocksDbWeight::get().reads(8_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+	/// Storage: Savings SavingsRates and the storage of drip (r:9 w:6)
+	fn set_savings_rate() -> Weight {
+		Weight::from_parts(28_000_000, 3_593)
+			.saturating_add(RocksDbWeight::get().reads(9_u64))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
+	}
+}
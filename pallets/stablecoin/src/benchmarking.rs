@@ -0,0 +1,85 @@
+//! Benchmarking setup for pallet-stablecoin, shared by every instance.
+
+use super::*;
+
+use alloc::{boxed::Box, vec};
+use frame_benchmarking::v2::*;
+use frame_support::traits::Get;
+use frame_system::RawOrigin;
+
+#[instance_benchmarks]
+mod benchmarks {
+	use super::*;
+
+	fn issuer<T: Config<I>, I: 'static>() -> T::RuntimeOrigin {
+		T::IssuerOrigin::try_successful_origin().expect("IssuerOrigin has no successful origin")
+	}
+
+	#[benchmark]
+	fn transfer() {
+		let caller: T::AccountId = whitelisted_caller();
+		let to: T::AccountId = account("to", 0, 0);
+		Pallet::<T, I>::mint_to(issuer::<T, I>(), &caller, 1_000u32.into()).unwrap();
+		#[extrinsic_call]
+		transfer(RawOrigin::Signed(caller.clone()), to.clone(), 1_000u32.into());
+
+		assert_eq!(Balances::<T, I>::get(&to), 1_000u32.into());
+	}
+
+	#[benchmark]
+	fn approve() {
+		let caller: T::AccountId = whitelisted_caller();
+		let spender: T::AccountId = account("spender", 0, 0);
+		#[extrinsic_call]
+		approve(RawOrigin::Signed(caller.clone()), spender.clone(), 1_000u32.into());
+
+		assert_eq!(Allowances::<T, I>::get(&caller, &spender), 1_000u32.into());
+	}
+
+	#[benchmark]
+	fn transfer_from() {
+		let owner: T::AccountId = account("owner", 0, 0);
+		let caller: T::AccountId = whitelisted_caller();
+		let to: T::AccountId = account("to", 0, 0);
+		Pallet::<T, I>::mint_to(issuer::<T, I>(), &owner, 1_000u32.into()).unwrap();
+		Allowances::<T, I>::insert(&owner, &caller, T::Balance::from(1_000u32));
+		#[extrinsic_call]
+		transfer_from(RawOrigin::Signed(caller), owner, to.clone(), 1_000u32.into());
+
+		assert_eq!(Balances::<T, I>::get(&to), 1_000u32.into());
+	}
+
+	#[benchmark]
+	fn flash_mint() {
+		let caller: T::AccountId = whitelisted_caller();
+		let amount = T::MaxFlashMint::get();
+		// Enough to pay any fee of up to 100%
+		Pallet::<T, I>::mint_to(issuer::<T, I>(), &caller, amount).unwrap();
+		let call: <T as Config<I>>::RuntimeCall = frame_system::Call::<T>::remark { remark: vec![] }.into();
+		#[extrinsic_call]
+		flash_mint(RawOrigin::Signed(caller), amount, Box::new(call));
+
+		assert_eq!(FlashMinted::<T, I>::get(), 0u32.into());
+	}
+
+	#[benchmark]
+	fn force_mint() {
+		let to: T::AccountId = account("to", 0, 0);
+		let origin = issuer::<T, I>();
+		#[extrinsic_call]
+		force_mint(origin as T::RuntimeOrigin, to.clone(), 1_000u32.into());
+
+		assert_eq!(Balances::<T, I>::get(&to), 1_000u32.into());
+	}
+
+	#[benchmark]
+	fn force_burn() {
+		let from: T::AccountId = account("from", 0, 0);
+		Pallet::<T, I>::mint_to(issuer::<T, I>(), &from, 1_000u32.into()).unwrap();
+		let origin = issuer::<T, I>();
+		#[extrinsic_call]
+		force_burn(origin as T::RuntimeOrigin, from.clone(), 1_000u32.into());
+
+		assert_eq!(Balances::<T, I>::get(&from), 0u32.into());
+	}
+}
@@ -0,0 +1,477 @@
+//! # Stablecoin Pallet
+//!
+//! The ORIUM stablecoins, one instance per currency: `Instance1` is dUSD and `Instance2` is
+//! dEUR.
+//!
+//! ## Overview
+//!
+//! Each instance keeps the balances, allowances and total supply of one stablecoin, and
+//! describes it with its `Symbol`, `Decimals` and `PegCurrency`. Holders `transfer` tokens and
+//! let others spend them with `approve` and `transfer_from`.
+//!
+//! Tokens are only issued and retired by `IssuerOrigin`. Approved modules, such as the
+//! collateral engine that keeps the supply in lockstep with the debt of its CDPs, call
+//! `mint_to` and `burn_from` with their own origin, and move tokens they hold or take in with
+//! `transfer_between`, which leaves the supply alone. Governance can `force_mint` and
+//! `force_burn`. Every change of the supply emits an event carrying the new total supply.
+//!
+//! `flash_mint` lends up to `MaxFlashMint` tokens for the length of a single call, which is
+//! dispatched from the borrower. The amount plus the `FlashMintFee` is burned from the borrower
+//! once the call returns, and everything the flash mint did is reverted otherwise. Tokens flash
+//! minted by the other instances, given by `OtherFlashMints`, count against the cap too, so
+//! that one cap bounds the stablecoins flash minted at once, counting one token of any of them
+//! as one.
+
+// We make sure this pallet uses `no_std` for compiling to Wasm.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+// Re-export pallet items so that they can be accessed from the crate namespace.
+pub use pallet::*;
+
+// FRAME pallets require their own "mock runtimes" to be able to run unit tests. This module
+// contains a mock runtime specific for testing this pallet's functionality.
+#[cfg(test)]
+mod mock;
+
+// This module contains the unit tests for this pallet.
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod weights;
+pub use weights::*;
+
+// Storage migrations between versions of this pallet.
+pub mod migrations;
+
+use frame_support::{dispatch::DispatchResult, traits::Get};
+
+/// Receives the fee burned on top of each flash mint.
+///
+/// The runtime decides where flash-mint revenue goes, e.g. by reissuing the fee to the
+/// collateral engine's surplus so the supply keeps matching the debt recorded there.
+pub trait OnFlashMintFee<Balance> {
+	/// `fee` has been burned from the borrower of a flash mint.
+	fn on_flash_mint_fee(fee: Balance) -> DispatchResult;
+}
+
+impl<Balance> OnFlashMintFee<Balance> for () {
+	fn on_flash_mint_fee(_: Balance) -> DispatchResult {
+		Ok(())
+	}
+}
+
+// All pallet logic is defined in its own module and must be annotated by the `pallet` attribute.
+#[frame_support::pallet]
+pub mod pallet {
+	// Import various useful types required by all FRAME pallets.
+	use super::*;
+	use alloc::boxed::Box;
+	use frame_support::{
+		dispatch::{GetDispatchInfo, PostDispatchInfo},
+		pallet_prelude::*,
+		storage::{with_transaction, TransactionOutcome},
+	};
+	use frame_system::pallet_prelude::*;
+	use sp_runtime::traits::{
+		AtLeast32BitUnsigned, Dispatchable, MaybeSerializeDeserialize, Member, Saturating, Zero,
+	};
+
+	/// Version 1 is the first version of the instantiable pallet.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
+	// The `Pallet` struct serves as a placeholder to implement traits, methods and dispatchables
+	// (`Call`s) in this pallet.
+	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
+	pub struct Pallet<T, I = ()>(_);
+
+	/// The pallet's configuration trait.
+	#[pallet::config]
+	pub trait Config<I: 'static = ()>: frame_system::Config {
+		/// The overarching runtime event type.
+		#[allow(deprecated)]
+		type RuntimeEvent: From<Event<Self, I>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		/// A type representing the weights required by the dispatchables of this pallet.
+		type WeightInfo: WeightInfo;
+		type Balance: Parameter + Member + AtLeast32BitUnsigned + Default + Copy + MaybeSerializeDeserialize + MaxEncodedLen;
+		/// The ticker of the stablecoin, e.g. `dUSD`.
+		#[pallet::constant]
+		type Symbol: Get<&'static str>;
+		/// The number of decimals balances are denominated in.
+		#[pallet::constant]
+		type Decimals: Get<u8>;
+		/// The ISO 4217 code of the currency the stablecoin is pegged to, e.g. `USD`.
+		#[pallet::constant]
+		type PegCurrency: Get<&'static str>;
+		/// The calls a flash mint may dispatch with the borrowed tokens.
+		type RuntimeCall: Parameter
+			+ Dispatchable<RuntimeOrigin = Self::RuntimeOrigin, PostInfo = PostDispatchInfo>
+			+ GetDispatchInfo
+			+ From<frame_system::Call<Self>>;
+		/// The most tokens that may be flash minted at once, across nested flash mints and the
+		/// flash mints of other instances.
+		#[pallet::constant]
+		type MaxFlashMint: Get<Self::Balance>;
+		/// Tokens flash minted by other instances at the moment, e.g. [`FlashMintedOf`].
+		type OtherFlashMints: Get<Self::Balance>;
+		/// Fee burned on top of each flash mint, in basis points.
+		#[pallet::constant]
+		type FlashMintFee: Get<u32>;
+		/// Receives the flash mint fees.
+		type OnFlashMintFee: OnFlashMintFee<Self::Balance>;
+		/// The origin allowed to mint and burn tokens, both for the modules issuing them and for
+		/// `force_mint` and `force_burn`.
+		type IssuerOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+	}
+
+	#[pallet::storage]
+	pub type TotalSupply<T: Config<I>, I: 'static = ()> = StorageValue<_, T::Balance, ValueQuery>;
+
+	#[pallet::storage]
+	pub type Balances<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, T::Balance, ValueQuery>;
+
+	#[pallet::storage]
+	pub type Allowances<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId, // owner
+		Blake2_128Concat,
+		T::AccountId, // spender
+		T::Balance,
+		ValueQuery,
+	>;
+
+	/// Tokens flash minted by the flash mints currently being dispatched.
+	#[pallet::storage]
+	pub type FlashMinted<T: Config<I>, I: 'static = ()> = StorageValue<_, T::Balance, ValueQuery>;
+
+	/// Events that functions in this pallet can emit.
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config<I>, I: 'static = ()> {
+		Transfer {
+			from: T::AccountId,
+			to: T::AccountId,
+			amount: T::Balance,
+		},
+		Mint {
+			to: T::AccountId,
+			amount: T::Balance,
+			total_supply: T::Balance,
+		},
+		Burn {
+			from: T::AccountId,
+			amount: T::Balance,
+			total_supply: T::Balance,
+		},
+		Approval {
+			owner: T::AccountId,
+			spender: T::AccountId,
+			amount: T::Balance,
+		},
+		FlashMint {
+			who: T::AccountId,
+			amount: T::Balance,
+			fee: T::Balance,
+			total_supply: T::Balance,
+		},
+		/// `IssuerOrigin` minted tokens through `force_mint`.
+		ForceMint {
+			to: T::AccountId,
+			amount: T::Balance,
+			total_supply: T::Balance,
+		},
+		/// `IssuerOrigin` burned tokens through `force_burn`.
+		ForceBurn {
+			from: T::AccountId,
+			amount: T::Balance,
+			total_supply: T::Balance,
+		},
+	}
+
+	/// Errors that can be returned by this pallet.
+	#[pallet::error]
+	pub enum Error<T, I = ()> {
+		InsufficientBalance,
+		InsufficientAllowance,
+		Overflow,
+		SelfTransfer,
+		/// The caller of `mint_to`, `burn_from` or `transfer_between` is not `IssuerOrigin`.
+		NotAuthorized,
+		ZeroAmount,
+		/// The flash mint would take the tokens flash minted at once, by this instance and the
+		/// others, over `MaxFlashMint`.
+		FlashMintCapExceeded,
+		/// The borrower did not hold the flash-minted amount plus the fee once the call was
+		/// dispatched.
+		FlashMintNotRepaid,
+	}
+
+	/// The pallet's dispatchable functions for stablecoin token operations.
+	#[pallet::call]
+	impl<T: Config<I>, I: 'static> Pallet<T, I> {
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::transfer())]
+		pub fn transfer(
+			origin: OriginFor<T>,
+			to: T::AccountId,
+			amount: T::Balance,
+		) -> DispatchResult {
+			let from = ensure_signed(origin)?;
+			
+			ensure!(from != to, Error::<T, I>::SelfTransfer);
+			
+			let from_balance = Balances::<T, I>::get(&from);
+			ensure!(from_balance >= amount, Error::<T, I>::InsufficientBalance);
+			
+			// Update balances
+			Balances::<T, I>::mutate(&from, |balance| *balance = balance.saturating_sub(amount));
+			Balances::<T, I>::mutate(&to, |balance| *balance = balance.saturating_add(amount));
+			
+			Self::deposit_event(Event::Transfer { from, to, amount });
+			
+			Ok(())
+		}
+
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::approve())]
+		pub fn approve(
+			origin: OriginFor<T>,
+			spender: T::AccountId,
+			amount: T::Balance,
+		) -> DispatchResult {
+			let owner = ensure_signed(origin)?;
+			
+			Allowances::<T, I>::insert(&owner, &spender, amount);
+			
+			Self::deposit_event(Event::Approval { owner, spender, amount });
+			
+			Ok(())
+		}
+
+		#[pallet::call_index(2)]
+		#[pallet::weight(T::WeightInfo::transfer_from())]
+		pub fn transfer_from(
+			origin: OriginFor<T>,
+			from: T::AccountId,
+			to: T::AccountId,
+			amount: T::Balance,
+		) -> DispatchResult {
+			let spender = ensure_signed(origin)?;
+			
+			ensure!(from != to, Error::<T, I>::SelfTransfer);
+			
+			let allowance = Allowances::<T, I>::get(&from, &spender);
+			ensure!(allowance >= amount, Error::<T, I>::InsufficientAllowance);
+			
+			let from_balance = Balances::<T, I>::get(&from);
+			ensure!(from_balance >= amount, Error::<T, I>::InsufficientBalance);
+			
+			// Update balances and allowance
+			Balances::<T, I>::mutate(&from, |balance| *balance = balance.saturating_sub(amount));
+			Balances::<T, I>::mutate(&to, |balance| *balance = balance.saturating_add(amount));
+			Allowances::<T, I>::mutate(&from, &spender, |allowance| *allowance = allowance.saturating_sub(amount));
+			
+			Self::deposit_event(Event::Transfer { from, to, amount });
+			
+			Ok(())
+		}
+
+		/// Mint `amount` to the caller, dispatch `call` from the caller, then burn `amount` plus
+		/// the `FlashMintFee` from the caller.
+		///
+		/// Everything the flash mint did is reverted unless the caller can pay it back.
+		#[pallet::call_index(3)]
+		#[pallet::weight({
+			let info = call.get_dispatch_info();
+			(T::WeightInfo::flash_mint().saturating_add(info.call_weight), info.class)
+		})]
+		pub fn flash_mint(
+			origin: OriginFor<T>,
+			amount: T::Balance,
+			call: Box<<T as Config<I>>::RuntimeCall>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			
+			ensure!(!amount.is_zero(), Error::<T, I>::ZeroAmount);
+			
+			let outstanding = FlashMinted::<T, I>::get().saturating_add(amount);
+			ensure!(
+				outstanding.saturating_add(T::OtherFlashMints::get()) <= T::MaxFlashMint::get(),
+				Error::<T, I>::FlashMintCapExceeded
+			);
+			
+			let fee = amount.saturating_mul(T::FlashMintFee::get().into()) / 10000u32.into();
+			
+			with_transaction(|| {
+				let result = Self::do_flash_mint(&who, amount, fee, outstanding, *call);
+				if result.is_ok() {
+					TransactionOutcome::Commit(result)
+				} else {
+					TransactionOutcome::Rollback(result)
+				}
+			})
+		}
+
+		/// Mint `amount` to `to` outside of the modules issuing tokens.
+		#[pallet::call_index(4)]
+		#[pallet::weight(T::WeightInfo::force_mint())]
+		pub fn force_mint(
+			origin: OriginFor<T>,
+			to: T::AccountId,
+			amount: T::Balance,
+		) -> DispatchResult {
+			T::IssuerOrigin::ensure_origin(origin)?;
+			
+			ensure!(!amount.is_zero(), Error::<T, I>::ZeroAmount);
+			
+			Self::credit(&to, amount);
+			
+			Self::deposit_event(Event::ForceMint { to, amount, total_supply: TotalSupply::<T, I>::get() });
+			
+			Ok(())
+		}
+
+		/// Burn `amount` held by `from` outside of the modules issuing tokens.
+		#[pallet::call_index(5)]
+		#[pallet::weight(T::WeightInfo::force_burn())]
+		pub fn force_burn(
+			origin: OriginFor<T>,
+			from: T::AccountId,
+			amount: T::Balance,
+		) -> DispatchResult {
+			T::IssuerOrigin::ensure_origin(origin)?;
+			
+			ensure!(!amount.is_zero(), Error::<T, I>::ZeroAmount);
+			
+			Self::debit(&from, amount)?;
+			
+			Self::deposit_event(Event::ForceBurn { from, amount, total_supply: TotalSupply::<T, I>::get() });
+			
+			Ok(())
+		}
+	}
+
+	impl<T: Config<I>, I: 'static> Pallet<T, I> {
+		fn do_flash_mint(
+			who: &T::AccountId,
+			amount: T::Balance,
+			fee: T::Balance,
+			outstanding: T::Balance,
+			call: <T as Config<I>>::RuntimeCall,
+		) -> DispatchResult {
+			FlashMinted::<T, I>::put(outstanding);
+			Self::credit(who, amount);
+			
+			call.dispatch(frame_system::RawOrigin::Signed(who.clone()).into())
+				.map_err(|e| e.error)?;
+			
+			Self::debit(who, amount.saturating_add(fee)).map_err(|_| Error::<T, I>::FlashMintNotRepaid)?;
+			T::OnFlashMintFee::on_flash_mint_fee(fee)?;
+			FlashMinted::<T, I>::mutate(|minted| *minted = minted.saturating_sub(amount));
+			
+			Self::deposit_event(Event::FlashMint {
+				who: who.clone(),
+				amount,
+				fee,
+				total_supply: TotalSupply::<T, I>::get(),
+			});
+			
+			Ok(())
+		}
+
+		/// Mint `amount` to `to` on behalf of `issuer`, which must be `IssuerOrigin`.
+		pub fn mint_to(issuer: OriginFor<T>, to: &T::AccountId, amount: T::Balance) -> DispatchResult {
+			T::IssuerOrigin::ensure_origin(issuer).map_err(|_| Error::<T, I>::NotAuthorized)?;
+			
+			Self::credit(to, amount);
+			
+			Self::deposit_event(Event::Mint { to: to.clone(), amount, total_supply: TotalSupply::<T, I>::get() });
+			
+			Ok(())
+		}
+
+		/// Burn `amount` held by `from` on behalf of `issuer`, which must be `IssuerOrigin`.
+		pub fn burn_from(issuer: OriginFor<T>, from: &T::AccountId, amount: T::Balance) -> DispatchResult {
+			T::IssuerOrigin::ensure_origin(issuer).map_err(|_| Error::<T, I>::NotAuthorized)?;
+			
+			Self::debit(from, amount)?;
+			
+			Self::deposit_event(Event::Burn { from: from.clone(), amount, total_supply: TotalSupply::<T, I>::get() });
+			
+			Ok(())
+		}
+
+		/// Move `amount` from `from` to `to` on behalf of `issuer`, which must be `IssuerOrigin`.
+		pub fn transfer_between(
+			issuer: OriginFor<T>,
+			from: &T::AccountId,
+			to: &T::AccountId,
+			amount: T::Balance,
+		) -> DispatchResult {
+			T::IssuerOrigin::ensure_origin(issuer).map_err(|_| Error::<T, I>::NotAuthorized)?;
+			
+			let from_balance = Balances::<T, I>::get(from);
+			ensure!(from_balance >= amount, Error::<T, I>::InsufficientBalance);
+			
+			Balances::<T, I>::mutate(from, |balance| *balance = balance.saturating_sub(amount));
+			Balances::<T, I>::mutate(to, |balance| *balance = balance.saturating_add(amount));
+			
+			Self::deposit_event(Event::Transfer { from: from.clone(), to: to.clone(), amount });
+			
+			Ok(())
+		}
+
+		fn credit(to: &T::AccountId, amount: T::Balance) {
+			// Update balance and total supply
+			Balances::<T, I>::mutate(to, |balance| *balance = balance.saturating_add(amount));
+			TotalSupply::<T, I>::mutate(|supply| *supply = supply.saturating_add(amount));
+		}
+
+		fn debit(from: &T::AccountId, amount: T::Balance) -> DispatchResult {
+			let balance = Balances::<T, I>::get(from);
+			ensure!(balance >= amount, Error::<T, I>::InsufficientBalance);
+			
+			// Update balance and total supply
+			Balances::<T, I>::mutate(from, |balance| *balance = balance.saturating_sub(amount));
+			TotalSupply::<T, I>::mutate(|supply| *supply = supply.saturating_sub(amount));
+			
+			Ok(())
+		}
+
+		pub fn balance_of(account: &T::AccountId) -> T::Balance {
+			Balances::<T, I>::get(account)
+		}
+
+		pub fn total_supply() -> T::Balance {
+			TotalSupply::<T, I>::get()
+		}
+
+		pub fn symbol() -> &'static str {
+			T::Symbol::get()
+		}
+
+		pub fn decimals() -> u8 {
+			T::Decimals::get()
+		}
+
+		pub fn peg_currency() -> &'static str {
+			T::PegCurrency::get()
+		}
+	}
+}
+
+/// The tokens instance `I` has flash minted at the moment, for the `OtherFlashMints` of the
+/// other instances.
+pub struct FlashMintedOf<T, I>(core::marker::PhantomData<(T, I)>);
+
+impl<T: Config<I>, I: 'static> Get<T::Balance> for FlashMintedOf<T, I> {
+	fn get() -> T::Balance {
+		FlashMinted::<T, I>::get()
+	}
+}
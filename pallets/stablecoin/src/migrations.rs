@@ -0,0 +1,58 @@
+use super::*;
+use frame_support::{migrations::VersionedMigration, pallet_prelude::*, traits::UncheckedOnRuntimeUpgrade};
+#[cfg(feature = "try-runtime")]
+use alloc::vec::Vec;
+
+/// Version 1 replaces `pallet-dusd` and `pallet-deur` with the instances of this pallet.
+///
+/// Storage is prefixed with the name of the pallet in the runtime, and the instances keep the
+/// names `Dusd` and `Deur` at pallet indices 10 and 11. The storage items keep their names and
+/// hashers, so the balances, allowances and total supply held by the old pallets are read as is,
+/// and the migration only records the new storage version.
+pub mod v1 {
+	use super::*;
+
+	/// Checks the balances of an instance survive the switch to this pallet.
+	pub struct InnerMigrateV0ToV1<T, I>(core::marker::PhantomData<(T, I)>);
+
+	impl<T: Config<I>, I: 'static> UncheckedOnRuntimeUpgrade for InnerMigrateV0ToV1<T, I> {
+		fn on_runtime_upgrade() -> Weight {
+			// Nothing moves; `VersionedMigration` writes the storage version
+			Weight::zero()
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, sp_runtime::TryRuntimeError> {
+			Ok((TotalSupply::<T, I>::get(), Self::sum_of_balances()).encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), sp_runtime::TryRuntimeError> {
+			let (supply, balances) = <(T::Balance, T::Balance)>::decode(&mut &state[..])
+				.map_err(|_| "invalid pre-upgrade state")?;
+			
+			ensure!(TotalSupply::<T, I>::get() == supply, "the total supply changed in the migration");
+			ensure!(Self::sum_of_balances() == balances, "balances were lost in the migration");
+			
+			Ok(())
+		}
+	}
+
+	impl<T: Config<I>, I: 'static> InnerMigrateV0ToV1<T, I> {
+		#[cfg(feature = "try-runtime")]
+		fn sum_of_balances() -> T::Balance {
+			use sp_runtime::traits::{Saturating, Zero};
+			
+			Balances::<T, I>::iter_values().fold(Zero::zero(), |sum, balance| sum.saturating_add(balance))
+		}
+	}
+
+	/// Moves an instance to version 1, only if the on-chain storage version is 0.
+	pub type MigrateV0ToV1<T, I> = VersionedMigration<
+		0,
+		1,
+		InnerMigrateV0ToV1<T, I>,
+		Pallet<T, I>,
+		<T as frame_system::Config>::DbWeight,
+	>;
+}
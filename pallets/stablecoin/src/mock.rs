@@ -0,0 +1,86 @@
+use crate as pallet_stablecoin;
+use frame_support::{
+	derive_impl, parameter_types,
+	instances::{Instance1, Instance2},
+	traits::{ConstU128, ConstU32, ConstU8},
+};
+use frame_system::EnsureRoot;
+use sp_runtime::BuildStorage;
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+#[frame_support::runtime]
+mod runtime {
+	// The main runtime
+	#[runtime::runtime]
+	// Runtime Types to be generated
+	#[runtime::derive(
+		RuntimeCall,
+		RuntimeEvent,
+		RuntimeError,
+		RuntimeOrigin,
+		RuntimeFreezeReason,
+		RuntimeHoldReason,
+		RuntimeSlashReason,
+		RuntimeLockId,
+		RuntimeTask,
+		RuntimeViewFunction
+	)]
+	pub struct Test;
+
+	#[runtime::pallet_index(0)]
+	pub type System = frame_system::Pallet<Test>;
+
+	#[runtime::pallet_index(1)]
+	pub type Dusd = pallet_stablecoin::Pallet<Test, Instance1>;
+
+	#[runtime::pallet_index(2)]
+	pub type Deur = pallet_stablecoin::Pallet<Test, Instance2>;
+}
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+	type Block = Block;
+}
+
+parameter_types! {
+	pub const DusdSymbol: &'static str = "dUSD";
+	pub const DeurSymbol: &'static str = "dEUR";
+	pub const Usd: &'static str = "USD";
+	pub const Eur: &'static str = "EUR";
+}
+
+impl pallet_stablecoin::Config<Instance1> for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = ();
+	type Balance = u128;
+	type Symbol = DusdSymbol;
+	type Decimals = ConstU8<12>;
+	type PegCurrency = Usd;
+	type RuntimeCall = RuntimeCall;
+	type MaxFlashMint = ConstU128<1_000_000>;
+	type OtherFlashMints = pallet_stablecoin::FlashMintedOf<Test, Instance2>;
+	type FlashMintFee = ConstU32<9>; // 0.09%
+	type OnFlashMintFee = ();
+	type IssuerOrigin = EnsureRoot<u64>;
+}
+
+impl pallet_stablecoin::Config<Instance2> for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = ();
+	type Balance = u128;
+	type Symbol = DeurSymbol;
+	type Decimals = ConstU8<12>;
+	type PegCurrency = Eur;
+	type RuntimeCall = RuntimeCall;
+	type MaxFlashMint = ConstU128<500_000>;
+	type OtherFlashMints = pallet_stablecoin::FlashMintedOf<Test, Instance1>;
+	type FlashMintFee = ConstU32<0>;
+	type OnFlashMintFee = ();
+	type IssuerOrigin = EnsureRoot<u64>;
+}
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	frame_system::GenesisConfig::<Test>::default().build_storage().unwrap().into()
+}
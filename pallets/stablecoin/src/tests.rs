@@ -0,0 +1,249 @@
+use crate::{mock::*, Allowances, Balances, Error, Event, FlashMinted, TotalSupply};
+use frame_support::{
+	assert_noop, assert_ok,
+	instances::{Instance1, Instance2},
+};
+use sp_runtime::traits::BadOrigin;
+
+type DusdBalances = Balances<Test, Instance1>;
+type DusdError = Error<Test, Instance1>;
+type DusdEvent = Event<Test, Instance1>;
+
+fn remark() -> Box<RuntimeCall> {
+	Box::new(RuntimeCall::System(frame_system::Call::remark { remark: vec![] }))
+}
+
+fn transfer(to: u64, amount: u128) -> Box<RuntimeCall> {
+	Box::new(RuntimeCall::Dusd(crate::Call::<Test, Instance1>::transfer { to, amount }))
+}
+
+#[test]
+fn transfer_works() {
+	new_test_ext().execute_with(|| {
+		// Go past genesis block so events get deposited
+		System::set_block_number(1);
+		assert_ok!(Dusd::mint_to(RuntimeOrigin::root(), &1, 1_000));
+		
+		assert_ok!(Dusd::transfer(RuntimeOrigin::signed(1), 2, 400));
+		
+		assert_eq!(DusdBalances::get(1), 600);
+		assert_eq!(DusdBalances::get(2), 400);
+		assert_eq!(TotalSupply::<Test, Instance1>::get(), 1_000);
+		System::assert_last_event(DusdEvent::Transfer { from: 1, to: 2, amount: 400 }.into());
+		
+		assert_noop!(Dusd::transfer(RuntimeOrigin::signed(1), 2, 601), DusdError::InsufficientBalance);
+		assert_noop!(Dusd::transfer(RuntimeOrigin::signed(1), 1, 100), DusdError::SelfTransfer);
+	});
+}
+
+#[test]
+fn transfer_from_spends_the_allowance() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Dusd::mint_to(RuntimeOrigin::root(), &1, 1_000));
+		assert_ok!(Dusd::approve(RuntimeOrigin::signed(1), 2, 300));
+		
+		assert_ok!(Dusd::transfer_from(RuntimeOrigin::signed(2), 1, 3, 200));
+		
+		assert_eq!(DusdBalances::get(1), 800);
+		assert_eq!(DusdBalances::get(3), 200);
+		assert_eq!(Allowances::<Test, Instance1>::get(1, 2), 100);
+		assert_noop!(
+			Dusd::transfer_from(RuntimeOrigin::signed(2), 1, 3, 101),
+			DusdError::InsufficientAllowance
+		);
+	});
+}
+
+#[test]
+fn instances_are_separate_stablecoins() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Dusd::mint_to(RuntimeOrigin::root(), &1, 1_000));
+		assert_ok!(Deur::mint_to(RuntimeOrigin::root(), &1, 500));
+		
+		assert_ok!(Deur::transfer(RuntimeOrigin::signed(1), 2, 500));
+		
+		assert_eq!(Dusd::balance_of(&1), 1_000);
+		assert_eq!(Deur::balance_of(&1), 0);
+		assert_eq!(Deur::balance_of(&2), 500);
+		assert_eq!(Dusd::total_supply(), 1_000);
+		assert_eq!(Deur::total_supply(), 500);
+		System::assert_last_event(Event::<Test, Instance2>::Transfer { from: 1, to: 2, amount: 500 }.into());
+		
+		assert_eq!((Dusd::symbol(), Dusd::decimals(), Dusd::peg_currency()), ("dUSD", 12, "USD"));
+		assert_eq!((Deur::symbol(), Deur::decimals(), Deur::peg_currency()), ("dEUR", 12, "EUR"));
+	});
+}
+
+#[test]
+fn flash_mint_burns_the_amount_plus_the_fee() {
+	new_test_ext().execute_with(|| {
+		// Go past genesis block so events get deposited
+		System::set_block_number(1);
+		assert_ok!(Dusd::mint_to(RuntimeOrigin::root(), &1, 1_000));
+		
+		assert_ok!(Dusd::flash_mint(RuntimeOrigin::signed(1), 100_000, remark()));
+		
+		assert_eq!(DusdBalances::get(1), 910);
+		assert_eq!(TotalSupply::<Test, Instance1>::get(), 910);
+		assert_eq!(FlashMinted::<Test, Instance1>::get(), 0);
+		System::assert_last_event(DusdEvent::FlashMint { who: 1, amount: 100_000, fee: 90, total_supply: 910 }.into());
+	});
+}
+
+#[test]
+fn flash_mint_call_can_raise_the_fee() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Dusd::mint_to(RuntimeOrigin::root(), &2, 1_000));
+		assert_ok!(Dusd::approve(RuntimeOrigin::signed(2), 1, 90));
+		
+		// Account 1 holds no dUSD and pulls the fee from account 2 within the call
+		let call = RuntimeCall::Dusd(crate::Call::<Test, Instance1>::transfer_from { from: 2, to: 1, amount: 90 });
+		assert_ok!(Dusd::flash_mint(RuntimeOrigin::signed(1), 100_000, Box::new(call)));
+		
+		assert_eq!(DusdBalances::get(1), 0);
+		assert_eq!(DusdBalances::get(2), 910);
+		assert_eq!(TotalSupply::<Test, Instance1>::get(), 910);
+	});
+}
+
+#[test]
+fn flash_mint_reverts_unless_repaid() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Dusd::mint_to(RuntimeOrigin::root(), &1, 1_000));
+		
+		// The borrowed dUSD leaves the account
+		assert_noop!(
+			Dusd::flash_mint(RuntimeOrigin::signed(1), 100_000, transfer(2, 100_000)),
+			DusdError::FlashMintNotRepaid
+		);
+		// The fee cannot be paid
+		assert_noop!(
+			Dusd::flash_mint(RuntimeOrigin::signed(2), 100_000, remark()),
+			DusdError::FlashMintNotRepaid
+		);
+		// The call fails
+		assert_noop!(
+			Dusd::flash_mint(RuntimeOrigin::signed(1), 100_000, transfer(1, 100_000)),
+			DusdError::SelfTransfer
+		);
+		assert_noop!(Dusd::flash_mint(RuntimeOrigin::signed(1), 0, remark()), DusdError::ZeroAmount);
+	});
+}
+
+#[test]
+fn flash_mints_are_capped() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Dusd::mint_to(RuntimeOrigin::root(), &1, 1_000));
+		
+		assert_noop!(
+			Dusd::flash_mint(RuntimeOrigin::signed(1), 1_000_001, remark()),
+			DusdError::FlashMintCapExceeded
+		);
+		
+		// Nested flash mints count towards the same cap
+		let nested = Box::new(RuntimeCall::Dusd(crate::Call::<Test, Instance1>::flash_mint {
+			amount: 500_000,
+			call: remark(),
+		}));
+		assert_noop!(
+			Dusd::flash_mint(RuntimeOrigin::signed(1), 500_001, nested.clone()),
+			DusdError::FlashMintCapExceeded
+		);
+		assert_ok!(Dusd::flash_mint(RuntimeOrigin::signed(1), 500_000, nested));
+		assert_eq!(DusdBalances::get(1), 1_000 - 450 - 450);
+		assert_eq!(FlashMinted::<Test, Instance1>::get(), 0);
+	});
+}
+
+#[test]
+fn flash_mint_terms_are_per_instance() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		
+		// dEUR charges no fee, so nothing needs to be held to flash mint it
+		assert_ok!(Deur::flash_mint(RuntimeOrigin::signed(1), 500_000, remark()));
+		assert_eq!(Deur::total_supply(), 0);
+		assert_noop!(
+			Deur::flash_mint(RuntimeOrigin::signed(1), 500_001, remark()),
+			Error::<Test, Instance2>::FlashMintCapExceeded
+		);
+	});
+}
+
+#[test]
+fn flash_mints_of_every_instance_share_the_cap() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Dusd::mint_to(RuntimeOrigin::root(), &1, 1_000));
+		
+		// dUSD flash minted by the outer call leaves 100_000 of the 500_000 dEUR cap
+		let nested = |amount| {
+			let call = crate::Call::<Test, Instance2>::flash_mint { amount, call: remark() };
+			Box::new(RuntimeCall::Deur(call))
+		};
+		assert_noop!(
+			Dusd::flash_mint(RuntimeOrigin::signed(1), 400_000, nested(100_001)),
+			Error::<Test, Instance2>::FlashMintCapExceeded
+		);
+		assert_ok!(Dusd::flash_mint(RuntimeOrigin::signed(1), 400_000, nested(100_000)));
+		assert_eq!(FlashMinted::<Test, Instance1>::get(), 0);
+		assert_eq!(FlashMinted::<Test, Instance2>::get(), 0);
+	});
+}
+
+#[test]
+fn only_the_issuer_mints_and_burns() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		
+		assert_noop!(Dusd::mint_to(RuntimeOrigin::signed(1), &1, 1_000), DusdError::NotAuthorized);
+		assert_ok!(Dusd::mint_to(RuntimeOrigin::root(), &1, 1_000));
+		System::assert_last_event(DusdEvent::Mint { to: 1, amount: 1_000, total_supply: 1_000 }.into());
+		
+		assert_noop!(Dusd::burn_from(RuntimeOrigin::signed(1), &1, 400), DusdError::NotAuthorized);
+		assert_ok!(Dusd::burn_from(RuntimeOrigin::root(), &1, 400));
+		assert_eq!(DusdBalances::get(1), 600);
+		System::assert_last_event(DusdEvent::Burn { from: 1, amount: 400, total_supply: 600 }.into());
+	});
+}
+
+#[test]
+fn issuer_transfers_leave_the_supply_alone() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		
+		assert_ok!(Dusd::mint_to(RuntimeOrigin::root(), &1, 1_000));
+		
+		assert_noop!(Dusd::transfer_between(RuntimeOrigin::signed(1), &1, &2, 400), DusdError::NotAuthorized);
+		assert_noop!(Dusd::transfer_between(RuntimeOrigin::root(), &1, &2, 1_001), DusdError::InsufficientBalance);
+		assert_ok!(Dusd::transfer_between(RuntimeOrigin::root(), &1, &2, 400));
+		assert_eq!(DusdBalances::get(1), 600);
+		assert_eq!(DusdBalances::get(2), 400);
+		assert_eq!(TotalSupply::<Test, Instance1>::get(), 1_000);
+		System::assert_last_event(DusdEvent::Transfer { from: 1, to: 2, amount: 400 }.into());
+	});
+}
+
+#[test]
+fn force_mint_and_force_burn_work() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		
+		assert_noop!(Dusd::force_mint(RuntimeOrigin::signed(1), 1, 1_000), BadOrigin);
+		assert_noop!(Dusd::force_mint(RuntimeOrigin::root(), 1, 0), DusdError::ZeroAmount);
+		assert_ok!(Dusd::force_mint(RuntimeOrigin::root(), 1, 1_000));
+		assert_eq!(DusdBalances::get(1), 1_000);
+		System::assert_last_event(DusdEvent::ForceMint { to: 1, amount: 1_000, total_supply: 1_000 }.into());
+		
+		assert_noop!(Dusd::force_burn(RuntimeOrigin::signed(1), 1, 400), BadOrigin);
+		assert_noop!(Dusd::force_burn(RuntimeOrigin::root(), 1, 1_001), DusdError::InsufficientBalance);
+		assert_ok!(Dusd::force_burn(RuntimeOrigin::root(), 1, 400));
+		assert_eq!(TotalSupply::<Test, Instance1>::get(), 600);
+		System::assert_last_event(DusdEvent::ForceBurn { from: 1, amount: 400, total_supply: 600 }.into());
+	});
+}
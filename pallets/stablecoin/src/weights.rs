@@ -0,0 +1,99 @@
+//! Weights for pallet_stablecoin
+//!
+//! Estimated from the template benchmarks until the pallet is benchmarked on reference hardware.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_stablecoin.
+pub trait WeightInfo {
+	fn transfer() -> Weight;
+	fn approve() -> Weight;
+	fn transfer_from() -> Weight;
+	fn flash_mint() -> Weight;
+	fn force_mint() -> Weight;
+	fn force_burn() -> Weight;
+}
+
+/// Weights for pallet_stablecoin using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: Stablecoin Balances (r:2 w:2)
+	fn transfer() -> Weight {
+		Weight::from_parts(18_000_000, 5_078)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: Stablecoin Allowances (r:0 w:1)
+	fn approve() -> Weight {
+		Weight::from_parts(11_000_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: Stablecoin Allowances, Balances (r:3 w:3)
+	fn transfer_from() -> Weight {
+		Weight::from_parts(24_000_000, 5_078)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: Stablecoin FlashMinted, Balances, TotalSupply, FlashMinted of the other instances (r:6 w:3)
+	fn flash_mint() -> Weight {
+		Weight::from_parts(25_000_000, 3_529)
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: Stablecoin Balances, TotalSupply (r:2 w:2)
+	fn force_mint() -> Weight {
+		Weight::from_parts(15_000_000, 3_529)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: Stablecoin Balances, TotalSupply (r:2 w:2)
+	fn force_burn() -> Weight {
+		Weight::from_parts(16_000_000, 3_529)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	/// Storage: Stablecoin Balances (r:2 w:2)
+	fn transfer() -> Weight {
+		Weight::from_parts(18_000_000, 5_078)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: Stablecoin Allowances (r:0 w:1)
+	fn approve() -> Weight {
+		Weight::from_parts(11_000_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: Stablecoin Allowances, Balances (r:3 w:3)
+	fn transfer_from() -> Weight {
+		Weight::from_parts(24_000_000, 5_078)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	/// Storage: Stablecoin FlashMinted, Balances, TotalSupply, FlashMinted of the other instances (r:6 w:3)
+	fn flash_mint() -> Weight {
+		Weight::from_parts(25_000_000, 3_529)
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	/// Storage: Stablecoin Balances, TotalSupply (r:2 w:2)
+	fn force_mint() -> Weight {
+		Weight::from_parts(15_000_000, 3_529)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: Stablecoin Balances, TotalSupply (r:2 w:2)
+	fn force_burn() -> Weight {
+		Weight::from_parts(16_000_000, 3_529)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+}
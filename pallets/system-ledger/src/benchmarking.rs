@@ -0,0 +1,103 @@
+//! Benchmarking setup for pallet-system-ledger
+
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as SystemLedger;
+use frame_benchmarking::v2::*;
+use frame_support::traits::Currency;
+use frame_system::RawOrigin;
+use pallet_collateral_engine::{DebtType, StablecoinMinter, SystemLedger as _};
+use sp_runtime::traits::Zero;
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	fn parameters<T: Config>() -> LedgerParameters<BalanceOf<T>> {
+		LedgerParameters {
+			surplus_buffer: 0u32.into(),
+			surplus_lot: 100u32.into(),
+			debt_lot: 100u32.into(),
+			debt_auction_initial_orm: 1_000u32.into(),
+			min_bid_increment: 500,
+			bid_duration: 10,
+			auction_duration: 100,
+		}
+	}
+
+	/// Gives `who` an ORM account, so that it can receive the ORM of a debt auction.
+	fn fund<T: Config>(who: &T::AccountId) {
+		let minimum = <T as pallet_collateral_engine::Config>::Currency::minimum_balance();
+		let _ = <T as pallet_collateral_engine::Config>::Currency::deposit_creating(who, minimum);
+	}
+
+	#[benchmark]
+	fn heal() {
+		let surplus = pallet_collateral_engine::Pallet::<T>::surplus_account();
+		T::DusdMinter::mint(pallet_collateral_engine::Pallet::<T>::issuer(), &surplus, 100u32.into())
+			.unwrap();
+		pallet_collateral_engine::TotalDusdDebt::<T>::put(BalanceOf::<T>::from(100u32));
+		Pallet::<T>::on_surplus(DebtType::Dusd, 100u32.into());
+		Pallet::<T>::on_bad_debt(DebtType::Dusd, 100u32.into());
+		let caller: T::AccountId = whitelisted_caller();
+		#[extrinsic_call]
+		heal(RawOrigin::Signed(caller), DebtType::Dusd);
+
+		assert!(SystemBadDebt::<T>::get(DebtType::Dusd).is_zero());
+	}
+
+	#[benchmark]
+	fn start_auction() {
+		Parameters::<T>::insert(DebtType::Dusd, parameters::<T>());
+		Pallet::<T>::on_bad_debt(DebtType::Dusd, 100u32.into());
+		let caller: T::AccountId = whitelisted_caller();
+		#[extrinsic_call]
+		start_debt_auction(RawOrigin::Signed(caller), DebtType::Dusd);
+
+		assert!(Auctions::<T>::contains_key(0));
+	}
+
+	#[benchmark]
+	fn bid() {
+		Parameters::<T>::insert(DebtType::Dusd, parameters::<T>());
+		Pallet::<T>::on_bad_debt(DebtType::Dusd, 100u32.into());
+		let caller: T::AccountId = whitelisted_caller();
+		T::DusdMinter::mint(pallet_collateral_engine::Pallet::<T>::issuer(), &caller, 100u32.into())
+			.unwrap();
+		fund::<T>(&caller);
+		Pallet::<T>::start_debt_auction(RawOrigin::Signed(caller.clone()).into(), DebtType::Dusd).unwrap();
+		#[extrinsic_call]
+		bid(RawOrigin::Signed(caller.clone()), 0, 1_000u32.into());
+
+		assert_eq!(Auctions::<T>::get(0).unwrap().bidder, Some(caller));
+	}
+
+	#[benchmark]
+	fn settle() {
+		Parameters::<T>::insert(DebtType::Dusd, parameters::<T>());
+		Pallet::<T>::on_bad_debt(DebtType::Dusd, 100u32.into());
+		let caller: T::AccountId = whitelisted_caller();
+		T::DusdMinter::mint(pallet_collateral_engine::Pallet::<T>::issuer(), &caller, 100u32.into())
+			.unwrap();
+		fund::<T>(&caller);
+		pallet_collateral_engine::TotalDusdDebt::<T>::put(BalanceOf::<T>::from(100u32));
+		Pallet::<T>::start_debt_auction(RawOrigin::Signed(caller.clone()).into(), DebtType::Dusd).unwrap();
+		Pallet::<T>::bid(RawOrigin::Signed(caller.clone()).into(), 0, 1_000u32.into()).unwrap();
+		frame_system::Pallet::<T>::set_block_number(1_000u32.into());
+		#[extrinsic_call]
+		settle(RawOrigin::Signed(caller), 0);
+
+		assert!(Auctions::<T>::get(0).is_none());
+	}
+
+	#[benchmark]
+	fn set_parameters() {
+		#[extrinsic_call]
+		set_parameters(RawOrigin::Root, DebtType::Dusd, parameters::<T>());
+
+		assert_eq!(Parameters::<T>::get(DebtType::Dusd), parameters::<T>());
+	}
+
+	impl_benchmark_test_suite!(SystemLedger, crate::mock::new_test_ext(), crate::mock::Test);
+}
@@ -0,0 +1,524 @@
+//! # System Ledger Pallet
+//!
+//! Keeps the balance sheet of the ORIUM stablecoin system alongside the collateral engine.
+//!
+//! ## Overview
+//!
+//! Stability fees and liquidation penalties collected by `pallet_collateral_engine` are recorded
+//! here as **system surplus**, and debt that collateral auctions failed to cover is recorded as
+//! **system bad debt**. Both are tracked per stablecoin.
+//!
+//! - `heal` cancels surplus against bad debt by burning stablecoin held in the surplus account.
+//! - Surplus above the governance-set buffer is sold in **surplus auctions**: a fixed lot of
+//!   stablecoin goes to the highest ORM bid, and the winning ORM is burned.
+//! - Bad debt that surplus cannot cover is covered by **debt auctions**: bidders pay a fixed
+//!   amount of stablecoin and compete on how little freshly minted ORM they accept in return.
+//!
+//! Auction parameters are set per stablecoin by `AdminOrigin`. Auctions are disabled until
+//! their lot size is configured, and no new auctions start nor bids are taken once emergency
+//! shutdown has caged the collateral engine. Auctions already bid on can still be settled.
+
+// We make sure this pallet uses `no_std` for compiling to Wasm.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+// Re-export pallet items so that they can be accessed from the crate namespace.
+pub use pallet::*;
+
+// FRAME pallets require their own "mock runtimes" to be able to run unit tests. This module
+// contains a mock runtime specific for testing this pallet's functionality.
+#[cfg(test)]
+mod mock;
+
+// This module contains the unit tests for this pallet.
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod weights;
+pub use weights::*;
+
+// All pallet logic is defined in its own module and must be annotated by the `pallet` attribute.
+#[frame_support::pallet]
+pub mod pallet {
+	// Import various useful types required by all FRAME pallets.
+	use super::*;
+	use frame_support::{
+		pallet_prelude::*,
+		traits::{Currency, Imbalance, ReservableCurrency},
+	};
+	use frame_system::pallet_prelude::*;
+	use pallet_collateral_engine::{DebtType, SystemLedger};
+	use sp_runtime::traits::{SaturatedConversion, Saturating, Zero};
+
+	pub type BalanceOf<T> = <T as pallet_collateral_engine::Config>::Balance;
+
+	// The `Pallet` struct serves as a placeholder to implement traits, methods and dispatchables
+	// (`Call`s) in this pallet.
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	/// The pallet's configuration trait.
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_collateral_engine::Config {
+		/// The overarching runtime event type.
+		#[allow(deprecated)]
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		/// A type representing the weights required by the dispatchables of this pallet.
+		type WeightInfo: WeightInfo;
+		/// The origin allowed to tune auction parameters.
+		type AdminOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+	}
+
+	/// Governance-tunable parameters of the surplus and debt auctions for one stablecoin.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct LedgerParameters<Balance> {
+		/// Surplus kept in reserve and never auctioned.
+		pub surplus_buffer: Balance,
+		/// Stablecoin sold per surplus auction. Zero disables surplus auctions.
+		pub surplus_lot: Balance,
+		/// Stablecoin raised per debt auction. Zero disables debt auctions.
+		pub debt_lot: Balance,
+		/// ORM offered when a debt auction starts.
+		pub debt_auction_initial_orm: Balance,
+		/// Minimum improvement of each bid over the previous one, in basis points.
+		pub min_bid_increment: u32,
+		/// Blocks after the last bid at which an auction can be settled.
+		pub bid_duration: u32,
+		/// Blocks after the start at which an auction can be settled.
+		pub auction_duration: u32,
+	}
+
+	#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub enum AuctionKind {
+		/// Sells surplus stablecoin for ORM, which is burned.
+		Surplus,
+		/// Sells newly minted ORM for stablecoin, which covers bad debt.
+		Debt,
+	}
+
+	pub type AuctionId = u32;
+
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct SystemAuction<AccountId, Balance> {
+		pub kind: AuctionKind,
+		pub currency: DebtType,
+		/// Stablecoin sold in a surplus auction or raised in a debt auction.
+		pub stablecoin: Balance,
+		/// The ORM bid in a surplus auction, or the ORM offered in a debt auction.
+		pub orm: Balance,
+		pub bidder: Option<AccountId>,
+		/// The block after which the current bid wins.
+		pub bid_expiry: u32,
+		/// The block after which the auction can be settled regardless of bids.
+		pub end: u32,
+	}
+
+	/// Stablecoin held in the surplus account, per stablecoin.
+	#[pallet::storage]
+	pub type SystemSurplus<T: Config> = StorageMap<_, Twox64Concat, DebtType, BalanceOf<T>, ValueQuery>;
+
+	/// Debt left uncovered by collateral auctions, per stablecoin.
+	#[pallet::storage]
+	pub type SystemBadDebt<T: Config> = StorageMap<_, Twox64Concat, DebtType, BalanceOf<T>, ValueQuery>;
+
+	/// Surplus currently offered in surplus auctions.
+	#[pallet::storage]
+	pub type SurplusOnAuction<T: Config> = StorageMap<_, Twox64Concat, DebtType, BalanceOf<T>, ValueQuery>;
+
+	/// Bad debt currently being covered by debt auctions.
+	#[pallet::storage]
+	pub type DebtOnAuction<T: Config> = StorageMap<_, Twox64Concat, DebtType, BalanceOf<T>, ValueQuery>;
+
+	#[pallet::storage]
+	pub type Parameters<T: Config> =
+		StorageMap<_, Twox64Concat, DebtType, LedgerParameters<BalanceOf<T>>, ValueQuery>;
+
+	#[pallet::storage]
+	pub type Auctions<T: Config> =
+		StorageMap<_, Blake2_128Concat, AuctionId, SystemAuction<T::AccountId, BalanceOf<T>>, OptionQuery>;
+
+	#[pallet::storage]
+	pub type NextAuctionId<T> = StorageValue<_, AuctionId, ValueQuery>;
+
+	/// Events that functions in this pallet can emit.
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		SurplusRecorded {
+			currency: DebtType,
+			amount: BalanceOf<T>,
+		},
+		BadDebtRecorded {
+			currency: DebtType,
+			amount: BalanceOf<T>,
+		},
+		DebtHealed {
+			currency: DebtType,
+			amount: BalanceOf<T>,
+		},
+		ParametersUpdated {
+			currency: DebtType,
+			parameters: LedgerParameters<BalanceOf<T>>,
+		},
+		AuctionStarted {
+			id: AuctionId,
+			kind: AuctionKind,
+			currency: DebtType,
+			stablecoin: BalanceOf<T>,
+			orm: BalanceOf<T>,
+		},
+		BidPlaced {
+			id: AuctionId,
+			bidder: T::AccountId,
+			orm: BalanceOf<T>,
+		},
+		AuctionSettled {
+			id: AuctionId,
+			winner: Option<T::AccountId>,
+			orm: BalanceOf<T>,
+		},
+	}
+
+	/// Errors that can be returned by this pallet.
+	#[pallet::error]
+	pub enum Error<T> {
+		NothingToHeal,
+		SystemCaged,
+		AuctionsDisabled,
+		InsufficientSurplus,
+		InsufficientBadDebt,
+		UnhealedBadDebt,
+		UnhealedSurplus,
+		AuctionNotFound,
+		AuctionEnded,
+		AuctionNotEnded,
+		BidTooLow,
+		/// The ORM of a debt auction would leave the bidder's account below the existential
+		/// deposit, so it could not be paid out.
+		BelowMinimumBalance,
+		/// The winning ORM of a surplus auction is no longer reserved on the bidder's account.
+		BidNotReserved,
+	}
+
+	/// The pallet's dispatchable functions.
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Cancel surplus against bad debt by burning stablecoin from the surplus account.
+		#[pallet::call_index(0)]
+		#[pallet::weight(<T as Config>::WeightInfo::heal())]
+		pub fn heal(
+			origin: OriginFor<T>,
+			currency: DebtType,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+			
+			let amount = Self::free_surplus(currency).min(Self::free_bad_debt(currency));
+			ensure!(!amount.is_zero(), Error::<T>::NothingToHeal);
+			
+			pallet_collateral_engine::Pallet::<T>::burn_surplus(Self::issuer(), currency, amount)?;
+			SystemSurplus::<T>::mutate(currency, |surplus| *surplus = surplus.saturating_sub(amount));
+			SystemBadDebt::<T>::mutate(currency, |debt| *debt = debt.saturating_sub(amount));
+			
+			Self::deposit_event(Event::DebtHealed { currency, amount });
+			
+			Ok(())
+		}
+
+		/// Offer a lot of surplus stablecoin for ORM.
+		///
+		/// Requires all bad debt to be healed and enough surplus above the buffer.
+		#[pallet::call_index(1)]
+		#[pallet::weight(<T as Config>::WeightInfo::start_auction())]
+		pub fn start_surplus_auction(
+			origin: OriginFor<T>,
+			currency: DebtType,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+			
+			ensure!(!pallet_collateral_engine::Pallet::<T>::is_caged(), Error::<T>::SystemCaged);
+			
+			let params = Parameters::<T>::get(currency);
+			ensure!(!params.surplus_lot.is_zero(), Error::<T>::AuctionsDisabled);
+			ensure!(SystemBadDebt::<T>::get(currency).is_zero(), Error::<T>::UnhealedBadDebt);
+			ensure!(
+				Self::free_surplus(currency) >= params.surplus_buffer.saturating_add(params.surplus_lot),
+				Error::<T>::InsufficientSurplus
+			);
+			
+			SurplusOnAuction::<T>::mutate(currency, |lot| *lot = lot.saturating_add(params.surplus_lot));
+			Self::open_auction(AuctionKind::Surplus, currency, params.surplus_lot, Zero::zero(), &params);
+			
+			Ok(())
+		}
+
+		/// Offer newly minted ORM for stablecoin to cover a lot of bad debt.
+		///
+		/// Requires all surplus to have been used to heal bad debt first.
+		#[pallet::call_index(2)]
+		#[pallet::weight(<T as Config>::WeightInfo::start_auction())]
+		pub fn start_debt_auction(
+			origin: OriginFor<T>,
+			currency: DebtType,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+			
+			ensure!(!pallet_collateral_engine::Pallet::<T>::is_caged(), Error::<T>::SystemCaged);
+			
+			let params = Parameters::<T>::get(currency);
+			ensure!(!params.debt_lot.is_zero(), Error::<T>::AuctionsDisabled);
+			ensure!(Self::free_surplus(currency).is_zero(), Error::<T>::UnhealedSurplus);
+			ensure!(Self::free_bad_debt(currency) >= params.debt_lot, Error::<T>::InsufficientBadDebt);
+			
+			DebtOnAuction::<T>::mutate(currency, |debt| *debt = debt.saturating_add(params.debt_lot));
+			Self::open_auction(
+				AuctionKind::Debt,
+				currency,
+				params.debt_lot,
+				params.debt_auction_initial_orm,
+				&params,
+			);
+			
+			Ok(())
+		}
+
+		/// Bid on an auction.
+		///
+		/// In a surplus auction `orm` is the amount of ORM offered and must beat the previous
+		/// bid; it is reserved until the auction settles. In a debt auction `orm` is the amount
+		/// of ORM accepted in return for paying the auction's stablecoin, and must undercut the
+		/// previous bid.
+		#[pallet::call_index(3)]
+		#[pallet::weight(<T as Config>::WeightInfo::bid())]
+		pub fn bid(
+			origin: OriginFor<T>,
+			id: AuctionId,
+			orm: BalanceOf<T>,
+		) -> DispatchResult {
+			let bidder = ensure_signed(origin)?;
+			
+			ensure!(!pallet_collateral_engine::Pallet::<T>::is_caged(), Error::<T>::SystemCaged);
+			
+			let mut auction = Auctions::<T>::get(id).ok_or(Error::<T>::AuctionNotFound)?;
+			
+			let now = Self::now();
+			ensure!(!Self::has_ended(&auction, now), Error::<T>::AuctionEnded);
+			
+			let increment = Parameters::<T>::get(auction.currency).min_bid_increment;
+			let step = auction.orm.saturating_mul(increment.into()) / 10000u32.into();
+			
+			match auction.kind {
+				AuctionKind::Surplus => {
+					ensure!(
+						!orm.is_zero() && orm >= auction.orm.saturating_add(step),
+						Error::<T>::BidTooLow
+					);
+					
+					<T as pallet_collateral_engine::Config>::Currency::reserve(&bidder, orm)?;
+					if let Some(previous) = &auction.bidder {
+						<T as pallet_collateral_engine::Config>::Currency::unreserve(previous, auction.orm);
+					}
+				},
+				AuctionKind::Debt => {
+					let limit = if auction.bidder.is_some() {
+						auction.orm.saturating_sub(step)
+					} else {
+						auction.orm
+					};
+					ensure!(orm <= limit, Error::<T>::BidTooLow);
+					ensure!(Self::can_receive_orm(&bidder, orm), Error::<T>::BelowMinimumBalance);
+					
+					// The stablecoin is held in the surplus account until the auction settles
+					let surplus = pallet_collateral_engine::Pallet::<T>::surplus_account();
+					pallet_collateral_engine::Pallet::<T>::transfer_stablecoin(
+						Self::issuer(),
+						auction.currency,
+						&bidder,
+						&surplus,
+						auction.stablecoin,
+					)?;
+					if let Some(previous) = &auction.bidder {
+						pallet_collateral_engine::Pallet::<T>::transfer_stablecoin(
+							Self::issuer(),
+							auction.currency,
+							&surplus,
+							previous,
+							auction.stablecoin,
+						)?;
+					}
+				},
+			}
+			
+			let bid_duration = Parameters::<T>::get(auction.currency).bid_duration;
+			auction.bidder = Some(bidder.clone());
+			auction.orm = orm;
+			auction.bid_expiry = now.saturating_add(bid_duration);
+			
+			Auctions::<T>::insert(id, &auction);
+			
+			Self::deposit_event(Event::BidPlaced { id, bidder, orm });
+			
+			Ok(())
+		}
+
+		/// Settle an auction once its last bid has expired or its duration has passed.
+		#[pallet::call_index(4)]
+		#[pallet::weight(<T as Config>::WeightInfo::settle())]
+		pub fn settle(
+			origin: OriginFor<T>,
+			id: AuctionId,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+			
+			let auction = Auctions::<T>::get(id).ok_or(Error::<T>::AuctionNotFound)?;
+			
+			ensure!(Self::has_ended(&auction, Self::now()), Error::<T>::AuctionNotEnded);
+			
+			let currency = auction.currency;
+			match (auction.kind, &auction.bidder) {
+				(AuctionKind::Surplus, Some(winner)) => {
+					// The winner's ORM is burned in exchange for the surplus lot
+					let (_, unslashed) = <T as pallet_collateral_engine::Config>::Currency::slash_reserved(
+						winner,
+						auction.orm,
+					);
+					ensure!(unslashed.is_zero(), Error::<T>::BidNotReserved);
+					pallet_collateral_engine::Pallet::<T>::transfer_stablecoin(
+						Self::issuer(),
+						currency,
+						&pallet_collateral_engine::Pallet::<T>::surplus_account(),
+						winner,
+						auction.stablecoin,
+					)?;
+					SystemSurplus::<T>::mutate(currency, |surplus| {
+						*surplus = surplus.saturating_sub(auction.stablecoin)
+					});
+				},
+				(AuctionKind::Debt, Some(winner)) => {
+					// The winner's stablecoin covers the bad debt and new ORM is minted for them
+					pallet_collateral_engine::Pallet::<T>::burn_surplus(
+						Self::issuer(),
+						currency,
+						auction.stablecoin,
+					)?;
+					let minted = <T as pallet_collateral_engine::Config>::Currency::deposit_creating(
+						winner,
+						auction.orm,
+					);
+					ensure!(minted.peek() == auction.orm, Error::<T>::BelowMinimumBalance);
+					SystemBadDebt::<T>::mutate(currency, |debt| {
+						*debt = debt.saturating_sub(auction.stablecoin)
+					});
+				},
+				(_, None) => {},
+			}
+			
+			match auction.kind {
+				AuctionKind::Surplus => SurplusOnAuction::<T>::mutate(currency, |lot| {
+					*lot = lot.saturating_sub(auction.stablecoin)
+				}),
+				AuctionKind::Debt => DebtOnAuction::<T>::mutate(currency, |debt| {
+					*debt = debt.saturating_sub(auction.stablecoin)
+				}),
+			}
+			Auctions::<T>::remove(id);
+			
+			Self::deposit_event(Event::AuctionSettled { id, winner: auction.bidder, orm: auction.orm });
+			
+			Ok(())
+		}
+
+		/// Update the auction parameters of a stablecoin.
+		#[pallet::call_index(5)]
+		#[pallet::weight(<T as Config>::WeightInfo::set_parameters())]
+		pub fn set_parameters(
+			origin: OriginFor<T>,
+			currency: DebtType,
+			parameters: LedgerParameters<BalanceOf<T>>,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			
+			Parameters::<T>::insert(currency, &parameters);
+			
+			Self::deposit_event(Event::ParametersUpdated { currency, parameters });
+			
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// The origin the ledger burns and moves stablecoin with. The ledger settles the surplus
+		/// and bad debt of the collateral engine, which it is configured as the `Ledger` of, so
+		/// it acts as the engine.
+		fn issuer() -> pallet_collateral_engine::Origin<T> {
+			pallet_collateral_engine::Pallet::<T>::issuer()
+		}
+
+		/// Surplus that is neither in reserve for an auction nor already spoken for.
+		pub fn free_surplus(currency: DebtType) -> BalanceOf<T> {
+			SystemSurplus::<T>::get(currency).saturating_sub(SurplusOnAuction::<T>::get(currency))
+		}
+
+		/// Bad debt that no debt auction is covering yet.
+		pub fn free_bad_debt(currency: DebtType) -> BalanceOf<T> {
+			SystemBadDebt::<T>::get(currency).saturating_sub(DebtOnAuction::<T>::get(currency))
+		}
+
+		fn open_auction(
+			kind: AuctionKind,
+			currency: DebtType,
+			stablecoin: BalanceOf<T>,
+			orm: BalanceOf<T>,
+			params: &LedgerParameters<BalanceOf<T>>,
+		) {
+			let id = NextAuctionId::<T>::get();
+			let end = Self::now().saturating_add(params.auction_duration);
+			
+			Auctions::<T>::insert(
+				id,
+				SystemAuction { kind, currency, stablecoin, orm, bidder: None, bid_expiry: end, end },
+			);
+			NextAuctionId::<T>::put(id.saturating_add(1));
+			
+			Self::deposit_event(Event::AuctionStarted { id, kind, currency, stablecoin, orm });
+		}
+
+		/// Whether `orm` can be credited to `who`: an account that does not exist yet is only
+		/// created for at least the existential deposit.
+		fn can_receive_orm(who: &T::AccountId, orm: BalanceOf<T>) -> bool {
+			orm.is_zero() ||
+				orm >= <T as pallet_collateral_engine::Config>::Currency::minimum_balance() ||
+				!<T as pallet_collateral_engine::Config>::Currency::total_balance(who).is_zero()
+		}
+
+		fn has_ended(auction: &SystemAuction<T::AccountId, BalanceOf<T>>, now: u32) -> bool {
+			now > auction.end || (auction.bidder.is_some() && now > auction.bid_expiry)
+		}
+
+		fn now() -> u32 {
+			frame_system::Pallet::<T>::block_number().saturated_into()
+		}
+	}
+
+	impl<T: Config> SystemLedger<BalanceOf<T>> for Pallet<T> {
+		fn on_surplus(currency: DebtType, amount: BalanceOf<T>) {
+			if amount.is_zero() {
+				return;
+			}
+			
+			SystemSurplus::<T>::mutate(currency, |surplus| *surplus = surplus.saturating_add(amount));
+			
+			Self::deposit_event(Event::SurplusRecorded { currency, amount });
+		}
+
+		fn on_bad_debt(currency: DebtType, amount: BalanceOf<T>) {
+			if amount.is_zero() {
+				return;
+			}
+			
+			SystemBadDebt::<T>::mutate(currency, |debt| *debt = debt.saturating_add(amount));
+			
+			Self::deposit_event(Event::BadDebtRecorded { currency, amount });
+		}
+	}
+}
@@ -0,0 +1,390 @@
+use crate::{mock::*, AuctionKind, Error, Event, LedgerParameters, SystemBadDebt, SystemSurplus};
+use frame_support::{assert_noop, assert_ok, instances::Instance1, traits::Currency};
+use pallet_collateral_engine::{CagedAt, Cdps, DebtType, NextCdpId, ReserveBackedDebt, TotalDusdDebt};
+use pallet_oracle::AssetPair;
+use sp_runtime::traits::BadOrigin;
+
+/// Prices are quoted with 18 decimals.
+const PRICE_ONE: u128 = 1_000_000_000_000_000_000;
+
+/// Opens the next oracle round for both pairs, which the single feeder settles on its own, and
+/// lets the new prices through the oracle security module.
+fn set_prices(usd: u128, eur: u128) {
+	for (pair, price) in [(AssetPair::OrmUsd, usd), (AssetPair::OrmEur, eur)] {
+		let round = pallet_oracle::Rounds::<Test>::get(pair).id + 1;
+		assert_ok!(Oracle::submit_price(RuntimeOrigin::signed(FEEDER), pair, round, price));
+	}
+	for _ in 0..2 {
+		Timestamp::set_timestamp(Timestamp::get() + DELAY);
+		for pair in [AssetPair::OrmUsd, AssetPair::OrmEur] {
+			let _ = Oracle::poke(RuntimeOrigin::signed(FEEDER), pair);
+		}
+	}
+}
+
+fn open_cdp(who: u64, collateral: u128, dusd: u128) {
+	let _ = Balances::deposit_creating(&who, collateral);
+	let cdp_id = NextCdpId::<Test>::get();
+	assert_ok!(CollateralEngine::create_cdp(RuntimeOrigin::signed(who), NATIVE, collateral));
+	assert_ok!(CollateralEngine::mint_dusd(RuntimeOrigin::signed(who), cdp_id, dusd));
+}
+
+/// Accounts 1 and 2 hold 15_000 ORM / 6_000 dUSD CDPs 0 and 1 that become unsafe at $0.50 and
+/// go to auctions 0 and 1. Account 4 holds a safe 40_000 ORM / 12_000 dUSD CDP and buys from both.
+fn setup_auctions() {
+	set_prices(PRICE_ONE, PRICE_ONE * 8 / 10);
+	open_cdp(1, 15_000, 6_000);
+	open_cdp(2, 15_000, 6_000);
+	open_cdp(4, 40_000, 12_000);
+	
+	set_prices(PRICE_ONE / 2, PRICE_ONE * 8 / 10);
+	assert_ok!(CollateralEngine::start_auction(RuntimeOrigin::signed(3), 0));
+	assert_ok!(CollateralEngine::start_auction(RuntimeOrigin::signed(3), 1));
+}
+
+/// Auction 0 is bought out at its $0.60 starting price, paying the 780 dUSD penalty in full.
+fn collect_surplus() {
+	assert_ok!(CollateralEngine::take(RuntimeOrigin::signed(4), 0, 15_000, PRICE_ONE));
+}
+
+/// Auction 1 runs out of collateral at $0.30, leaving 1_500 dUSD of its debt uncovered.
+fn record_bad_debt() {
+	System::set_block_number(51);
+	assert_ok!(CollateralEngine::take(RuntimeOrigin::signed(4), 1, 15_000, PRICE_ONE));
+}
+
+fn set_parameters() {
+	assert_ok!(SystemLedger::set_parameters(
+		RuntimeOrigin::root(),
+		DebtType::Dusd,
+		LedgerParameters {
+			surplus_buffer: 200,
+			surplus_lot: 500,
+			debt_lot: 500,
+			debt_auction_initial_orm: 2_000,
+			min_bid_increment: 500, // 5%
+			bid_duration: 10,
+			auction_duration: 100,
+		},
+	));
+}
+
+/// The books balance when every dUSD in circulation is backed by CDP debt, debt still being
+/// worked off in collateral auctions or recorded bad debt, and recorded surplus matches what
+/// the surplus account actually holds.
+fn assert_books_balance() {
+	let cdp_debt: u128 = Cdps::<Test>::iter_values()
+		.map(|cdp| CollateralEngine::cdp_debt(&cdp).0)
+		.sum();
+	let auction_debt: u128 = pallet_collateral_engine::Auctions::<Test>::iter_values()
+		.filter(|auction| auction.currency == DebtType::Dusd)
+		.map(|auction| auction.debt)
+		.sum();
+	let supply = pallet_stablecoin::TotalSupply::<Test, Instance1>::get();
+	
+	assert_eq!(supply, TotalDusdDebt::<Test>::get());
+	assert_eq!(supply, cdp_debt + auction_debt + SystemBadDebt::<Test>::get(DebtType::Dusd));
+	assert_eq!(
+		SystemSurplus::<Test>::get(DebtType::Dusd),
+		pallet_stablecoin::Balances::<Test, Instance1>::get(CollateralEngine::surplus_account())
+	);
+}
+
+/// While a debt auction is running its current bid is held in the surplus account without
+/// being recorded as surplus.
+fn assert_books_balance_with_escrow(escrow: u128) {
+	let surplus = pallet_stablecoin::Balances::<Test, Instance1>::get(CollateralEngine::surplus_account());
+	assert_eq!(surplus, SystemSurplus::<Test>::get(DebtType::Dusd) + escrow);
+	assert_eq!(pallet_stablecoin::TotalSupply::<Test, Instance1>::get(), TotalDusdDebt::<Test>::get());
+}
+
+#[test]
+fn liquidation_penalty_is_recorded_as_surplus() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		setup_auctions();
+		assert_books_balance();
+		
+		collect_surplus();
+		
+		assert_eq!(SystemSurplus::<Test>::get(DebtType::Dusd), 780);
+		assert_eq!(SystemBadDebt::<Test>::get(DebtType::Dusd), 0);
+		System::assert_has_event(Event::SurplusRecorded { currency: DebtType::Dusd, amount: 780 }.into());
+		assert_books_balance();
+	});
+}
+
+#[test]
+fn auction_shortfall_is_recorded_as_bad_debt() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		setup_auctions();
+		
+		record_bad_debt();
+		
+		assert_eq!(SystemBadDebt::<Test>::get(DebtType::Dusd), 1_500);
+		assert_eq!(SystemSurplus::<Test>::get(DebtType::Dusd), 0);
+		System::assert_has_event(Event::BadDebtRecorded { currency: DebtType::Dusd, amount: 1_500 }.into());
+		assert_books_balance();
+	});
+}
+
+#[test]
+fn heal_cancels_surplus_against_bad_debt() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		setup_auctions();
+		
+		assert_noop!(
+			SystemLedger::heal(RuntimeOrigin::signed(3), DebtType::Dusd),
+			Error::<Test>::NothingToHeal
+		);
+		
+		collect_surplus();
+		record_bad_debt();
+		
+		let supply = pallet_stablecoin::TotalSupply::<Test, Instance1>::get();
+		assert_ok!(SystemLedger::heal(RuntimeOrigin::signed(3), DebtType::Dusd));
+		
+		assert_eq!(SystemSurplus::<Test>::get(DebtType::Dusd), 0);
+		assert_eq!(SystemBadDebt::<Test>::get(DebtType::Dusd), 720);
+		assert_eq!(pallet_stablecoin::TotalSupply::<Test, Instance1>::get(), supply - 780);
+		System::assert_last_event(Event::DebtHealed { currency: DebtType::Dusd, amount: 780 }.into());
+		assert_books_balance();
+	});
+}
+
+#[test]
+fn heal_keeps_reserve_backed_debt_in_step() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		setup_auctions();
+		collect_surplus();
+		record_bad_debt();
+		
+		// Half of the 27_000 dUSD is now issued against reserves, 1_220 of it as swap fees
+		let issuer = CollateralEngine::issuer();
+		assert_ok!(CollateralEngine::mint_reserve_backed(issuer, DebtType::Dusd, &5, 12_280, 1_220));
+		assert_eq!(TotalDusdDebt::<Test>::get(), 27_000);
+		
+		// So is half of the 1_500 dUSD of surplus burned against the bad debt
+		assert_ok!(SystemLedger::heal(RuntimeOrigin::signed(3), DebtType::Dusd));
+		
+		assert_eq!(TotalDusdDebt::<Test>::get(), 25_500);
+		assert_eq!(ReserveBackedDebt::<Test>::get(DebtType::Dusd), 12_750);
+		assert_eq!(CollateralEngine::collateral_backed_debt(DebtType::Dusd), 12_750);
+	});
+}
+
+#[test]
+fn set_parameters_requires_admin_origin() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			SystemLedger::set_parameters(
+				RuntimeOrigin::signed(1),
+				DebtType::Dusd,
+				LedgerParameters::default()
+			),
+			BadOrigin
+		);
+	});
+}
+
+#[test]
+fn auctions_are_disabled_until_configured() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		setup_auctions();
+		collect_surplus();
+		
+		assert_noop!(
+			SystemLedger::start_surplus_auction(RuntimeOrigin::signed(3), DebtType::Dusd),
+			Error::<Test>::AuctionsDisabled
+		);
+		assert_noop!(
+			SystemLedger::start_debt_auction(RuntimeOrigin::signed(3), DebtType::Dusd),
+			Error::<Test>::AuctionsDisabled
+		);
+	});
+}
+
+#[test]
+fn surplus_auction_sells_surplus_for_orm() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		setup_auctions();
+		collect_surplus();
+		set_parameters();
+		let _ = Balances::deposit_creating(&3, 1_000);
+		let _ = Balances::deposit_creating(&5, 1_000);
+		
+		assert_ok!(SystemLedger::start_surplus_auction(RuntimeOrigin::signed(3), DebtType::Dusd));
+		System::assert_last_event(
+			Event::AuctionStarted {
+				id: 0,
+				kind: AuctionKind::Surplus,
+				currency: DebtType::Dusd,
+				stablecoin: 500,
+				orm: 0,
+			}
+			.into(),
+		);
+		
+		// Only 280 dUSD is left above the auctioned lot, less than the buffer plus another lot
+		assert_noop!(
+			SystemLedger::start_surplus_auction(RuntimeOrigin::signed(3), DebtType::Dusd),
+			Error::<Test>::InsufficientSurplus
+		);
+		
+		assert_ok!(SystemLedger::bid(RuntimeOrigin::signed(3), 0, 100));
+		assert_eq!(Balances::reserved_balance(3), 100);
+		assert_noop!(SystemLedger::bid(RuntimeOrigin::signed(5), 0, 104), Error::<Test>::BidTooLow);
+		assert_ok!(SystemLedger::bid(RuntimeOrigin::signed(5), 0, 200));
+		assert_eq!(Balances::reserved_balance(3), 0);
+		assert_eq!(Balances::reserved_balance(5), 200);
+		
+		assert_noop!(SystemLedger::settle(RuntimeOrigin::signed(3), 0), Error::<Test>::AuctionNotEnded);
+		
+		let orm_issuance = Balances::total_issuance();
+		System::set_block_number(12);
+		assert_ok!(SystemLedger::settle(RuntimeOrigin::signed(3), 0));
+		
+		// The winning ORM is burned and the lot leaves the surplus account
+		assert_eq!(Balances::total_balance(&5), 800);
+		assert_eq!(Balances::total_issuance(), orm_issuance - 200);
+		assert_eq!(pallet_stablecoin::Balances::<Test, Instance1>::get(5), 500);
+		assert_eq!(SystemSurplus::<Test>::get(DebtType::Dusd), 280);
+		System::assert_last_event(Event::AuctionSettled { id: 0, winner: Some(5), orm: 200 }.into());
+		assert_books_balance();
+	});
+}
+
+#[test]
+fn surplus_auction_requires_healed_bad_debt() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		setup_auctions();
+		collect_surplus();
+		record_bad_debt();
+		set_parameters();
+		
+		assert_noop!(
+			SystemLedger::start_surplus_auction(RuntimeOrigin::signed(3), DebtType::Dusd),
+			Error::<Test>::UnhealedBadDebt
+		);
+		assert_noop!(
+			SystemLedger::start_debt_auction(RuntimeOrigin::signed(3), DebtType::Dusd),
+			Error::<Test>::UnhealedSurplus
+		);
+	});
+}
+
+#[test]
+fn debt_auction_covers_bad_debt_with_new_orm() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		setup_auctions();
+		collect_surplus();
+		record_bad_debt();
+		set_parameters();
+		assert_ok!(SystemLedger::heal(RuntimeOrigin::signed(3), DebtType::Dusd));
+		
+		assert_ok!(SystemLedger::start_debt_auction(RuntimeOrigin::signed(3), DebtType::Dusd));
+		
+		// The first bidder pays 500 dUSD for at most the initial 2_000 ORM
+		assert_noop!(SystemLedger::bid(RuntimeOrigin::signed(1), 0, 2_001), Error::<Test>::BidTooLow);
+		assert_ok!(SystemLedger::bid(RuntimeOrigin::signed(1), 0, 2_000));
+		assert_eq!(pallet_stablecoin::Balances::<Test, Instance1>::get(1), 5_500);
+		assert_books_balance_with_escrow(500);
+		
+		// Later bidders must accept at least 5% less ORM and refund the previous bidder
+		assert_noop!(SystemLedger::bid(RuntimeOrigin::signed(2), 0, 1_950), Error::<Test>::BidTooLow);
+		assert_ok!(SystemLedger::bid(RuntimeOrigin::signed(2), 0, 1_800));
+		assert_eq!(pallet_stablecoin::Balances::<Test, Instance1>::get(1), 6_000);
+		assert_eq!(pallet_stablecoin::Balances::<Test, Instance1>::get(2), 5_500);
+		
+		let orm_balance = Balances::free_balance(2);
+		System::set_block_number(62);
+		assert_ok!(SystemLedger::settle(RuntimeOrigin::signed(3), 0));
+		
+		assert_eq!(Balances::free_balance(2), orm_balance + 1_800);
+		assert_eq!(SystemBadDebt::<Test>::get(DebtType::Dusd), 220);
+		assert_noop!(SystemLedger::bid(RuntimeOrigin::signed(2), 0, 1_000), Error::<Test>::AuctionNotFound);
+		assert_books_balance();
+	});
+}
+
+#[test]
+fn debt_auction_orm_must_reach_the_existential_deposit() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		setup_auctions();
+		collect_surplus();
+		record_bad_debt();
+		set_parameters();
+		assert_ok!(SystemLedger::heal(RuntimeOrigin::signed(3), DebtType::Dusd));
+		assert_ok!(SystemLedger::start_debt_auction(RuntimeOrigin::signed(3), DebtType::Dusd));
+		ExistentialDeposit::set(2_500);
+		
+		// Account 6 holds dUSD but no ORM, so 2_000 ORM would not create its account
+		assert_ok!(Dusd::transfer(RuntimeOrigin::signed(1), 6, 1_000));
+		assert_noop!(SystemLedger::bid(RuntimeOrigin::signed(6), 0, 2_000), Error::<Test>::BelowMinimumBalance);
+		
+		let _ = Balances::deposit_creating(&6, 2_500);
+		assert_ok!(SystemLedger::bid(RuntimeOrigin::signed(6), 0, 2_000));
+		
+		// The winner's account is reaped before the auction settles
+		let _ = Balances::make_free_balance_be(&6, 0);
+		System::set_block_number(12);
+		assert_noop!(SystemLedger::settle(RuntimeOrigin::signed(3), 0), Error::<Test>::BelowMinimumBalance);
+		
+		let _ = Balances::deposit_creating(&6, 2_500);
+		assert_ok!(SystemLedger::settle(RuntimeOrigin::signed(3), 0));
+		assert_eq!(Balances::free_balance(6), 4_500);
+		assert_books_balance();
+	});
+}
+
+#[test]
+fn surplus_auction_settles_only_reserved_bids() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		setup_auctions();
+		collect_surplus();
+		set_parameters();
+		let _ = Balances::deposit_creating(&5, 1_000);
+		assert_ok!(SystemLedger::start_surplus_auction(RuntimeOrigin::signed(3), DebtType::Dusd));
+		assert_ok!(SystemLedger::bid(RuntimeOrigin::signed(5), 0, 200));
+		
+		Balances::unreserve(&5, 50);
+		System::set_block_number(12);
+		
+		assert_noop!(SystemLedger::settle(RuntimeOrigin::signed(3), 0), Error::<Test>::BidNotReserved);
+	});
+}
+
+#[test]
+fn no_bids_are_taken_after_shutdown() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		setup_auctions();
+		collect_surplus();
+		set_parameters();
+		let _ = Balances::deposit_creating(&5, 1_000);
+		assert_ok!(SystemLedger::start_surplus_auction(RuntimeOrigin::signed(3), DebtType::Dusd));
+		assert_ok!(SystemLedger::bid(RuntimeOrigin::signed(5), 0, 200));
+		
+		CagedAt::<Test>::put(1);
+		
+		assert_noop!(SystemLedger::bid(RuntimeOrigin::signed(5), 0, 300), Error::<Test>::SystemCaged);
+		assert_noop!(
+			SystemLedger::start_surplus_auction(RuntimeOrigin::signed(3), DebtType::Dusd),
+			Error::<Test>::SystemCaged
+		);
+		
+		// The running auction can still be settled with the bid it already has
+		System::set_block_number(12);
+		assert_ok!(SystemLedger::settle(RuntimeOrigin::signed(3), 0));
+		assert_eq!(pallet_stablecoin::Balances::<Test, Instance1>::get(5), 500);
+	});
+}
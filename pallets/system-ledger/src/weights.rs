@@ -0,0 +1,87 @@
+
+//! Weights for pallet_system_ledger
+//!
+//! Estimated from the template benchmarks until the pallet is benchmarked on reference hardware.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_system_ledger.
+pub trait WeightInfo {
+	fn heal() -> Weight;
+	fn start_auction() -> Weight;
+	fn bid() -> Weight;
+	fn settle() -> Weight;
+	fn set_parameters() -> Weight;
+}
+
+/// Weights for pallet_system_ledger using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: System ledger SystemSurplus, SystemBadDebt, SurplusOnAuction, DebtOnAuction, CollateralEngine TotalDusdDebt, ReserveBackedDebt (r:6 w:4)
+	fn heal() -> Weight {
+		Weight::from_parts(21_000_000, 3_593)
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+	/// Storage: System ledger Parameters, SystemSurplus, SystemBadDebt, SurplusOnAuction, DebtOnAuction, NextAuctionId (r:5 w:3)
+	fn start_auction() -> Weight {
+		Weight::from_parts(16_000_000, 3_541)
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: CollateralEngine CagedAt, System ledger Auctions, Parameters, Balances Account (r:5 w:3)
+	fn bid() -> Weight {
+		Weight::from_parts(30_000_000, 3_593)
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: System ledger Auctions, SystemSurplus, SystemBadDebt, Balances Account, CollateralEngine TotalDusdDebt, ReserveBackedDebt (r:6 w:6)
+	fn settle() -> Weight {
+		Weight::from_parts(35_000_000, 3_593)
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+	}
+	/// Storage: System ledger Parameters (r:0 w:1)
+	fn set_parameters() -> Weight {
+		Weight::from_parts(9_000_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	/// Storage: System ledger SystemSurplus, SystemBadDebt, SurplusOnAuction, DebtOnAuction, CollateralEngine TotalDusdDebt, ReserveBackedDebt (r:6 w:4)
+	fn heal() -> Weight {
+		Weight::from_parts(21_000_000, 3_593)
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+	/// Storage: System ledger Parameters, SystemSurplus, SystemBadDebt, SurplusOnAuction, DebtOnAuction, NextAuctionId (r:5 w:3)
+	fn start_auction() -> Weight {
+		Weight::from_parts(16_000_000, 3_541)
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	/// Storage: CollateralEngine CagedAt, System ledger Auctions, Parameters, Balances Account (r:5 w:3)
+	fn bid() -> Weight {
+		Weight::from_parts(30_000_000, 3_593)
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	/// Storage: System ledger Auctions, SystemSurplus, SystemBadDebt, Balances Account, CollateralEngine TotalDusdDebt, ReserveBackedDebt (r:6 w:6)
+	fn settle() -> Weight {
+		Weight::from_parts(35_000_000, 3_593)
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
+	}
+	/// Storage: System ledger Parameters (r:0 w:1)
+	fn set_parameters() -> Weight {
+		Weight::from_parts(9_000_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+}
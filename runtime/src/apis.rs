@@ -36,14 +36,15 @@ use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
 	traits::{Block as BlockT, NumberFor},
 	transaction_validity::{TransactionSource, TransactionValidity},
-	ApplyExtrinsicResult,
+	ApplyExtrinsicResult, DispatchError,
 };
 use sp_version::RuntimeVersion;
 
 // Local module imports
 use super::{
-	AccountId, Aura, Balance, Block, Executive, Grandpa, InherentDataExt, Nonce, Runtime,
-	RuntimeCall, RuntimeGenesisConfig, SessionKeys, System, TransactionPayment, VERSION,
+	AccountId, Aura, Balance, Block, CollateralEngine, Executive, Grandpa, InherentDataExt, Nonce,
+	Oracle, Runtime, RuntimeCall, RuntimeGenesisConfig, Savings, SessionKeys, System, TransactionPayment,
+	VERSION,
 };
 
 impl_runtime_apis! {
@@ -220,6 +221,44 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_collateral_engine_runtime_api::CollateralEngineApi<Block, AccountId, Balance> for Runtime {
+		fn cdp(id: pallet_collateral_engine::CdpId) -> Option<pallet_collateral_engine::CdpInfo<AccountId, Balance>> {
+			CollateralEngine::cdp_info(id)
+		}
+		fn cdps_of(owner: AccountId) -> Vec<pallet_collateral_engine::CdpId> {
+			CollateralEngine::cdps_of(&owner)
+		}
+		fn health(
+			id: pallet_collateral_engine::CdpId,
+		) -> Result<pallet_collateral_engine::CdpHealth<Balance>, DispatchError> {
+			CollateralEngine::cdp_health(id)
+		}
+		fn liquidatable_cdps(
+			start: pallet_collateral_engine::CdpId,
+			limit: u32,
+		) -> Vec<pallet_collateral_engine::LiquidatableCdp> {
+			CollateralEngine::liquidatable_cdps(start, limit)
+		}
+		fn next_cdp_id() -> pallet_collateral_engine::CdpId {
+			CollateralEngine::next_cdp_id()
+		}
+	}
+
+	impl pallet_savings_runtime_api::SavingsApi<Block, AccountId, Balance> for Runtime {
+		fn savings_of(who: AccountId) -> pallet_savings::AccountSavings<Balance> {
+			Savings::savings_of(&who)
+		}
+	}
+
+	impl pallet_oracle_runtime_api::OracleApi<Block> for Runtime {
+		fn twap(pair: pallet_oracle::AssetPair, window: u64) -> Option<u128> {
+			Oracle::twap(pair, window)
+		}
+		fn cumulative_price(pair: pallet_oracle::AssetPair) -> Option<(u128, u64)> {
+			Oracle::cumulative_price(pair)
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn benchmark_metadata(extra: bool) -> (
@@ -25,24 +25,42 @@
 
 // Substrate and Polkadot dependencies
 use frame_support::{
-	derive_impl, parameter_types,
-	traits::{ConstBool, ConstU128, ConstU32, ConstU64, ConstU8, VariantCountOf},
+	derive_impl,
+	instances::{Instance1, Instance2},
+	parameter_types,
+	traits::{
+		AsEnsureOriginWithArg, ConstBool, ConstU128, ConstU32, ConstU64, ConstU8, EitherOfDiverse, VariantCountOf,
+	},
 	weights::{
 		constants::{RocksDbWeight, WEIGHT_REF_TIME_PER_SECOND},
 		IdentityFee, Weight,
 	},
+	PalletId,
+};
+use frame_system::{
+	limits::{BlockLength, BlockWeights},
+	EnsureRoot, EnsureSigned,
+};
+use pallet_collateral_engine::{
+	CollateralAsset, CollateralType, DebtType, EnsureIssuer, FlashMintFeeToSurplus, PriceFeed,
 };
-use frame_system::limits::{BlockLength, BlockWeights};
 use pallet_transaction_payment::{ConstFeeMultiplier, FungibleAdapter, Multiplier};
 use sp_consensus_babe::AuthorityId as BabeId;
-use sp_runtime::{traits::One, Perbill};
+use codec::Encode;
+use sp_runtime::{
+	generic::Era,
+	traits::{One, SaturatedConversion, Verify},
+	transaction_validity::TransactionPriority,
+	Perbill,
+};
 use sp_version::RuntimeVersion;
 
 // Local module imports
 use super::{
-	AccountId, Babe, Balance, Balances, Block, BlockNumber, Hash, Nonce, PalletInfo, Runtime,
-	RuntimeCall, RuntimeEvent, RuntimeFreezeReason, RuntimeHoldReason, RuntimeOrigin, RuntimeTask,
-	System, EXISTENTIAL_DEPOSIT, SLOT_DURATION, VERSION, tps_config,
+	AccountId, Address, Assets, Babe, Balance, Balances, Block, BlockNumber, Deur, Dusd, Hash, Nonce, Oracle, OriumToken,
+	PalletInfo, Runtime, RuntimeCall, RuntimeEvent, RuntimeFreezeReason, RuntimeHoldReason, RuntimeOrigin,
+	RuntimeTask, Signature, SignedPayload, System, SystemLedger, Timestamp, TxExtension, UncheckedExtrinsic,
+	DAYS, EXISTENTIAL_DEPOSIT, HOURS, MILLI_UNIT, MINUTES, SLOT_DURATION, UNIT, VERSION, tps_config,
 };
 
 const NORMAL_DISPATCH_RATIO: Perbill = Perbill::from_percent(75);
@@ -175,27 +193,246 @@ impl pallet_orium_token::Config for Runtime {
 	type Balance = Balance;
 }
 
+parameter_types! {
+	pub const CollateralEnginePalletId: PalletId = PalletId(*b"orm/cdpe");
+	pub const SavingsPalletId: PalletId = PalletId(*b"orm/save");
+	pub const PsmPalletId: PalletId = PalletId(*b"orm/psm_");
+	/// Native ORM at 150% / 130% with a 5% stability fee. The CDPs opened before collateral
+	/// types existed are migrated under it.
+	pub NativeCollateralType: CollateralType<Balance> = CollateralType {
+		asset: CollateralAsset::Native,
+		price_feed: PriceFeed::Orm,
+		min_collateral_ratio: 15000,
+		liquidation_ratio: 13000,
+		stability_fee: 500,
+		debt_ceiling: 10_000_000 * UNIT,
+		dust: 0,
+	};
+}
+
 /// Configure the collateral engine pallet.
 impl pallet_collateral_engine::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type WeightInfo = pallet_collateral_engine::weights::SubstrateWeight<Runtime>;
 	type Balance = Balance;
 	type Currency = Balances;
-	type MinCollateralRatio = ConstU32<15000>; // 150%
-	type LiquidationRatio = ConstU32<13000>; // 130%
-	type StabilityFee = ConstU32<500>; // 5%
+	type OriumToken = OriumToken;
+	type Oracle = Oracle;
+	type UnixTime = Timestamp;
+	type MaxPriceAge = ConstU64<{ 2 * 60 * 60 * 1000 }>; // 2 hours
+	type BlocksPerYear = ConstU32<{ 365 * DAYS }>;
+	type PalletId = CollateralEnginePalletId;
+	type AuctionCurve = pallet_collateral_engine::StairstepExponentialDecrease<
+		ConstU32<{ 3 * MINUTES }>,
+		ConstU32<9900>, // 1% every 3 minutes
+	>;
+	type AuctionStartBuffer = ConstU32<12000>; // 120%
+	type AuctionResetDuration = ConstU32<{ 2 * HOURS }>;
+	type AuctionResetRatio = ConstU32<4000>; // 40%
+	type LiquidationPenalty = ConstU32<1300>; // 13%
+	type CloseFactor = ConstU32<5000>; // 50%
+	type DusdMinter = Dusd;
+	type DeurMinter = Deur;
+	type ShutdownOrigin = EnsureRoot<AccountId>;
+	type AdminOrigin = EnsureRoot<AccountId>;
+	type MaxCollateralTypes = ConstU32<16>;
+	type Ledger = SystemLedger;
+	type LiquidationScanBatch = ConstU32<50>;
+	type MaxAutoLiquidations = ConstU32<10>;
+	type UnsignedPriority = ConstU64<{ TransactionPriority::MAX / 2 }>;
+}
+
+/// Configure the price oracle pallet.
+impl pallet_oracle::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = pallet_oracle::weights::SubstrateWeight<Runtime>;
+	type AdminOrigin = EnsureRoot<AccountId>;
+	type MaxFeeders = ConstU32<32>;
+	type RoundTimeout = ConstU32<{ 10 * MINUTES }>;
+	type UnixTime = Timestamp;
+	type SecurityDelay = ConstU64<{ 60 * 60 * 1000 }>; // 1 hour
+	type AuthorityId = pallet_oracle::crypto::OracleAuthId;
+	type FetchInterval = ConstU32<{ MINUTES }>;
+	type MaxSources = ConstU32<8>;
+	type MaxSourceLength = ConstU32<256>;
+	type MaxObservations = ConstU32<120>; // 2 hours of prices fetched every minute
+	type OnPriceUpdate = CollateralEngine;
+}
+
+impl frame_system::offchain::SigningTypes for Runtime {
+	type Public = <Signature as Verify>::Signer;
+	type Signature = Signature;
+}
+
+impl<LocalCall> frame_system::offchain::CreateTransactionBase<LocalCall> for Runtime
+where
+	RuntimeCall: From<LocalCall>,
+{
+	type RuntimeCall = RuntimeCall;
+	type Extrinsic = UncheckedExtrinsic;
+}
+
+/// Lets offchain workers, such as the oracle's price feed, sign and submit transactions with
+/// keys from the node keystore.
+impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for Runtime
+where
+	RuntimeCall: From<LocalCall>,
+{
+	fn create_signed_transaction<C: frame_system::offchain::AppCrypto<Self::Public, Self::Signature>>(
+		call: RuntimeCall,
+		public: <Signature as Verify>::Signer,
+		account: AccountId,
+		nonce: Nonce,
+	) -> Option<UncheckedExtrinsic> {
+		// Valid for as long as the block hash of the era start is kept
+		let period = BlockHashCount::get().checked_next_power_of_two().map(|c| c / 2).unwrap_or(2) as u64;
+		let current_block = System::block_number().saturated_into::<u64>().saturating_sub(1);
+		let tx_ext: TxExtension = (
+			frame_system::AuthorizeCall::<Runtime>::new(),
+			frame_system::CheckNonZeroSender::<Runtime>::new(),
+			frame_system::CheckSpecVersion::<Runtime>::new(),
+			frame_system::CheckTxVersion::<Runtime>::new(),
+			frame_system::CheckGenesis::<Runtime>::new(),
+			frame_system::CheckEra::<Runtime>::from(Era::mortal(period, current_block)),
+			frame_system::CheckNonce::<Runtime>::from(nonce),
+			frame_system::CheckWeight::<Runtime>::new(),
+			pallet_transaction_payment::ChargeTransactionPayment::<Runtime>::from(0),
+			frame_metadata_hash_extension::CheckMetadataHash::<Runtime>::new(false),
+			frame_system::WeightReclaim::<Runtime>::new(),
+		);
+		let raw_payload = SignedPayload::new(call, tx_ext).ok()?;
+		let signature = raw_payload.using_encoded(|payload| C::sign(payload, public))?;
+		let (call, tx_ext, _) = raw_payload.deconstruct();
+		Some(UncheckedExtrinsic::new_signed(call, Address::Id(account), signature, tx_ext))
+	}
+}
+
+/// Lets the collateral engine's offchain worker submit unsigned liquidations.
+impl<LocalCall> frame_system::offchain::CreateBare<LocalCall> for Runtime
+where
+	RuntimeCall: From<LocalCall>,
+{
+	fn create_bare(call: RuntimeCall) -> UncheckedExtrinsic {
+		UncheckedExtrinsic::new_bare(call)
+	}
+}
+
+/// Configure the system ledger pallet.
+impl pallet_system_ledger::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = pallet_system_ledger::weights::SubstrateWeight<Runtime>;
+	type AdminOrigin = EnsureRoot<AccountId>;
+}
+
+/// Configure the stablecoin savings rate pallet.
+impl pallet_savings::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = pallet_savings::weights::SubstrateWeight<Runtime>;
+	type AdminOrigin = EnsureRoot<AccountId>;
+	type PalletId = SavingsPalletId;
+	type MaxSavingsRate = ConstU32<2000>; // 20% a year
+}
+
+/// Configure the assets pallet, which holds the external stable assets swapped by the PSM.
+impl pallet_assets::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Balance = Balance;
+	type RemoveItemsLimit = ConstU32<1000>;
+	type AssetId = u32;
+	type AssetIdParameter = codec::Compact<u32>;
+	type Currency = Balances;
+	type CreateOrigin = AsEnsureOriginWithArg<EnsureSigned<AccountId>>;
+	type ForceOrigin = EnsureRoot<AccountId>;
+	type AssetDeposit = ConstU128<{ 100 * UNIT }>;
+	type AssetAccountDeposit = ConstU128<{ UNIT }>;
+	type MetadataDepositBase = ConstU128<{ 10 * UNIT }>;
+	type MetadataDepositPerByte = ConstU128<{ MILLI_UNIT }>;
+	type ApprovalDeposit = ConstU128<{ MILLI_UNIT }>;
+	type StringLimit = ConstU32<50>;
+	type Freezer = ();
+	type Extra = ();
+	type CallbackHandle = ();
+	type WeightInfo = pallet_assets::weights::SubstrateWeight<Runtime>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = ();
 }
 
-/// Configure the dUSD stablecoin pallet.
-impl pallet_dusd::Config for Runtime {
+/// Configure the peg stability module pallet.
+impl pallet_psm::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
-	type WeightInfo = pallet_dusd::weights::SubstrateWeight<Runtime>;
+	type WeightInfo = pallet_psm::weights::SubstrateWeight<Runtime>;
+	type Assets = Assets;
+	type DusdDecimals = DusdDecimals;
+	type AdminOrigin = EnsureRoot<AccountId>;
+	type PalletId = PsmPalletId;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = PsmBenchmarkHelper;
+}
+
+/// Creates the asset the peg stability module benchmarks swap dUSD against, with the decimals of
+/// dUSD.
+#[cfg(feature = "runtime-benchmarks")]
+pub struct PsmBenchmarkHelper;
+
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_psm::BenchmarkHelper<u32> for PsmBenchmarkHelper {
+	fn create_asset() -> u32 {
+		use frame_support::traits::fungibles::Create;
+		<Assets as Create<AccountId>>::create(1, AccountId::from([0u8; 32]), true, 1)
+			.expect("the benchmark asset is created once");
+		Assets::force_set_metadata(RuntimeOrigin::root(), 1.into(), b"USDX".to_vec(), b"USDX".to_vec(), 12, false)
+			.expect("the benchmark asset takes the decimals of dUSD");
+		1
+	}
+}
+
+parameter_types! {
+	pub const DusdSymbol: &'static str = "dUSD";
+	pub const DusdDecimals: u8 = 12;
+	pub const DeurSymbol: &'static str = "dEUR";
+	pub const Usd: &'static str = "USD";
+	pub const Eur: &'static str = "EUR";
+	pub const DusdDebt: DebtType = DebtType::Dusd;
+	pub const DeurDebt: DebtType = DebtType::Deur;
+	/// The most dUSD and dEUR flash minted at once, together.
+	pub const MaxFlashMint: Balance = 10_000_000 * UNIT;
+}
+
+/// The modules issuing stablecoin, each with an origin of its own: the collateral engine, the
+/// savings rate and the peg stability module.
+type StablecoinIssuers = EitherOfDiverse<
+	EnsureIssuer<Runtime, CollateralEnginePalletId>,
+	EitherOfDiverse<EnsureIssuer<Runtime, SavingsPalletId>, EnsureIssuer<Runtime, PsmPalletId>>,
+>;
+
+/// Configure the dUSD instance of the stablecoin pallet.
+impl pallet_stablecoin::Config<Instance1> for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = pallet_stablecoin::weights::SubstrateWeight<Runtime>;
 	type Balance = Balance;
+	type Symbol = DusdSymbol;
+	type Decimals = DusdDecimals;
+	type PegCurrency = Usd;
+	type RuntimeCall = RuntimeCall;
+	type MaxFlashMint = MaxFlashMint;
+	type OtherFlashMints = pallet_stablecoin::FlashMintedOf<Runtime, Instance2>;
+	type FlashMintFee = ConstU32<9>; // 0.09%
+	type OnFlashMintFee = FlashMintFeeToSurplus<Runtime, DusdDebt>;
+	type IssuerOrigin = EitherOfDiverse<EnsureRoot<AccountId>, StablecoinIssuers>;
 }
 
-/// Configure the dEUR stablecoin pallet.
-impl pallet_deur::Config for Runtime {
+/// Configure the dEUR instance of the stablecoin pallet.
+impl pallet_stablecoin::Config<Instance2> for Runtime {
 	type RuntimeEvent = RuntimeEvent;
-	type WeightInfo = pallet_deur::weights::SubstrateWeight<Runtime>;
+	type WeightInfo = pallet_stablecoin::weights::SubstrateWeight<Runtime>;
 	type Balance = Balance;
+	type Symbol = DeurSymbol;
+	type Decimals = ConstU8<12>;
+	type PegCurrency = Eur;
+	type RuntimeCall = RuntimeCall;
+	type MaxFlashMint = MaxFlashMint;
+	type OtherFlashMints = pallet_stablecoin::FlashMintedOf<Runtime, Instance1>;
+	type FlashMintFee = ConstU32<9>; // 0.09%
+	type OnFlashMintFee = FlashMintFeeToSurplus<Runtime, DeurDebt>;
+	type IssuerOrigin = EitherOfDiverse<EnsureRoot<AccountId>, StablecoinIssuers>;
 }
@@ -15,9 +15,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{AccountId, BalancesConfig, RuntimeGenesisConfig, SudoConfig};
+use crate::{
+	configs::NativeCollateralType, AccountId, BalancesConfig, CollateralEngineConfig, OracleConfig,
+	RuntimeGenesisConfig, SudoConfig,
+};
 use alloc::{vec, vec::Vec};
 use frame_support::build_struct_json_patch;
+use pallet_collateral_engine::{CollateralAsset, CollateralType};
 use serde_json::Value;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_consensus_grandpa::AuthorityId as GrandpaId;
@@ -44,10 +48,23 @@ fn testnet_genesis(
 		grandpa: pallet_grandpa::GenesisConfig {
 			authorities: initial_authorities.iter().map(|x| (x.1.clone(), 1)).collect::<Vec<_>>(),
 		},
-		sudo: SudoConfig { key: Some(root) },
+		sudo: SudoConfig { key: Some(root.clone()) },
+		collateral_engine: CollateralEngineConfig {
+			collateral_types: vec![
+				(0, orm_collateral_type(CollateralAsset::Native)),
+				(1, orm_collateral_type(CollateralAsset::OriumToken)),
+			],
+		},
+		// The sudo key feeds prices until independent feeders are added
+		oracle: OracleConfig { feeders: vec![root.clone()], min_quorum: 1 },
 	})
 }
 
+// Native and token ORM both mint at 150%, liquidate below 130% and pay a 5% stability fee.
+fn orm_collateral_type(asset: CollateralAsset) -> CollateralType<u128> {
+	CollateralType { asset, ..NativeCollateralType::get() }
+}
+
 /// Return the development genesis config.
 pub fn development_config_genesis() -> Value {
 	testnet_genesis(
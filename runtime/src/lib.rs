@@ -11,6 +11,7 @@ pub mod tps_config;
 
 extern crate alloc;
 use alloc::vec::Vec;
+use frame_support::instances::{Instance1, Instance2};
 use sp_runtime::{
 	generic, impl_opaque_keys,
 	traits::{BlakeTwo256, IdentifyAccount, Verify},
@@ -174,8 +175,11 @@ pub type SignedPayload = generic::SignedPayload<RuntimeCall, TxExtension>;
 /// All migrations of the runtime, aside from the ones declared in the pallets.
 ///
 /// This can be a tuple of types, each implementing `OnRuntimeUpgrade`.
-#[allow(unused_parens)]
-type Migrations = ();
+type Migrations = (
+	pallet_collateral_engine::migrations::v1::MigrateV0ToV1<Runtime, configs::NativeCollateralType>,
+	pallet_stablecoin::migrations::v1::MigrateV0ToV1<Runtime, Instance1>,
+	pallet_stablecoin::migrations::v1::MigrateV0ToV1<Runtime, Instance2>,
+);
 
 /// Executive: handles dispatch to the various modules.
 pub type Executive = frame_executive::Executive<
@@ -238,11 +242,31 @@ mod runtime {
 	#[runtime::pallet_index(9)]
 	pub type CollateralEngine = pallet_collateral_engine;
 
-	// Include the dUSD stablecoin pallet in the runtime.
+	// Include the dUSD instance of the stablecoin pallet in the runtime.
 	#[runtime::pallet_index(10)]
-	pub type Dusd = pallet_dusd;
+	pub type Dusd = pallet_stablecoin<Instance1>;
 
-	// Include the dEUR stablecoin pallet in the runtime.
+	// Include the dEUR instance of the stablecoin pallet in the runtime.
 	#[runtime::pallet_index(11)]
-	pub type Deur = pallet_deur;
+	pub type Deur = pallet_stablecoin<Instance2>;
+
+	// Include the system ledger pallet in the runtime.
+	#[runtime::pallet_index(12)]
+	pub type SystemLedger = pallet_system_ledger;
+
+	// Include the price oracle pallet in the runtime.
+	#[runtime::pallet_index(13)]
+	pub type Oracle = pallet_oracle;
+
+	// Include the stablecoin savings rate pallet in the runtime.
+	#[runtime::pallet_index(14)]
+	pub type Savings = pallet_savings;
+
+	// Include the assets pallet in the runtime.
+	#[runtime::pallet_index(15)]
+	pub type Assets = pallet_assets;
+
+	// Include the peg stability module pallet in the runtime.
+	#[runtime::pallet_index(16)]
+	pub type Psm = pallet_psm;
 }